@@ -0,0 +1,104 @@
+//! Clipboard text for the tray's charset converter (Windows only)
+
+use anyhow::Result;
+
+#[cfg(target_os = "windows")]
+mod win {
+    use anyhow::{bail, Result};
+    use windows_sys::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, GetClipboardData, OpenClipboard, SetClipboardData,
+    };
+    use windows_sys::Win32::System::Memory::{
+        GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock, GMEM_MOVEABLE,
+    };
+
+    /// Standard clipboard format of UTF-16 text
+    const CF_UNICODETEXT: u32 = 13;
+
+    /// Keeps the clipboard open until dropped
+    struct Open;
+
+    impl Open {
+        fn new() -> Result<Self> {
+            if unsafe { OpenClipboard(0) } == 0 {
+                bail!("the clipboard is in use by another program");
+            }
+            Ok(Open)
+        }
+    }
+
+    impl Drop for Open {
+        fn drop(&mut self) {
+            unsafe { CloseClipboard() };
+        }
+    }
+
+    pub fn read_text() -> Result<String> {
+        let _open = Open::new()?;
+        let handle = unsafe { GetClipboardData(CF_UNICODETEXT) };
+        if handle == 0 {
+            bail!("the clipboard holds no text");
+        }
+        let data = unsafe { GlobalLock(handle) } as *const u16;
+        if data.is_null() {
+            bail!("the clipboard text cannot be read");
+        }
+        let mut len = 0;
+        while unsafe { *data.add(len) } != 0 {
+            len += 1;
+        }
+        let text = String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(data, len) });
+        unsafe { GlobalUnlock(handle) };
+        Ok(text)
+    }
+
+    pub fn write_text(text: &str) -> Result<()> {
+        let mut units: Vec<u16> = text.encode_utf16().collect();
+        units.push(0);
+
+        let _open = Open::new()?;
+        let handle = unsafe { GlobalAlloc(GMEM_MOVEABLE, units.len() * 2) };
+        if handle == 0 {
+            bail!("out of memory for the clipboard text");
+        }
+        let data = unsafe { GlobalLock(handle) } as *mut u16;
+        if data.is_null() {
+            unsafe { GlobalFree(handle) };
+            bail!("out of memory for the clipboard text");
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(units.as_ptr(), data, units.len());
+            GlobalUnlock(handle);
+        }
+
+        // The clipboard owns the memory once it accepts it
+        unsafe { EmptyClipboard() };
+        if unsafe { SetClipboardData(CF_UNICODETEXT, handle) } == 0 {
+            unsafe { GlobalFree(handle) };
+            bail!("the clipboard did not accept the text");
+        }
+        Ok(())
+    }
+}
+
+/// Read the text on the clipboard
+#[cfg(target_os = "windows")]
+pub fn read_text() -> Result<String> {
+    win::read_text()
+}
+
+/// Put `text` on the clipboard, replacing what it holds
+#[cfg(target_os = "windows")]
+pub fn write_text(text: &str) -> Result<()> {
+    win::write_text(text)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn read_text() -> Result<String> {
+    anyhow::bail!("the clipboard is only supported on Windows")
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn write_text(_text: &str) -> Result<()> {
+    anyhow::bail!("the clipboard is only supported on Windows")
+}
@@ -1,11 +1,17 @@
 #![windows_subsystem = "windows"]
 
+mod clipboard;
+
 use anyhow::Result;
-use muda::{IconMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use muda::{CheckMenuItem, IconMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use std::sync::mpsc;
+use std::time::Duration;
 use tray_icon::{Icon as TrayIcon, TrayIconBuilder};
+use vikey_config::{Loader, Settings, UserFile, Watcher};
+use vikey_core::{Capabilities, Engine, Shorthand};
+use vikey_nom::NomPlugin;
+use vikey_vietnamese::detect::{decode_auto, Guess};
+use vikey_vietnamese::{encoding, Charset, OutputEncoding, VietnamesePlugin};
 use winit::{
     event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
@@ -17,60 +23,189 @@ const VIETNAMESE_ICON_BYTES: &[u8] = include_bytes!("../icons/vietnamese.png");
 const ENGLISH_ICON_BYTES: &[u8] = include_bytes!("../icons/english.png");
 const CHECK_ICON_BYTES: &[u8] = include_bytes!("../icons/check.png");
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Settings {
-    input_method: String, // "english", "telex", "vni"
-    auto_correct: bool,
-    shorthand: bool,
-    startup: bool,
+/// How often the settings files are checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Locale of the tray labels
+const UI_LOCALE: &str = "vi";
+
+/// Capabilities the text service cannot host yet (no candidate window);
+/// methods that need them are listed but cannot be selected
+const UNSUPPORTED_CAPABILITIES: Capabilities = Capabilities::CANDIDATES;
+
+/// Convert the text on the clipboard to Unicode, guessing its charset
+///
+/// Text copied from documents in legacy fonts (TCVN3, VNI Windows...)
+/// reaches the clipboard as Windows-1252 characters, which `decode_auto`
+/// reads back. Unicode text is left alone.
+fn convert_clipboard() -> Result<Guess> {
+    let text = clipboard::read_text()?;
+    let (converted, guess) = decode_auto(text.as_bytes());
+    if converted != text {
+        clipboard::write_text(&converted)?;
+    }
+    Ok(guess)
 }
 
-impl Default for Settings {
-    fn default() -> Self {
-        Self {
-            input_method: "english".to_string(),
-            auto_correct: false,
-            shorthand: false,
-            startup: false,
-        }
+/// Load the merged settings (system defaults, user file, profile)
+///
+/// Entries that cannot be used are reported and skipped.
+fn load_settings(loader: &Loader) -> Settings {
+    let loaded = loader.load();
+    for diagnostic in &loaded.diagnostics {
+        eprintln!("{}", diagnostic);
     }
+    loaded.settings
 }
 
-/// Get the settings file path
-/// 
+/// Write entries to the user settings file and reload the merged settings
+///
 /// Platform-specific paths:
-/// - Windows: %APPDATA%\vikey\settings.toml
-/// - macOS: ~/Library/Application Support/vikey/settings.toml
-/// - Linux: ~/.config/vikey/settings.toml
-fn get_settings_path() -> Result<PathBuf> {
-    let data_dir = dirs::data_dir()
-        .ok_or_else(|| anyhow::anyhow!("Could not find data directory"))?;
-    let vikey_dir = data_dir.join("vikey");
-    fs::create_dir_all(&vikey_dir)?;
-    Ok(vikey_dir.join("settings.toml"))
+/// - Windows: %APPDATA%\vikey\config.toml
+/// - macOS: ~/Library/Application Support/vikey/config.toml
+/// - Linux: ~/.config/vikey/config.toml
+fn save_settings(loader: &Loader, entries: &[(&str, toml::Value)]) -> Result<Settings> {
+    let path = loader
+        .user_file()
+        .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+    let mut file = UserFile::open(path)?;
+    for (key, value) in entries {
+        file.set(key, value.clone());
+    }
+    file.save()?;
+    Ok(load_settings(loader))
+}
+
+/// Apply the settings to the engine
+fn apply_settings(engine: &mut Engine, settings: &Settings) {
+    if let Err(errors) = engine.set_config(settings.engine.clone()) {
+        for e in errors {
+            eprintln!("Setting not applied: {}", e);
+        }
+    }
 }
 
-fn load_settings() -> Settings {
-    match get_settings_path() {
-        Ok(path) => {
-            if path.exists() {
-                if let Ok(content) = fs::read_to_string(&path) {
-                    if let Ok(settings) = toml::from_str(&content) {
-                        return settings;
+/// Is automatic spelling correction on in the engine?
+fn auto_correct_enabled(engine: &Engine) -> bool {
+    engine
+        .config()
+        .plugin_option("vietnamese", "auto_restore")
+        .and_then(|mode| mode.as_str().map(|mode| mode != "off"))
+        .unwrap_or(false)
+}
+
+/// Output charset selected in the engine settings
+fn output_charset(engine: &Engine) -> Charset {
+    engine
+        .config()
+        .stage_option(encoding::ID, "charset")
+        .and_then(|charset| charset.as_str().and_then(Charset::from_id))
+        .unwrap_or_default()
+}
+
+/// Tray item of one input method
+struct MethodItem {
+    /// Input method ID
+    id: String,
+    /// Name shown in the tooltip
+    label: String,
+    item: IconMenuItem,
+}
+
+/// Tray submenu of one language, generated from the plugin metadata
+struct LanguageMenu {
+    /// Language (plugin) ID
+    id: String,
+    /// Name shown in the menu and tooltip
+    label: String,
+    /// Tray icon shown while this language is active
+    icon: TrayIcon,
+    submenu: Submenu,
+    methods: Vec<MethodItem>,
+}
+
+/// Build one submenu per registered language, in registry order
+fn build_language_menus(engine: &Engine) -> Vec<LanguageMenu> {
+    engine
+        .catalog()
+        .iter()
+        .map(|language| {
+            let label = language.display_name(UI_LOCALE).to_string();
+            let submenu = Submenu::new(&label, true);
+            let methods: Vec<MethodItem> = language
+                .methods
+                .iter()
+                .map(|method| {
+                    let selectable = !method.experimental
+                        && !method.capabilities.contains(UNSUPPORTED_CAPABILITIES);
+                    let name = method.display_name(UI_LOCALE);
+                    MethodItem {
+                        id: method.id.clone(),
+                        label: name.to_uppercase(),
+                        item: IconMenuItem::new(name, selectable, None, None),
                     }
-                }
+                })
+                .collect();
+            for method in &methods {
+                let _ = submenu.append(&method.item);
             }
-        }
-        Err(e) => eprintln!("Failed to get settings path: {:?}", e),
+            LanguageMenu {
+                id: language.id.clone(),
+                label,
+                icon: language_tray_icon(),
+                submenu,
+                methods,
+            }
+        })
+        .collect()
+}
+
+/// Tray icon while a language is active
+///
+/// Only the Vietnamese icon ships for now, so every language shares it.
+fn language_tray_icon() -> TrayIcon {
+    load_icon_from_bytes(VIETNAMESE_ICON_BYTES)
+        .unwrap_or_else(|_| TrayIcon::from_rgba(vec![0, 0, 0, 0], 1, 1).unwrap())
+}
+
+/// Active (language, input method), or `None` when the engine is off
+fn selection(engine: &Engine) -> Option<(String, String)> {
+    if !engine.is_enabled() {
+        return None;
     }
-    Settings::default()
+    let language = engine.current_language()?.to_string();
+    let method = engine.current_input_method()?.to_string();
+    Some((language, method))
 }
 
-fn save_settings(settings: &Settings) -> Result<()> {
-    let path = get_settings_path()?;
-    let content = toml::to_string_pretty(settings)?;
-    fs::write(path, content)?;
-    Ok(())
+/// Show the active selection: check marks, tray icon and tooltip
+fn update_tray(
+    tray: &tray_icon::TrayIcon,
+    english_item: &IconMenuItem,
+    english_icon: &TrayIcon,
+    menus: &[LanguageMenu],
+    selection: Option<&(String, String)>,
+) {
+    let check_icon = load_menu_icon(CHECK_ICON_BYTES);
+    let checked = |on: bool| if on { check_icon.clone() } else { None };
+
+    let _ = english_item.set_icon(checked(selection.is_none()));
+    let mut tooltip = "Vikey\nEnglish".to_string();
+    let mut icon = english_icon.clone();
+    for menu in menus {
+        let active = selection.filter(|(language, _)| *language == menu.id);
+        let _ = menu.submenu.set_icon(checked(active.is_some()));
+        for method in &menu.methods {
+            let on = active.is_some_and(|(_, id)| *id == method.id);
+            let _ = method.item.set_icon(checked(on));
+            if on {
+                tooltip = format!("Vikey\n{}\n{}", menu.label, method.label);
+                icon = menu.icon.clone();
+            }
+        }
+    }
+    let _ = tray.set_icon(Some(icon));
+    let _ = tray.set_tooltip(Some(tooltip));
 }
 
 fn load_icon_from_bytes(bytes: &[u8]) -> Result<TrayIcon> {
@@ -103,9 +238,42 @@ fn main() -> Result<()> {
         std::process::exit(0);
     }
 
-    // Load settings
-    let mut settings = load_settings();
+    // Move an older settings file to the current format
+    let loader = Loader::new();
+    match loader.upgrade() {
+        Ok(Some(version)) => println!("Upgraded settings file from version {}", version),
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to upgrade settings file: {}", e),
+    }
+
+    // --- Vikey Engine Setup ---
+    // Every registered language and input method gets a tray entry
+    let mut engine = Engine::new();
+    let mut vietnamese_plugin = VietnamesePlugin::new();
+    if let Some(dir) = loader.schema_dir() {
+        for error in vietnamese_plugin.load_schemas(dir) {
+            eprintln!("Input method schema ignored: {}", error);
+        }
+    }
+    if let Err(e) = engine.register(Box::new(vietnamese_plugin)) {
+        eprintln!("Failed to register Vietnamese plugin: {:?}", e);
+    }
+    if let Err(e) = engine.register(Box::new(NomPlugin::new())) {
+        eprintln!("Failed to register Nôm plugin: {:?}", e);
+    }
+    // Same stages as the broker so every settings entry applies; the macro
+    // table itself is only used by the broker
+    if let Err(e) = engine.add_stage(Box::new(Shorthand::default())) {
+        eprintln!("Failed to add shorthand stage: {:?}", e);
+    }
+    if let Err(e) = engine.add_stage(Box::new(OutputEncoding::default())) {
+        eprintln!("Failed to add output encoding stage: {:?}", e);
+    }
+    // Plugin and stage options in the files are checked against the engine
+    let loader = loader.with_options(engine.option_schema());
+    let mut settings = load_settings(&loader);
     println!("Loaded settings: {:?}", settings);
+    apply_settings(&mut engine, &settings);
 
     let event_loop = EventLoop::new()?;
     
@@ -115,61 +283,49 @@ fn main() -> Result<()> {
 
     // --- Menu Setup ---
     
-    // 0. English (disable input method) - IconMenuItem
-    let english_item = IconMenuItem::new(
-        "English", 
-        true, 
-        if settings.input_method == "english" { load_menu_icon(CHECK_ICON_BYTES) } else { None },
-        None
-    );
-    
-    // 1. Chữ Việt submenu (enabled)
-    let chu_viet_menu = Submenu::new("Chữ Việt", true);
-    let telex_item = IconMenuItem::new(
-        "Telex", 
-        true, 
-        if settings.input_method == "telex" { load_menu_icon(CHECK_ICON_BYTES) } else { None },
-        None
-    );
-    let vni_item = IconMenuItem::new(
-        "VNI", 
-        true, 
-        if settings.input_method == "vni" { load_menu_icon(CHECK_ICON_BYTES) } else { None },
-        None
-    );
-    let viqr_item = MenuItem::new("VIQR", false, None); // disabled for now
-    let _ = chu_viet_menu.append_items(&[&telex_item, &vni_item, &viqr_item]);
+    // English (disable input method)
+    let english_item = IconMenuItem::new("English", true, None, None);
     
-    // 2. Chữ Nôm submenu (disabled)
-    let chu_nom_menu = Submenu::new("Chữ Nôm", true);
-    let ky_am_item = MenuItem::new("Ký âm", false, None);
-    let chiet_tu_item = MenuItem::new("Chiết tự", false, None);
-    let _ = chu_nom_menu.append_items(&[&ky_am_item, &chiet_tu_item]);
+    // One submenu per language, one item per input method
+    let language_menus = build_language_menus(&engine);
     
-    // 3. Other writing systems (disabled)
-    let chu_tay_nguyen_item = MenuItem::new("Chữ Tây Nguyên", false, None);
-    
-    // 4. Tùy chọn submenu
+    // Tùy chọn submenu
     let tuy_chon_menu = Submenu::new("Tùy chọn", true);
-    // Note: muda doesn't have CheckMenuItem, using regular MenuItem for now
-    let auto_correct_item = MenuItem::new("Tự động sửa lỗi chính tả", false, None);
-    let shorthand_item = MenuItem::new("Gõ tắt", false, None);
+    let auto_correct_item = CheckMenuItem::new("Tự động sửa lỗi chính tả", true, auto_correct_enabled(&engine), None);
+    let shorthand_item = CheckMenuItem::new("Gõ tắt", true, settings.engine.shorthand, None);
     let startup_item = MenuItem::new("Tự động khởi động", false, None);
-    let chuyen_ma_item = MenuItem::new("Chuyển mã", true, None);
     let huong_dan_item = MenuItem::new("Hướng dẫn", true, None);
     let _ = tuy_chon_menu.append_items(&[&auto_correct_item, &shorthand_item, &startup_item]);
     
-    // 5. Other items
+    // Bảng mã submenu: charset of the text written to applications
+    let bang_ma_menu = Submenu::new("Bảng mã", true);
+    let charset_items: Vec<(Charset, CheckMenuItem)> = Charset::ALL
+        .iter()
+        .map(|&charset| {
+            let checked = charset == output_charset(&engine);
+            (charset, CheckMenuItem::new(charset.name(), true, checked, None))
+        })
+        .collect();
+    for (_, item) in &charset_items {
+        let _ = bang_ma_menu.append(item);
+    }
+    
+    // Chuyển mã: clipboard text from legacy charsets to Unicode
+    let chuyen_ma_item = MenuItem::new("Chuyển mã clipboard", true, None);
+    
+    // Other items
     let thoat_item = MenuItem::new("Thoát", true, None);
 
     // Assemble menu
     let menu = Menu::new();
+    let _ = menu.append(&english_item);
+    for language_menu in &language_menus {
+        let _ = menu.append(&language_menu.submenu);
+    }
     let _ = menu.append_items(&[
-        &english_item,
-        &chu_viet_menu,
-        &chu_nom_menu,
-        &chu_tay_nguyen_item,
         &PredefinedMenuItem::separator(),
+        &bang_ma_menu,
+        &chuyen_ma_item,
         &tuy_chon_menu,
         &PredefinedMenuItem::separator(),
         &huong_dan_item,
@@ -177,174 +333,138 @@ fn main() -> Result<()> {
     ]);
 
     // --- Tray Setup ---
-    let vietnamese_icon = load_icon_from_bytes(VIETNAMESE_ICON_BYTES).unwrap_or_else(|_| {
-        TrayIcon::from_rgba(vec![0, 0, 0, 0], 1, 1).unwrap()
-    });
-    
     let english_icon = load_icon_from_bytes(ENGLISH_ICON_BYTES).unwrap_or_else(|_| {
         TrayIcon::from_rgba(vec![0, 0, 0, 0], 1, 1).unwrap()
     });
-
-    let initial_tooltip = if settings.input_method == "english" {
-        "Vikey\nEnglish".to_string()
-    } else {
-        format!("Vikey\nChữ Việt\n{}", 
-            if settings.input_method == "telex" { "TELEX" } else { "VNI" })
-    };
     
     let _tray_icon = TrayIconBuilder::new()
         .with_menu(Box::new(menu))
-        .with_tooltip(&initial_tooltip)
-        .with_icon(if settings.input_method == "english" { 
-            english_icon.clone() 
-        } else { 
-            vietnamese_icon.clone() 
-        })
+        .with_icon(english_icon.clone())
         .build()?;
-    
-    // Set submenu icon if Vietnamese input is selected
-    if settings.input_method != "english" {
-        if let Some(check_icon) = load_menu_icon(CHECK_ICON_BYTES) {
-            let _ = chu_viet_menu.set_icon(Some(check_icon));
-        }
-    }
+    let mut current = selection(&engine);
+    update_tray(&_tray_icon, &english_item, &english_icon, &language_menus, current.as_ref());
 
-    // --- Vikey Engine Setup ---
-    use vikey_core::engine::Engine;
-    use vikey_vietnamese::VietnamesePlugin;
-    
-    let mut engine = Engine::new();
-    if let Err(e) = engine.register(Box::new(VietnamesePlugin::new())) {
-        eprintln!("Failed to register Vietnamese plugin: {:?}", e);
-    }
-    
-    // Apply settings to engine
-    if settings.input_method != "english" {
-        if let Err(e) = engine.set_language("vietnamese") {
-            eprintln!("Failed to set language: {:?}", e);
+    // Reload settings when the files are edited by hand (or by another tool)
+    let (reload_sender, reloads) = mpsc::channel();
+    let proxy = event_loop.create_proxy();
+    let _watcher = Watcher::spawn(loader.clone(), RELOAD_INTERVAL, move |loaded| {
+        for diagnostic in &loaded.diagnostics {
+            eprintln!("{}", diagnostic);
         }
-        if let Err(e) = engine.set_input_method(&settings.input_method) {
-            eprintln!("Failed to set input method: {:?}", e);
+        if reload_sender.send(loaded.settings).is_ok() {
+            let _ = proxy.send_event(());
         }
-    }
+    })
+    .map_err(|e| eprintln!("Failed to watch settings files: {}", e))
+    .ok();
 
     // --- Event Loop ---
     let menu_channel = muda::MenuEvent::receiver();
     let _tray_channel = tray_icon::TrayIconEvent::receiver();
-    let mut _is_enabled = settings.input_method != "english";
-    let mut _current_method = settings.input_method.clone();
 
     event_loop.run(move |event, elwt| {
         elwt.set_control_flow(ControlFlow::Wait);
 
+        // Settings entries to save for the clicked menu item
+        let mut entries: Vec<(&str, toml::Value)> = Vec::new();
+
         match event {
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
-            } => elwt.exit(),
+            } => {
+                elwt.exit();
+                return;
+            }
+            
+            // Settings files changed on disk
+            Event::UserEvent(()) => {
+                let Some(reloaded) = reloads.try_iter().last() else {
+                    return;
+                };
+                settings = reloaded;
+            }
             
             Event::AboutToWait => {
-                if let Ok(event) = menu_channel.try_recv() {
-                    // Handle Thoát (Exit)
-                    if event.id == thoat_item.id() {
-                        elwt.exit();
-                    } 
-                    // Handle English (disable input method)
-                    else if event.id == english_item.id() {
-                        _is_enabled = false;
-                        _current_method = "english".to_string();
-                        println!("Switched to English (disabled)");
-                        
-                        // Update settings
-                        settings.input_method = "english".to_string();
-                        if let Err(e) = save_settings(&settings) {
-                            eprintln!("Failed to save settings: {:?}", e);
-                        }
-                        
-                        // Update menu icons
-                        if let Some(check_icon) = load_menu_icon(CHECK_ICON_BYTES) {
-                            let _ = english_item.set_icon(Some(check_icon)); // Show check
-                        }
-                        let _ = telex_item.set_icon(None); // Remove check
-                        let _ = vni_item.set_icon(None); // Remove check
-                        let _ = chu_viet_menu.set_icon(None); // Remove icon from submenu
-                        
-                        // Update icon and tooltip
-                        let _ = _tray_icon.set_icon(Some(english_icon.clone()));
-                        let _ = _tray_icon.set_tooltip(Some("Vikey\nOFF".to_string()));
-                    }
-                    // Handle Telex
-                    else if event.id == telex_item.id() {
-                        _is_enabled = true;
-                        _current_method = "telex".to_string();
-                        println!("Switched to Telex");
-                        
-                        // Update settings
-                        settings.input_method = "telex".to_string();
-                        if let Err(e) = save_settings(&settings) {
-                            eprintln!("Failed to save settings: {:?}", e);
-                        }
-                        
-                        // Update engine
-                        if let Err(e) = engine.set_language("vietnamese") {
-                            eprintln!("Failed to set language: {:?}", e);
-                        }
-                        if let Err(e) = engine.set_input_method("telex") {
-                            eprintln!("Failed to set input method: {:?}", e);
-                        }
-                        
-                        // Update menu icons
-                        let _ = english_item.set_icon(None); // Remove check
-                        if let Some(check_icon) = load_menu_icon(CHECK_ICON_BYTES) {
-                            let _ = telex_item.set_icon(Some(check_icon.clone())); // Show check
-                            let _ = chu_viet_menu.set_icon(Some(check_icon)); // Add check icon to submenu
-                        }
-                        let _ = vni_item.set_icon(None); // Remove check
-                        
-                        // Update icon and tooltip
-                        let _ = _tray_icon.set_icon(Some(vietnamese_icon.clone()));
-                        let _ = _tray_icon.set_tooltip(Some("Vikey\nChữ Việt\nTELEX".to_string()));
-                    } 
-                    // Handle VNI
-                    else if event.id == vni_item.id() {
-                        _is_enabled = true;
-                        _current_method = "vni".to_string();
-                        println!("Switched to VNI");
-                        
-                        // Update settings
-                        settings.input_method = "vni".to_string();
-                        if let Err(e) = save_settings(&settings) {
-                            eprintln!("Failed to save settings: {:?}", e);
-                        }
-                        
-                        // Update engine
-                        if let Err(e) = engine.set_language("vietnamese") {
-                            eprintln!("Failed to set language: {:?}", e);
-                        }
-                        if let Err(e) = engine.set_input_method("vni") {
-                            eprintln!("Failed to set input method: {:?}", e);
-                        }
-                        
-                        // Update menu icons
-                        let _ = english_item.set_icon(None); // Remove check
-                        let _ = telex_item.set_icon(None); // Remove check
-                        if let Some(check_icon) = load_menu_icon(CHECK_ICON_BYTES) {
-                            let _ = vni_item.set_icon(Some(check_icon.clone())); // Show check
-                            let _ = chu_viet_menu.set_icon(Some(check_icon)); // Add check icon to submenu
-                        }
-                        
-                        // Update icon and tooltip
-                        let _ = _tray_icon.set_icon(Some(vietnamese_icon.clone()));
-                        let _ = _tray_icon.set_tooltip(Some("Vikey\nChữ Việt\nVNI".to_string()));
-                    }
-                    // Handle Hướng dẫn
-                    else if event.id == huong_dan_item.id() {
-                        println!("Opening documentation...");
-                        // TODO: Open README.md or documentation URL
+                let Ok(event) = menu_channel.try_recv() else {
+                    return;
+                };
+                // Handle Thoát (Exit)
+                if event.id == thoat_item.id() {
+                    elwt.exit();
+                    return;
+                }
+                // Handle English (disable input method)
+                else if event.id == english_item.id() {
+                    println!("Switched to English (disabled)");
+                    entries.push(("engine.enabled", false.into()));
+                }
+                // Handle option toggles (muda flips the check mark itself)
+                else if event.id == auto_correct_item.id() || event.id == shorthand_item.id() {
+                    let auto_correct = auto_correct_item.is_checked();
+                    let shorthand = shorthand_item.is_checked();
+                    println!("Options: auto_correct={}, shorthand={}", auto_correct, shorthand);
+                    
+                    let auto_restore = if auto_correct { "restore_on_boundary" } else { "off" };
+                    entries.push(("engine.plugins.vietnamese.auto_restore", auto_restore.into()));
+                    entries.push(("engine.shorthand", shorthand.into()));
+                }
+                // Handle Bảng mã
+                else if let Some((charset, _)) =
+                    charset_items.iter().find(|(_, item)| event.id == item.id())
+                {
+                    println!("Output charset: {}", charset);
+                    entries.push(("engine.stages.encoding.charset", charset.id().into()));
+                }
+                // Handle Chuyển mã
+                else if event.id == chuyen_ma_item.id() {
+                    match convert_clipboard() {
+                        Ok(guess) => println!("Converted the clipboard from {}", guess),
+                        Err(e) => eprintln!("Failed to convert the clipboard: {}", e),
                     }
+                    return;
+                }
+                // Handle Hướng dẫn
+                else if event.id == huong_dan_item.id() {
+                    println!("Opening documentation...");
+                    // TODO: Open README.md or documentation URL
+                    return;
+                }
+                // Handle input methods
+                else {
+                    let clicked = language_menus.iter().find_map(|language| {
+                        let method = language.methods.iter().find(|m| event.id == m.item.id())?;
+                        Some((language, method))
+                    });
+                    let Some((language, method)) = clicked else {
+                        return;
+                    };
+                    println!("Switched to {} ({})", method.id, language.id);
+                    entries.push(("engine.enabled", true.into()));
+                    entries.push(("engine.language", language.id.as_str().into()));
+                    entries.push(("engine.input_method", method.id.as_str().into()));
+                }
+                
+                match save_settings(&loader, &entries) {
+                    Ok(saved) => settings = saved,
+                    Err(e) => eprintln!("Failed to save settings: {:?}", e),
                 }
             }
-            _ => {}
+            _ => return,
+        }
+        
+        // Apply the new settings and show them in the tray
+        apply_settings(&mut engine, &settings);
+        auto_correct_item.set_checked(auto_correct_enabled(&engine));
+        shorthand_item.set_checked(settings.engine.shorthand);
+        let charset = output_charset(&engine);
+        for (item_charset, item) in &charset_items {
+            item.set_checked(*item_charset == charset);
+        }
+        let now = selection(&engine);
+        if now != current {
+            current = now;
+            update_tray(&_tray_icon, &english_item, &english_icon, &language_menus, current.as_ref());
         }
     })?;
 
@@ -3,18 +3,38 @@
 //! Shared message types between TSF DLL and broker
 
 use serde::{Deserialize, Serialize};
+use vikey_core::KeyEvent;
 
 /// IPC Request from TSF DLL to broker
 #[derive(Serialize, Deserialize, Debug)]
 pub enum IpcRequest {
-    /// Process a keystroke
-    ProcessKey(char),
+    /// Process a keystroke (key code, produced char, modifiers, press/release)
+    ProcessKey(KeyEvent),
     /// Process backspace
     ProcessBackspace,
     /// Reset engine state
     Reset,
     /// Ping to check if broker is alive
     Ping,
+    /// A text field gained focus: route following keys to its own session
+    FocusContext(u64),
+    /// A text field was closed: drop its session
+    DestroyContext(u64),
+    /// The text service gained keyboard focus
+    FocusIn,
+    /// The text service lost keyboard focus
+    FocusOut,
+    /// The caret was moved by the application
+    CursorMoved,
+    /// The user clicked into the text
+    MouseClick,
+    /// Text before the caret, so a committed word can be edited again
+    SurroundingText(String),
+    /// Output charset ID (e.g. "tcvn3") for the focused text field only;
+    /// `None` goes back to the configured one
+    SetCharset(Option<String>),
+    /// Is a word being composed in the focused text field?
+    IsComposing,
 }
 
 /// IPC Response from broker to TSF DLL
@@ -26,13 +46,21 @@ pub enum IpcResponse {
     Pong,
     /// Error occurred
     Error(String),
+    /// Answer to `IsComposing`
+    Composing(bool),
 }
 
 /// Action to execute in the application
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Action {
-    /// Do nothing, pass through
+    /// Key was consumed, nothing to change in the text
     DoNothing,
+    /// Key was not handled; deliver it to the application unchanged
+    PassThrough,
     /// Replace text: delete N chars, insert string
     Replace { delete: usize, insert: String },
+    /// Show/update the composition string (preedit mode)
+    UpdatePreedit { text: String, cursor: usize },
+    /// Commit the composition string, then pass the key through
+    CommitPreedit,
 }
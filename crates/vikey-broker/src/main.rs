@@ -6,9 +6,25 @@ mod ipc_protocol;
 mod pipe_server;
 
 use anyhow::Result;
-use vikey_core::Engine;
-use vikey_vietnamese::VietnamesePlugin;
-use tracing::{info, error};
+use std::sync::mpsc;
+use std::time::Duration;
+use vikey_config::{Loaded, Loader, Severity, Watcher};
+use vikey_core::{Engine, EngineEvent, SessionManager, Shorthand};
+use vikey_vietnamese::{OutputEncoding, VietnamesePlugin};
+use tracing::{debug, info, warn, error};
+
+/// How often the settings files are checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Log problems found in the settings files
+fn log_diagnostics(loaded: &Loaded) {
+    for diagnostic in &loaded.diagnostics {
+        match diagnostic.severity {
+            Severity::Info => info!("{}", diagnostic),
+            _ => warn!("{}", diagnostic),
+        }
+    }
+}
 
 fn main() -> Result<()> {
     // Initialize logging
@@ -21,8 +37,17 @@ fn main() -> Result<()> {
     // Create engine
     let mut engine = Engine::new();
     
-    // Register Vietnamese plugin
-    let vietnamese_plugin = VietnamesePlugin::new();
+    // Log commits, method switches and rejected keys for diagnostics
+    engine.subscribe(Box::new(|event: &EngineEvent| debug!("Engine event: {:?}", event)));
+    
+    // Register Vietnamese plugin, with the user's own input methods
+    let loader = Loader::new();
+    let mut vietnamese_plugin = VietnamesePlugin::new();
+    if let Some(dir) = loader.schema_dir() {
+        for error in vietnamese_plugin.load_schemas(dir) {
+            warn!("Input method schema ignored: {}", error);
+        }
+    }
     engine.register(Box::new(vietnamese_plugin))?;
     
     // Set language and input method
@@ -31,10 +56,39 @@ fn main() -> Result<()> {
     
     info!("Engine initialized with Vietnamese Telex");
     
+    engine.add_stage(Box::new(Shorthand::default()))?;
+    engine.add_stage(Box::new(OutputEncoding::default()))?;
+    
+    // Apply the shared settings (system defaults, user file, profile);
+    // plugin and stage options are checked against the engine
+    let loader = loader.with_options(engine.option_schema());
+    let loaded = loader.load();
+    log_diagnostics(&loaded);
+    info!("Loaded {} shorthand entries", loaded.macros.len());
+    engine.replace_stage(Box::new(Shorthand::new(loaded.macros)));
+    if let Err(errors) = engine.set_config(loaded.settings.engine) {
+        for e in errors {
+            warn!("Setting not applied: {}", e);
+        }
+    }
+    
+    // Reload settings when the files change; applied between requests
+    let (reload_sender, reloads) = mpsc::channel();
+    let _watcher = Watcher::spawn(loader, RELOAD_INTERVAL, move |loaded| {
+        info!("Settings changed, reloading");
+        log_diagnostics(&loaded);
+        let _ = reload_sender.send(loaded);
+    })
+    .map_err(|e| warn!("Cannot watch settings files: {}", e))
+    .ok();
+    
     // Start Named Pipe server
     info!("Starting Named Pipe server on \\\\.\\pipe\\vikey-broker");
     
-    match pipe_server::run_server(engine) {
+    // Each focused text field gets its own composition state
+    let sessions = SessionManager::new(engine);
+    
+    match pipe_server::run_server(sessions, reloads) {
         Ok(()) => {
             info!("Server stopped gracefully");
             Ok(())
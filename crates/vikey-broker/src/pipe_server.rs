@@ -3,18 +3,24 @@
 //! Uses std library for cross-platform compatibility
 
 use crate::ipc_protocol::{IpcRequest, IpcResponse, Action};
-use vikey_core::{Engine, Action as EngineAction};
+use vikey_config::Loaded;
+use vikey_core::{SessionManager, Shorthand, Action as EngineAction};
+use vikey_vietnamese::encoding;
 use anyhow::{Result, Context};
 use tracing::{info, warn, error, debug};
 use std::io::{Read, Write};
 use std::fs::OpenOptions;
 use std::os::windows::fs::OpenOptionsExt;
+use std::sync::mpsc::Receiver;
 
 const PIPE_NAME: &str = r"\\.\pipe\vikey-broker";
 const BUFFER_SIZE: usize = 4096;
 
 /// Run the Named Pipe server
-pub fn run_server(mut engine: Engine) -> Result<()> {
+///
+/// Settings and shorthand tables received on `reloads` are applied before
+/// the next request.
+pub fn run_server(mut sessions: SessionManager, reloads: Receiver<Loaded>) -> Result<()> {
     info!("Starting Named Pipe server: {}", PIPE_NAME);
     info!("Note: Using simplified std implementation");
     
@@ -33,7 +39,7 @@ pub fn run_server(mut engine: Engine) -> Result<()> {
         info!("Client connected!");
         
         // Handle client
-        if let Err(e) = handle_client(&mut pipe, &mut engine) {
+        if let Err(e) = handle_client(&mut pipe, &mut sessions, &reloads) {
             error!("Client error: {:?}", e);
         }
         
@@ -42,7 +48,11 @@ pub fn run_server(mut engine: Engine) -> Result<()> {
 }
 
 /// Handle client connection
-fn handle_client(pipe: &mut std::fs::File, engine: &mut Engine) -> Result<()> {
+fn handle_client(
+    pipe: &mut std::fs::File,
+    sessions: &mut SessionManager,
+    reloads: &Receiver<Loaded>,
+) -> Result<()> {
     let mut buffer = vec![0u8; BUFFER_SIZE];
     
     loop {
@@ -75,8 +85,20 @@ fn handle_client(pipe: &mut std::fs::File, engine: &mut Engine) -> Result<()> {
         
         debug!("Request: {:?}", request);
         
+        // Apply the latest reloaded settings, if any
+        if let Some(loaded) = reloads.try_iter().last() {
+            let engine = sessions.engine_mut();
+            engine.replace_stage(Box::new(Shorthand::new(loaded.macros)));
+            if let Err(errors) = sessions.set_config(loaded.settings.engine) {
+                for e in errors {
+                    warn!("Reloaded setting not applied: {}", e);
+                }
+            }
+            info!("Settings reloaded");
+        }
+        
         // Process request
-        let response = process_request(request, engine);
+        let response = process_request(request, sessions);
         
         debug!("Response: {:?}", response);
         
@@ -98,55 +120,16 @@ fn handle_client(pipe: &mut std::fs::File, engine: &mut Engine) -> Result<()> {
 }
 
 /// Process IPC request
-fn process_request(request: IpcRequest, engine: &mut Engine) -> IpcResponse {
+fn process_request(request: IpcRequest, sessions: &mut SessionManager) -> IpcResponse {
+    let engine = sessions.engine_mut();
     match request {
-        IpcRequest::ProcessKey(ch) => {
-            info!("Processing key: '{}'", ch);
-            
-            match engine.process(ch) {
-                EngineAction::Replace { backspace_count, text } => {
-                    info!("→ Replace(delete={}, insert='{}')", backspace_count, text);
-                    IpcResponse::Action(Action::Replace { 
-                        delete: backspace_count, 
-                        insert: text 
-                    })
-                }
-                EngineAction::DoNothing => {
-                    debug!("→ DoNothing");
-                    IpcResponse::Action(Action::DoNothing)
-                }
-                EngineAction::Commit(text) => {
-                    info!("→ Commit('{}')", text);
-                    IpcResponse::Action(Action::Replace { 
-                        delete: 0, 
-                        insert: text 
-                    })
-                }
-            }
+        IpcRequest::ProcessKey(key) => {
+            info!("Processing key: {:?}", key);
+            IpcResponse::Action(to_ipc_action(engine.process(key)))
         }
         IpcRequest::ProcessBackspace => {
             info!("Processing backspace");
-            
-            match engine.process_backspace() {
-                EngineAction::Replace { backspace_count, text } => {
-                    info!("→ Replace(delete={}, insert='{}')", backspace_count, text);
-                    IpcResponse::Action(Action::Replace { 
-                        delete: backspace_count, 
-                        insert: text 
-                    })
-                }
-                EngineAction::DoNothing => {
-                    debug!("→ DoNothing");
-                    IpcResponse::Action(Action::DoNothing)
-                }
-                EngineAction::Commit(text) => {
-                    info!("→ Commit('{}')", text);
-                    IpcResponse::Action(Action::Replace { 
-                        delete: 0, 
-                        insert: text 
-                    })
-                }
-            }
+            IpcResponse::Action(to_ipc_action(engine.process_backspace()))
         }
         IpcRequest::Reset => {
             info!("Resetting engine");
@@ -157,5 +140,89 @@ fn process_request(request: IpcRequest, engine: &mut Engine) -> IpcResponse {
             debug!("Ping → Pong");
             IpcResponse::Pong
         }
+        IpcRequest::FocusContext(id) => {
+            debug!("Focus context {}", id);
+            sessions.focus(id);
+            IpcResponse::Action(Action::DoNothing)
+        }
+        IpcRequest::DestroyContext(id) => {
+            debug!("Destroy context {}", id);
+            sessions.destroy(id);
+            IpcResponse::Action(Action::DoNothing)
+        }
+        IpcRequest::FocusIn => {
+            debug!("Focus in");
+            IpcResponse::Action(to_ipc_action(engine.on_focus_in()))
+        }
+        IpcRequest::FocusOut => {
+            debug!("Focus out");
+            IpcResponse::Action(to_ipc_action(engine.on_focus_out()))
+        }
+        IpcRequest::CursorMoved => {
+            debug!("Cursor moved");
+            IpcResponse::Action(to_ipc_action(engine.on_cursor_moved()))
+        }
+        IpcRequest::MouseClick => {
+            debug!("Mouse click");
+            IpcResponse::Action(to_ipc_action(engine.on_mouse_click()))
+        }
+        IpcRequest::SurroundingText(text) => {
+            let resumed = engine.set_surrounding_text(&text);
+            debug!("Surrounding text (resumed={})", resumed);
+            IpcResponse::Action(Action::DoNothing)
+        }
+        IpcRequest::SetCharset(charset) => {
+            info!("Output charset for this context: {:?}", charset);
+            match engine.set_session_option(encoding::ID, "charset", charset.map(Into::into)) {
+                Ok(()) => IpcResponse::Action(Action::DoNothing),
+                Err(e) => IpcResponse::Error(e.to_string()),
+            }
+        }
+        IpcRequest::IsComposing => {
+            let composing = !engine.buffer_content().is_empty();
+            debug!("Composing: {}", composing);
+            IpcResponse::Composing(composing)
+        }
+    }
+}
+
+/// Convert engine action to IPC action
+fn to_ipc_action(action: EngineAction) -> Action {
+    match action {
+        EngineAction::Replace { backspace_count, text } => {
+            info!("→ Replace(delete={}, insert='{}')", backspace_count, text);
+            Action::Replace { 
+                delete: backspace_count, 
+                insert: text 
+            }
+        }
+        EngineAction::Commit(text) => {
+            info!("→ Commit('{}')", text);
+            Action::Replace { 
+                delete: 0, 
+                insert: text 
+            }
+        }
+        EngineAction::UpdatePreedit { text, cursor, .. } => {
+            info!("→ UpdatePreedit('{}', cursor={})", text, cursor);
+            Action::UpdatePreedit { text, cursor }
+        }
+        EngineAction::CommitPreedit => {
+            info!("→ CommitPreedit");
+            Action::CommitPreedit
+        }
+        EngineAction::ShowCandidates(_) | EngineAction::HideCandidates => {
+            // TODO: TSF chưa có cửa sổ candidate
+            debug!("→ PassThrough (candidate window not supported)");
+            Action::PassThrough
+        }
+        EngineAction::DoNothing => {
+            debug!("→ DoNothing");
+            Action::DoNothing
+        }
+        EngineAction::PassThrough => {
+            debug!("→ PassThrough");
+            Action::PassThrough
+        }
     }
 }
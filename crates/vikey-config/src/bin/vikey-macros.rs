@@ -0,0 +1,88 @@
+//! Vikey Macros - shorthand table import/export
+//!
+//! ```text
+//! vikey-macros import <file>               merge a UniKey/OpenKey file into macros.toml
+//! vikey-macros export <unikey|openkey> <file>
+//! vikey-macros list
+//! ```
+
+use std::path::Path;
+use std::process::ExitCode;
+use vikey_config::{interop, Diagnostic, Loader, MacroFile, MacroFormat, Severity};
+
+const USAGE: &str = "usage: vikey-macros import <file>
+       vikey-macros export <unikey|openkey> <file>
+       vikey-macros list";
+
+fn report(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let loader = Loader::new();
+    let path = loader
+        .macro_file()
+        .ok_or("could not find the config directory")?;
+
+    match args {
+        [command, file] if command == "import" => {
+            let (table, diagnostics) =
+                interop::import_file(Path::new(file)).map_err(|e| e.to_string())?;
+            report(&diagnostics);
+            let mut macros = MacroFile::open(path).map_err(|e| e.to_string())?;
+            let replaced = macros.merge(&table);
+            macros.save().map_err(|e| e.to_string())?;
+            let skipped = diagnostics
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .count();
+            println!(
+                "Imported {} entries ({} replaced, {} skipped) into {}",
+                table.len(),
+                replaced,
+                skipped,
+                path.display()
+            );
+            Ok(())
+        }
+        [command, format, file] if command == "export" => {
+            let format = match format.to_ascii_lowercase().as_str() {
+                "unikey" => MacroFormat::UniKey,
+                "openkey" => MacroFormat::OpenKey,
+                _ => return Err(format!("unknown format '{}'\n{}", format, USAGE)),
+            };
+            let table = MacroFile::open(path).map_err(|e| e.to_string())?.macros();
+            let diagnostics =
+                interop::export_file(Path::new(file), format, &table).map_err(|e| e.to_string())?;
+            report(&diagnostics);
+            println!(
+                "Exported {} of {} entries to {}",
+                table.len() - diagnostics.len(),
+                table.len(),
+                file
+            );
+            Ok(())
+        }
+        [command] if command == "list" => {
+            let table = MacroFile::open(path).map_err(|e| e.to_string())?.macros();
+            for (key, expansion) in table.iter() {
+                println!("{}:{}", key, expansion);
+            }
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
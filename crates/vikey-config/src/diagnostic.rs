@@ -0,0 +1,162 @@
+//! Vikey Config - Diagnostics
+//!
+//! Thông báo về các mục cấu hình bị bỏ qua, file được nâng cấp..., đủ rõ
+//! để user tự sửa file.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Mức độ của một thông báo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Thông tin (ví dụ: file đã được nâng cấp)
+    Info,
+    /// Cấu hình vẫn dùng được nhưng có thể không như user muốn
+    Warning,
+    /// Mục cấu hình (hoặc cả file) bị bỏ qua
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// Lớp cấu hình
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// Mặc định cho mọi user của máy
+    System,
+    /// File của user (ghi đè mặc định hệ thống)
+    User,
+    /// File gõ tắt của user
+    Macros,
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Layer::System => "system config",
+            Layer::User => "user config",
+            Layer::Macros => "macro file",
+        })
+    }
+}
+
+/// Nguồn của một lớp cấu hình
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    /// Lớp
+    pub layer: Layer,
+
+    /// File (nếu lớp được đọc từ file)
+    pub path: Option<PathBuf>,
+}
+
+impl Origin {
+    /// Lớp không gắn với file nào (ví dụ: chuỗi trong test)
+    pub fn new(layer: Layer) -> Self {
+        Self { layer, path: None }
+    }
+
+    /// Lớp đọc từ `path`
+    pub fn file(layer: Layer, path: impl Into<PathBuf>) -> Self {
+        Self {
+            layer,
+            path: Some(path.into()),
+        }
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}", path.display()),
+            None => write!(f, "{}", self.layer),
+        }
+    }
+}
+
+/// Một thông báo về cấu hình
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Mức độ
+    pub severity: Severity,
+
+    /// Lớp (file) liên quan
+    pub origin: Origin,
+
+    /// Dòng (từ 1); `None` nếu không xác định được, ví dụ với file vừa
+    /// được nâng cấp từ phiên bản cũ
+    pub line: Option<usize>,
+
+    /// Cột (từ 1, tính theo ký tự)
+    pub column: Option<usize>,
+
+    /// Khóa của mục liên quan (ví dụ: "engine.keys.undo")
+    pub entry: Option<String>,
+
+    /// Mô tả
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Thông báo cho cả lớp
+    pub fn new(severity: Severity, origin: Origin, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            origin,
+            line: None,
+            column: None,
+            entry: None,
+            message: message.into(),
+        }
+    }
+}
+
+/// Dạng "file:dòng:cột: error: khóa: mô tả"
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.origin)?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        write!(f, ": {}: ", self.severity)?;
+        if let Some(entry) = &self.entry {
+            write!(f, "{}: ", entry)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut diagnostic = Diagnostic::new(
+            Severity::Error,
+            Origin::file(Layer::User, "config.toml"),
+            "invalid key binding",
+        );
+        diagnostic.line = Some(4);
+        diagnostic.column = Some(8);
+        diagnostic.entry = Some("engine.keys.undo".to_string());
+        assert_eq!(
+            diagnostic.to_string(),
+            "config.toml:4:8: error: engine.keys.undo: invalid key binding"
+        );
+
+        let diagnostic = Diagnostic::new(Severity::Info, Origin::new(Layer::System), "upgraded");
+        assert_eq!(diagnostic.to_string(), "system config: info: upgraded");
+    }
+}
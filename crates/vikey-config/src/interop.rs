@@ -0,0 +1,356 @@
+//! Vikey Config - UniKey / OpenKey Macro Files
+//!
+//! Đọc và ghi bảng gõ tắt theo định dạng văn bản của UniKey và file xuất
+//! gõ tắt của OpenKey, để chuyển bảng gõ tắt sẵn có sang Vikey (và ngược
+//! lại). Hai định dạng giống nhau, chỉ khác dòng đầu:
+//!
+//! ```text
+//! ;DO NOT DELETE THIS LINE*** version=1 ***
+//! vn:Việt Nam
+//! ko:không
+//! ```
+//!
+//! Mỗi dòng là `khóa:nội dung` (tách ở dấu `:` đầu tiên), dòng bắt đầu
+//! bằng `;` là chú thích. Mục không chuyển được được báo lại
+//! (`Diagnostic`) thay vì bị bỏ âm thầm.
+
+use crate::diagnostic::{Diagnostic, Layer, Origin, Severity};
+use crate::user_file::FileError;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use vikey_core::MacroTable;
+
+/// Dòng đầu file gõ tắt của UniKey (nội dung UTF-8)
+const UNIKEY_HEADER: &str = ";DO NOT DELETE THIS LINE*** version=1 ***";
+
+/// Dòng đầu file xuất gõ tắt của OpenKey
+const OPENKEY_HEADER: &str = ";Compatible OpenKey Macro Data file for UniKey*** version=1 ***";
+
+/// Độ dài tối đa của khóa trong UniKey (ký tự)
+const MAX_KEY_LEN: usize = 16;
+
+/// Độ dài tối đa của nội dung trong UniKey (ký tự)
+const MAX_TEXT_LEN: usize = 1024;
+
+/// Định dạng file gõ tắt của bộ gõ khác
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroFormat {
+    /// File gõ tắt của UniKey
+    UniKey,
+    /// File xuất gõ tắt của OpenKey
+    OpenKey,
+}
+
+impl MacroFormat {
+    /// Đoán định dạng theo dòng đầu của file
+    pub fn detect(text: &str) -> Option<Self> {
+        let first = strip_bom(text).lines().next()?.trim_end();
+        if first == OPENKEY_HEADER {
+            Some(MacroFormat::OpenKey)
+        } else if first.starts_with(";DO NOT DELETE THIS LINE") {
+            Some(MacroFormat::UniKey)
+        } else {
+            None
+        }
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            MacroFormat::UniKey => UNIKEY_HEADER,
+            MacroFormat::OpenKey => OPENKEY_HEADER,
+        }
+    }
+
+    /// Xuống dòng mà bộ gõ đó dùng khi ghi file
+    fn newline(self) -> &'static str {
+        match self {
+            MacroFormat::UniKey => "\r\n",
+            MacroFormat::OpenKey => "\n",
+        }
+    }
+}
+
+impl fmt::Display for MacroFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MacroFormat::UniKey => "UniKey",
+            MacroFormat::OpenKey => "OpenKey",
+        })
+    }
+}
+
+/// Bỏ BOM UTF-8 ở đầu
+fn strip_bom(text: &str) -> &str {
+    text.strip_prefix('\u{feff}').unwrap_or(text)
+}
+
+/// Giải mã nội dung file: UTF-8 (có hoặc không có BOM) hoặc UTF-16 có BOM
+///
+/// # Returns
+/// `None` nếu không giải mã được
+pub fn decode(bytes: &[u8]) -> Option<String> {
+    let utf16 = |bytes: &[u8], le: bool| {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| {
+                let pair = [pair[0], pair[1]];
+                if le {
+                    u16::from_le_bytes(pair)
+                } else {
+                    u16::from_be_bytes(pair)
+                }
+            })
+            .collect();
+        String::from_utf16(&units).ok()
+    };
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, true),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, false),
+        _ => std::str::from_utf8(bytes)
+            .ok()
+            .map(|text| strip_bom(text).to_string()),
+    }
+}
+
+/// Đọc file gõ tắt của UniKey hoặc OpenKey
+///
+/// File UniKey cũ (không có dòng phiên bản) lưu nội dung theo VIQR; các mục
+/// được nhập nguyên văn kèm một cảnh báo.
+///
+/// # Returns
+/// Các mục đọc được, và thông báo cho các mục bị bỏ
+pub fn read_macros(origin: &Origin, text: &str) -> (MacroTable, Vec<Diagnostic>) {
+    let mut table = MacroTable::new();
+    let mut diagnostics = Vec::new();
+    let text = strip_bom(text);
+
+    if MacroFormat::detect(text).is_none() && !text.trim().is_empty() {
+        diagnostics.push(Diagnostic::new(
+            Severity::Warning,
+            origin.clone(),
+            "no version header: written by an old UniKey in VIQR, entries imported as typed",
+        ));
+    }
+
+    for (index, line) in text.lines().enumerate() {
+        let report = |severity, key: Option<&str>, message: String| {
+            let mut diagnostic = Diagnostic::new(severity, origin.clone(), message);
+            diagnostic.line = Some(index + 1);
+            diagnostic.entry = key.map(str::to_string);
+            diagnostic
+        };
+
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        let Some((key, expansion)) = line.split_once(':') else {
+            diagnostics.push(report(
+                Severity::Error,
+                None,
+                "expected 'key:text'".to_string(),
+            ));
+            continue;
+        };
+        let key = key.trim();
+        let expansion = expansion.trim_end_matches('\r');
+        match table.insert(key, expansion) {
+            Ok(Some(_)) => diagnostics.push(report(
+                Severity::Warning,
+                Some(key),
+                "duplicate key, the last entry is used".to_string(),
+            )),
+            Ok(None) => {}
+            Err(e) => diagnostics.push(report(Severity::Error, Some(key), e.to_string())),
+        }
+    }
+    (table, diagnostics)
+}
+
+/// Ghi bảng gõ tắt theo định dạng `format`
+///
+/// Mục mà bộ gõ kia không đọc được (khóa có `:` hoặc dài quá, nội dung
+/// nhiều dòng hoặc dài quá) bị bỏ và được báo lại.
+///
+/// # Returns
+/// Nội dung file (UTF-8), và thông báo cho các mục bị bỏ
+pub fn write_macros(format: MacroFormat, table: &MacroTable) -> (String, Vec<Diagnostic>) {
+    let newline = format.newline();
+    let mut text = format!("{}{}", format.header(), newline);
+    let mut diagnostics = Vec::new();
+
+    for (key, expansion) in table.iter() {
+        let problem = if key.contains(':') {
+            Some("key contains ':'".to_string())
+        } else if key.starts_with(';') {
+            Some("key starts with ';'".to_string())
+        } else if key.chars().count() > MAX_KEY_LEN {
+            Some(format!("key is longer than {} characters", MAX_KEY_LEN))
+        } else if expansion.contains(['\n', '\r']) {
+            Some("text spans several lines".to_string())
+        } else if expansion.chars().count() > MAX_TEXT_LEN {
+            Some(format!("text is longer than {} characters", MAX_TEXT_LEN))
+        } else {
+            None
+        };
+
+        match problem {
+            Some(problem) => {
+                let mut diagnostic = Diagnostic::new(
+                    Severity::Error,
+                    Origin::new(Layer::Macros),
+                    format!("not supported by {}: {}", format, problem),
+                );
+                diagnostic.entry = Some(key.to_string());
+                diagnostics.push(diagnostic);
+            }
+            None => {
+                text.push_str(key);
+                text.push(':');
+                text.push_str(expansion);
+                text.push_str(newline);
+            }
+        }
+    }
+    (text, diagnostics)
+}
+
+/// Đọc file gõ tắt của UniKey hoặc OpenKey từ đĩa
+pub fn import_file(path: &Path) -> Result<(MacroTable, Vec<Diagnostic>), FileError> {
+    let bytes = fs::read(path).map_err(|error| FileError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let text = decode(&bytes).ok_or_else(|| FileError::Parse {
+        path: path.to_path_buf(),
+        message: "not a UTF-8 or UTF-16 text file".to_string(),
+    })?;
+    Ok(read_macros(&Origin::file(Layer::Macros, path), &text))
+}
+
+/// Ghi bảng gõ tắt ra file theo định dạng `format`
+///
+/// # Returns
+/// Thông báo cho các mục bị bỏ
+pub fn export_file(
+    path: &Path,
+    format: MacroFormat,
+    table: &MacroTable,
+) -> Result<Vec<Diagnostic>, FileError> {
+    let (text, mut diagnostics) = write_macros(format, table);
+    fs::write(path, text).map_err(|error| FileError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    for diagnostic in &mut diagnostics {
+        diagnostic.origin = Origin::file(Layer::Macros, path);
+    }
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origin() -> Origin {
+        Origin::file(Layer::Macros, "macro.txt")
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            MacroFormat::detect("\u{feff};DO NOT DELETE THIS LINE*** version=1 ***\r\nvn:VN\r\n"),
+            Some(MacroFormat::UniKey)
+        );
+        assert_eq!(
+            MacroFormat::detect(&format!("{}\nvn:VN\n", OPENKEY_HEADER)),
+            Some(MacroFormat::OpenKey)
+        );
+        assert_eq!(MacroFormat::detect("vn:VN\n"), None);
+    }
+
+    #[test]
+    fn test_read_reports_bad_lines() {
+        let text = ";DO NOT DELETE THIS LINE*** version=1 ***\r\n\
+                    vn:Việt Nam\r\n\
+                    ; chú thích\r\n\
+                    không có dấu hai chấm\r\n\
+                    tp:Thành phố: Hồ Chí Minh\r\n\
+                    ko:\r\n\
+                    vn:Việt Nam!\r\n";
+        let (table, diagnostics) = read_macros(&origin(), text);
+
+        assert_eq!(table.get("vn"), Some("Việt Nam!"));
+        assert_eq!(table.get("tp"), Some("Thành phố: Hồ Chí Minh"));
+        assert_eq!(table.len(), 2);
+        let messages: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "macro.txt:4: error: expected 'key:text'",
+                "macro.txt:6: error: ko: Shorthand 'ko' has an empty expansion",
+                "macro.txt:7: warning: vn: duplicate key, the last entry is used",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_legacy_file_warns() {
+        let (table, diagnostics) = read_macros(&origin(), "ko:kho^ng\n");
+        assert_eq!(table.get("ko"), Some("kho^ng"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut table = MacroTable::new();
+        table.insert("vn", "Việt Nam").unwrap();
+        table.insert("đc", "được").unwrap();
+        table.insert("tp", "Thành phố: Hồ Chí Minh").unwrap();
+
+        for format in [MacroFormat::UniKey, MacroFormat::OpenKey] {
+            let (text, diagnostics) = write_macros(format, &table);
+            assert!(diagnostics.is_empty());
+            assert_eq!(MacroFormat::detect(&text), Some(format));
+            let (read, diagnostics) = read_macros(&origin(), &text);
+            assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+            assert_eq!(read, table);
+        }
+    }
+
+    #[test]
+    fn test_write_reports_unsupported() {
+        let mut table = MacroTable::new();
+        table.insert("a:b", "x").unwrap();
+        table.insert("dia-chi-cong-ty-abc", "x").unwrap();
+        table.insert("ck", "Trân trọng,\nAn").unwrap();
+        table.insert("vn", "Việt Nam").unwrap();
+
+        let (text, diagnostics) = write_macros(MacroFormat::UniKey, &table);
+        assert_eq!(text, format!("{}\r\nvn:Việt Nam\r\n", UNIKEY_HEADER));
+        let entries: Vec<_> = diagnostics
+            .iter()
+            .map(|d| d.entry.as_deref().unwrap())
+            .collect();
+        assert_eq!(entries, vec!["a:b", "ck", "dia-chi-cong-ty-abc"]);
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "macro file: error: ck: not supported by UniKey: text spans several lines"
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let text = ";x\r\nvn:Việt Nam\r\n";
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(decode(&utf16).as_deref(), Some(text));
+
+        let mut utf8 = "\u{feff}".as_bytes().to_vec();
+        utf8.extend(text.as_bytes());
+        assert_eq!(decode(&utf8).as_deref(), Some(text));
+        assert_eq!(decode(&[0xC3, 0x28]), None);
+    }
+}
@@ -0,0 +1,51 @@
+//! Vikey Config - Settings Files
+//!
+//! File cấu hình dùng chung cho tray app và broker:
+//!
+//! - Schema có phiên bản (`Settings`, `CURRENT_VERSION`); file cũ được tự
+//!   động nâng cấp (`migrate`), kể cả `settings.toml` phẳng của tray app
+//!   trước đây
+//! - Nhiều lớp: mặc định hệ thống → file của user → profile đang chọn
+//!   (`[profiles.<tên>]`), lớp sau ghi đè từng khóa của lớp trước
+//! - Mục sai được bỏ qua riêng lẻ và báo lại kèm file, dòng, cột
+//!   (`Diagnostic`), các mục còn lại vẫn được dùng
+//! - Theo dõi file và tải lại khi có thay đổi (`Watcher`)
+//! - Bảng gõ tắt của user trong file riêng (`MacroFile`), nhập/xuất theo
+//!   định dạng của UniKey và OpenKey (`interop`)
+//!
+//! ```toml
+//! version = 2
+//! profile = "work"
+//!
+//! [engine]
+//! language = "vietnamese"
+//! input_method = "telex_v2"
+//!
+//! [engine.plugins.vietnamese]
+//! auto_restore = "off"
+//!
+//! [app]
+//! startup = true
+//!
+//! [profiles.work.engine]
+//! input_method = "vni"
+//! ```
+
+pub mod diagnostic;
+pub mod interop;
+pub mod loader;
+pub mod macros;
+pub mod migrate;
+pub mod schema;
+pub mod user_file;
+pub mod watch;
+
+// Re-exports
+pub use diagnostic::{Diagnostic, Layer, Origin, Severity};
+pub use interop::MacroFormat;
+pub use loader::{resolve, Loaded, Loader};
+pub use macros::{parse_macros, MacroFile, MACRO_VERSION};
+pub use migrate::{migrate, CURRENT_VERSION};
+pub use schema::{AppSettings, Settings};
+pub use user_file::{FileError, UserFile};
+pub use watch::Watcher;
@@ -0,0 +1,707 @@
+//! Vikey Config - Loader
+//!
+//! Đọc và gộp các lớp cấu hình: mặc định hệ thống → file của user →
+//! profile đang chọn. Mỗi lớp được nâng cấp lên phiên bản hiện tại và kiểm
+//! tra theo schema trước khi gộp; mục sai bị bỏ (kèm `Diagnostic`), phần
+//! còn lại của file vẫn được dùng.
+
+use crate::diagnostic::{Diagnostic, Layer, Origin, Severity};
+use crate::macros;
+use crate::migrate::{self, CURRENT_VERSION};
+use crate::schema::{FileSchema, OptionTables, Settings};
+use crate::user_file::{self, FileError};
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+use vikey_core::{MacroTable, OptionSchema, OptionSpec, OptionValue};
+
+/// Kết quả đọc cấu hình
+#[derive(Debug, Clone)]
+pub struct Loaded {
+    /// Cấu hình đã gộp
+    pub settings: Settings,
+
+    /// Các thông báo, theo thứ tự lớp
+    pub diagnostics: Vec<Diagnostic>,
+
+    /// Tên các profile có trong các lớp
+    pub profiles: Vec<String>,
+
+    /// Bảng gõ tắt của user
+    pub macros: MacroTable,
+}
+
+impl Loaded {
+    /// Có mục cấu hình nào bị bỏ qua không?
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+}
+
+/// Vị trí các file cấu hình và profile cần dùng
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Loader {
+    /// Mặc định hệ thống
+    system_file: Option<PathBuf>,
+
+    /// File của user
+    user_file: Option<PathBuf>,
+
+    /// `settings.toml` cũ của tray app, đọc khi chưa có file của user
+    legacy_file: Option<PathBuf>,
+
+    /// File gõ tắt của user
+    macro_file: Option<PathBuf>,
+
+    /// Thư mục chứa các bộ gõ do user định nghĩa
+    schema_dir: Option<PathBuf>,
+
+    /// Profile chọn từ bên ngoài (ghi đè khóa `profile` trong file)
+    profile: Option<String>,
+
+    /// Tùy chọn của các plugin và stage, để kiểm tra giá trị trong file
+    options: OptionSchema,
+}
+
+impl Loader {
+    /// Loader với các đường dẫn mặc định của hệ điều hành
+    ///
+    /// - Hệ thống: `%PROGRAMDATA%\vikey\config.toml` (Windows),
+    ///   `/Library/Application Support/vikey/config.toml` (macOS),
+    ///   `/etc/vikey/config.toml` (Linux)
+    /// - User: `vikey/config.toml` trong thư mục cấu hình của user
+    ///   (`%APPDATA%`, `~/Library/Application Support`, `~/.config`)
+    /// - File cũ: `vikey/settings.toml` trong thư mục dữ liệu của user
+    /// - Gõ tắt: `vikey/macros.toml` trong thư mục cấu hình của user
+    /// - Bộ gõ của user: `vikey/schemas/` trong thư mục cấu hình của user
+    pub fn new() -> Self {
+        let user_dir = dirs::config_dir().map(|dir| dir.join("vikey"));
+        Self {
+            system_file: default_system_file(),
+            user_file: user_dir.as_ref().map(|dir| dir.join("config.toml")),
+            legacy_file: dirs::data_dir().map(|dir| dir.join("vikey").join("settings.toml")),
+            macro_file: user_dir.as_ref().map(|dir| dir.join("macros.toml")),
+            schema_dir: user_dir.map(|dir| dir.join("schemas")),
+            profile: None,
+            options: OptionSchema::default(),
+        }
+    }
+
+    /// Đặt file mặc định hệ thống (builder)
+    pub fn with_system_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.system_file = Some(path.into());
+        self
+    }
+
+    /// Đặt file của user (builder)
+    pub fn with_user_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.user_file = Some(path.into());
+        self
+    }
+
+    /// Đặt file cũ của tray app (builder)
+    pub fn with_legacy_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.legacy_file = Some(path.into());
+        self
+    }
+
+    /// Đặt file gõ tắt (builder)
+    pub fn with_macro_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.macro_file = Some(path.into());
+        self
+    }
+
+    /// Đặt thư mục bộ gõ của user (builder)
+    pub fn with_schema_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.schema_dir = Some(path.into());
+        self
+    }
+
+    /// Dùng profile `name`, bất kể khóa `profile` trong file (builder)
+    pub fn with_profile(mut self, name: impl Into<String>) -> Self {
+        self.profile = Some(name.into());
+        self
+    }
+
+    /// Kiểm tra giá trị trong `[engine.plugins.*]` và `[engine.stages.*]`
+    /// theo `options` (thường là `Engine::option_schema`) (builder)
+    ///
+    /// Plugin và stage không có trong `options` không được kiểm tra.
+    pub fn with_options(mut self, options: OptionSchema) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// File mặc định hệ thống
+    pub fn system_file(&self) -> Option<&Path> {
+        self.system_file.as_deref()
+    }
+
+    /// File của user (nơi tray app ghi thay đổi)
+    pub fn user_file(&self) -> Option<&Path> {
+        self.user_file.as_deref()
+    }
+
+    /// File cũ của tray app
+    pub fn legacy_file(&self) -> Option<&Path> {
+        self.legacy_file.as_deref()
+    }
+
+    /// File gõ tắt (nơi tray app ghi thay đổi)
+    pub fn macro_file(&self) -> Option<&Path> {
+        self.macro_file.as_deref()
+    }
+
+    /// Thư mục bộ gõ của user (file `.toml`, `.yaml`, đọc khi khởi động)
+    pub fn schema_dir(&self) -> Option<&Path> {
+        self.schema_dir.as_deref()
+    }
+
+    /// Tùy chọn của các plugin và stage dùng để kiểm tra file
+    pub fn options(&self) -> &OptionSchema {
+        &self.options
+    }
+
+    /// Các file có thể ảnh hưởng tới cấu hình (để theo dõi thay đổi)
+    pub(crate) fn files(&self) -> impl Iterator<Item = &Path> {
+        [
+            &self.system_file,
+            &self.user_file,
+            &self.legacy_file,
+            &self.macro_file,
+        ]
+        .into_iter()
+        .filter_map(|path| path.as_deref())
+    }
+
+    /// File được đọc làm lớp của user: file mới, hoặc file cũ nếu chưa có
+    fn user_source(&self) -> Option<&Path> {
+        let user = self.user_file.as_deref();
+        match user {
+            Some(path) if path.exists() => Some(path),
+            _ => self
+                .legacy_file
+                .as_deref()
+                .filter(|path| path.exists())
+                .or(user),
+        }
+    }
+
+    /// Đọc và gộp các lớp
+    ///
+    /// File không tồn tại được bỏ qua; file không đọc được hoặc sai cú
+    /// pháp được báo trong `diagnostics`.
+    pub fn load(&self) -> Loaded {
+        let mut diagnostics = Vec::new();
+        let mut layers = Vec::new();
+
+        let sources = [
+            (Layer::System, self.system_file.as_deref()),
+            (Layer::User, self.user_source()),
+        ];
+        for (layer, path) in sources {
+            let Some(path) = path else {
+                continue;
+            };
+            let origin = Origin::file(layer, path);
+            match fs::read_to_string(path) {
+                Ok(text) => layers.push((origin, text)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    origin,
+                    format!("cannot read file: {}", e),
+                )),
+            }
+        }
+
+        let layers: Vec<(Origin, &str)> = layers
+            .iter()
+            .map(|(origin, text)| (origin.clone(), text.as_str()))
+            .collect();
+        let mut loaded = resolve(&layers, self.profile.as_deref(), &self.options);
+        diagnostics.append(&mut loaded.diagnostics);
+
+        if let Some(path) = &self.macro_file {
+            let origin = Origin::file(Layer::Macros, path);
+            match fs::read_to_string(path) {
+                Ok(text) => {
+                    let (table, mut errors) = macros::parse_macros(&origin, &text);
+                    loaded.macros = table;
+                    diagnostics.append(&mut errors);
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    origin,
+                    format!("cannot read file: {}", e),
+                )),
+            }
+        }
+
+        loaded.diagnostics = diagnostics;
+        loaded
+    }
+
+    /// Ghi lại file của user ở phiên bản hiện tại
+    ///
+    /// Nếu chưa có file của user, file cũ của tray app được chuyển sang
+    /// (file cũ giữ nguyên). File của user ở phiên bản cũ được sao lưu
+    /// thành `config.toml.v<phiên bản>.bak` trước khi ghi đè.
+    ///
+    /// # Returns
+    /// Phiên bản ban đầu nếu có file được nâng cấp
+    pub fn upgrade(&self) -> Result<Option<u32>, FileError> {
+        let Some(user) = &self.user_file else {
+            return Ok(None);
+        };
+        let Some(source) = self.user_source().filter(|path| path.exists()) else {
+            return Ok(None);
+        };
+
+        let mut table = user_file::read_table(source)?;
+        let from = migrate::migrate(&mut table);
+        let from_user = source == user.as_path();
+        if from_user && from >= CURRENT_VERSION {
+            return Ok(None);
+        }
+
+        if from_user {
+            let backup = user.with_extension(format!("toml.v{}.bak", from));
+            fs::copy(user, &backup).map_err(|error| FileError::Io {
+                path: backup.clone(),
+                error,
+            })?;
+        }
+        user_file::write_table(user, &table)?;
+        Ok(Some(from))
+    }
+}
+
+/// File mặc định hệ thống của nền tảng hiện tại
+fn default_system_file() -> Option<PathBuf> {
+    if cfg!(windows) {
+        std::env::var_os("PROGRAMDATA")
+            .map(|dir| PathBuf::from(dir).join("vikey").join("config.toml"))
+    } else if cfg!(target_os = "macos") {
+        Some(PathBuf::from(
+            "/Library/Application Support/vikey/config.toml",
+        ))
+    } else {
+        Some(PathBuf::from("/etc/vikey/config.toml"))
+    }
+}
+
+/// Gộp các lớp (theo thứ tự, lớp sau ghi đè lớp trước) rồi áp dụng profile
+///
+/// `profile` ghi đè khóa `profile` trong các lớp; giá trị tùy chọn của
+/// plugin và stage được kiểm tra theo `options`. Hàm không đọc file nào,
+/// dùng được với cấu hình từ nguồn bất kỳ.
+pub fn resolve(layers: &[(Origin, &str)], profile: Option<&str>, options: &OptionSchema) -> Loaded {
+    let mut diagnostics = Vec::new();
+    let mut merged = Table::new();
+    for (origin, text) in layers {
+        if let Some(table) = check_layer(origin, text, options, &mut diagnostics) {
+            merge(&mut merged, table);
+        }
+    }
+
+    // Profile: ghi đè `engine` / `app` lên phần còn lại
+    let profiles = match merged.remove("profiles") {
+        Some(Value::Table(profiles)) => profiles,
+        _ => Table::new(),
+    };
+    let active = profile
+        .map(str::to_string)
+        .or_else(|| merged.get("profile")?.as_str().map(str::to_string));
+    if let Some(name) = &active {
+        match profiles.get(name) {
+            Some(Value::Table(overrides)) => merge(&mut merged, overrides.clone()),
+            _ => {
+                let origin = layers
+                    .last()
+                    .map(|(origin, _)| origin.clone())
+                    .unwrap_or(Origin::new(Layer::User));
+                let mut diagnostic = Diagnostic::new(
+                    Severity::Warning,
+                    origin,
+                    format!("profile '{}' not found, using the base settings", name),
+                );
+                diagnostic.entry = Some("profile".to_string());
+                diagnostics.push(diagnostic);
+            }
+        }
+        merged.insert("profile".to_string(), Value::String(name.clone()));
+    }
+    merged.insert(
+        "version".to_string(),
+        Value::Integer(CURRENT_VERSION.into()),
+    );
+
+    let settings = match Value::Table(merged).try_into::<Settings>() {
+        Ok(settings) => settings,
+        Err(e) => {
+            let origin = Origin::new(Layer::User);
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                origin,
+                format!("{}; using the default settings", e.message()),
+            ));
+            Settings::default()
+        }
+    };
+
+    Loaded {
+        settings,
+        diagnostics,
+        profiles: profiles.keys().cloned().collect(),
+        macros: MacroTable::new(),
+    }
+}
+
+/// Nâng cấp và kiểm tra một lớp, bỏ các mục sai
+///
+/// # Returns
+/// Các mục hợp lệ, hoặc `None` nếu cả lớp bị bỏ (sai cú pháp)
+fn check_layer(
+    origin: &Origin,
+    text: &str,
+    options: &OptionSchema,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Table> {
+    let mut table: Table = match toml::from_str(text) {
+        Ok(table) => table,
+        Err(e) => {
+            let mut diagnostic = error_at(origin, text, e.span(), e.message(), true);
+            diagnostic.message = format!("{}; file ignored", diagnostic.message);
+            diagnostics.push(diagnostic);
+            return None;
+        }
+    };
+
+    // Sau khi nâng cấp, vị trí trong file gốc không còn đúng
+    let from = migrate::migrate(&mut table);
+    let (mut source, has_lines) = if from < CURRENT_VERSION {
+        diagnostics.push(Diagnostic::new(
+            Severity::Info,
+            origin.clone(),
+            format!(
+                "upgraded from version {} to version {}",
+                from, CURRENT_VERSION
+            ),
+        ));
+        (toml::to_string(&table).ok()?, false)
+    } else {
+        if from > CURRENT_VERSION {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                origin.clone(),
+                format!(
+                    "written for version {}, newer than this Vikey (version {})",
+                    from, CURRENT_VERSION
+                ),
+            ));
+        }
+        (text.to_string(), true)
+    };
+
+    // Mỗi lần bỏ một mục sai rồi kiểm tra lại
+    while let Err(e) = toml::from_str::<FileSchema>(&source) {
+        diagnostics.push(error_at(origin, &source, e.span(), e.message(), has_lines));
+        let removed = e.span().is_some_and(|span| blank_entry(&mut source, span));
+        if !removed {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                origin.clone(),
+                "file ignored",
+            ));
+            return None;
+        }
+    }
+
+    // Giá trị tùy chọn của plugin và stage, theo schema đã đăng ký
+    while let Some((span, message)) = invalid_option(&source, options) {
+        diagnostics.push(error_at(
+            origin,
+            &source,
+            Some(span.clone()),
+            &message,
+            has_lines,
+        ));
+        if !blank_entry(&mut source, span) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                origin.clone(),
+                "file ignored",
+            ));
+            return None;
+        }
+    }
+    toml::from_str(&source).ok()
+}
+
+/// Giá trị tùy chọn plugin/stage sai đầu tiên trong `source`
+///
+/// # Returns
+/// Vị trí của giá trị và thông báo lỗi
+fn invalid_option(source: &str, options: &OptionSchema) -> Option<(Range<usize>, String)> {
+    let tables: OptionTables = toml::from_str(source).ok()?;
+    let engines = std::iter::once(&tables.engine)
+        .chain(tables.profiles.values().map(|profile| &profile.engine));
+    for engine in engines {
+        let groups = [
+            (&engine.plugins, &options.plugins),
+            (&engine.stages, &options.stages),
+        ];
+        for (tables, schema) in groups {
+            for (id, values) in tables {
+                let Some(specs) = schema.get(id) else {
+                    continue;
+                };
+                for (key, value) in values {
+                    if let Err(message) = check_option(specs, key, value.get_ref()) {
+                        return Some((value.span(), message));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Kiểm tra một giá trị theo tùy chọn tên `key` trong `specs`
+fn check_option(specs: &[OptionSpec], key: &str, value: &Value) -> Result<(), String> {
+    let Some(spec) = specs.iter().find(|spec| spec.key == key) else {
+        let keys: Vec<_> = specs.iter().map(|spec| format!("`{}`", spec.key)).collect();
+        return Err(if keys.is_empty() {
+            format!("unknown option `{}`, there are no options", key)
+        } else {
+            format!(
+                "unknown option `{}`, expected one of {}",
+                key,
+                keys.join(", ")
+            )
+        });
+    };
+    let value: OptionValue = value.clone().try_into().map_err(|e| e.to_string())?;
+    spec.validate(&value)
+}
+
+/// Thông báo lỗi tại vị trí `span` của `text`
+pub(crate) fn error_at(
+    origin: &Origin,
+    text: &str,
+    span: Option<Range<usize>>,
+    message: &str,
+    has_lines: bool,
+) -> Diagnostic {
+    let mut diagnostic = Diagnostic::new(Severity::Error, origin.clone(), message.trim_end());
+    if let Some(span) = span {
+        if has_lines {
+            let before = text.get(..span.start).unwrap_or(text);
+            diagnostic.line = Some(before.matches('\n').count() + 1);
+            diagnostic.column = Some(before.rsplit('\n').next().unwrap_or("").chars().count() + 1);
+        }
+        diagnostic.entry = entry_at(text, span.start);
+    }
+    diagnostic
+}
+
+/// Byte đầu của dòng chứa `offset`
+fn line_start(text: &str, offset: usize) -> usize {
+    text.get(..offset)
+        .and_then(|before| before.rfind('\n'))
+        .map_or(0, |i| i + 1)
+}
+
+/// Byte cuối (không gồm '\n') của dòng chứa `offset`
+fn line_end(text: &str, offset: usize) -> usize {
+    text.get(offset..)
+        .and_then(|after| after.find('\n'))
+        .map_or(text.len(), |i| offset + i)
+}
+
+/// Tên bảng trong một dòng header ("[engine.keys]" → "engine.keys")
+fn header_name(line: &str) -> Option<&str> {
+    let line = line.trim();
+    line.starts_with('[').then(|| {
+        line.trim_start_matches('[')
+            .split(']')
+            .next()
+            .unwrap_or("")
+            .trim()
+    })
+}
+
+/// Khóa đầy đủ của mục ở dòng chứa `offset` (ví dụ: "engine.keys.undo")
+fn entry_at(text: &str, offset: usize) -> Option<String> {
+    let start = line_start(text, offset);
+    let line = &text[start..line_end(text, start)];
+    if let Some(name) = header_name(line) {
+        return Some(name.to_string());
+    }
+
+    let key = line.split('=').next()?.trim();
+    if key.is_empty() {
+        return None;
+    }
+    let table = text[..start].lines().rev().find_map(header_name);
+    Some(match table {
+        Some(table) => format!("{}.{}", table, key),
+        None => key.to_string(),
+    })
+}
+
+/// Xóa (thay bằng khoảng trắng) mục chứa `span`, giữ nguyên vị trí các dòng
+///
+/// Mục là một header bảng thì cả bảng bị xóa.
+///
+/// # Returns
+/// `false` nếu không có gì để xóa
+fn blank_entry(source: &mut String, span: Range<usize>) -> bool {
+    let start = line_start(source, span.start);
+    let mut end = line_end(source, span.end.saturating_sub(1).max(span.start));
+    if header_name(&source[start..end]).is_some() {
+        while end < source.len() {
+            let next = line_end(source, end + 1);
+            if header_name(&source[end + 1..next]).is_some() {
+                break;
+            }
+            end = next;
+        }
+    }
+    if source[start..end].trim().is_empty() {
+        return false;
+    }
+
+    // Chỉ thay cả dòng bằng ký tự ASCII nên chuỗi vẫn là UTF-8 hợp lệ
+    let mut bytes = std::mem::take(source).into_bytes();
+    for byte in &mut bytes[start..end] {
+        if *byte != b'\n' {
+            *byte = b' ';
+        }
+    }
+    *source = String::from_utf8(bytes).unwrap_or_default();
+    true
+}
+
+/// Gộp `overlay` vào `base`: bảng được gộp đệ quy, giá trị khác bị thay
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(text: &str) -> Loaded {
+        resolve(
+            &[(Origin::new(Layer::User), text)],
+            None,
+            &OptionSchema::default(),
+        )
+    }
+
+    #[test]
+    fn test_invalid_entries_are_dropped() {
+        let loaded = user(
+            "[engine]\nshorthand = true\nenabeld = false\n\n[engine.keys]\nundo = \"Hyper+Z\"\nredo = \"Ctrl+R\"\n",
+        );
+
+        assert!(loaded.settings.engine.shorthand);
+        assert_eq!(loaded.settings.engine.keys.redo, "Ctrl+R".parse().ok());
+        assert_eq!(
+            loaded.settings.engine.keys.undo,
+            vikey_core::KeysConfig::default().undo
+        );
+
+        let errors: Vec<_> = loaded
+            .diagnostics
+            .iter()
+            .map(|d| (d.line, d.column, d.entry.as_deref()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (Some(3), Some(1), Some("engine.enabeld")),
+                (Some(6), Some(8), Some("engine.keys.undo")),
+            ]
+        );
+        assert!(loaded.diagnostics[1].message.contains("Hyper+Z"));
+    }
+
+    #[test]
+    fn test_unknown_table_is_dropped() {
+        let loaded =
+            user("[engine]\nshorthand = true\n\n[ui]\ntheme = \"dark\"\n\n[app]\nstartup = true\n");
+        assert!(loaded.settings.engine.shorthand);
+        assert!(loaded.settings.app.startup);
+        assert_eq!(loaded.diagnostics.len(), 1);
+        assert_eq!(loaded.diagnostics[0].entry.as_deref(), Some("ui"));
+    }
+
+    #[test]
+    fn test_syntax_error_ignores_layer() {
+        let loaded = user("[engine\nshorthand = true\n");
+        assert_eq!(loaded.settings, Settings::default());
+        assert_eq!(loaded.diagnostics.len(), 1);
+        assert_eq!(loaded.diagnostics[0].line, Some(1));
+        assert!(loaded.diagnostics[0].message.ends_with("file ignored"));
+    }
+
+    #[test]
+    fn test_stage_options_follow_the_schema() {
+        let mut options = OptionSchema::default();
+        options.stages.insert(
+            "shorthand".to_string(),
+            vec![OptionSpec::bool("enabled", true, "Bật stage")],
+        );
+        let text = "[engine.stages.shorthand]\nenabled = 1\n\n[engine.stages.other]\nlevel = 3\n\n[profiles.work.engine.stages.shorthand]\nenabeld = false\n";
+        let loaded = resolve(&[(Origin::new(Layer::User), text)], None, &options);
+
+        let errors: Vec<_> = loaded
+            .diagnostics
+            .iter()
+            .map(|d| (d.line, d.entry.as_deref()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (Some(2), Some("engine.stages.shorthand.enabled")),
+                (
+                    Some(8),
+                    Some("profiles.work.engine.stages.shorthand.enabeld")
+                ),
+            ]
+        );
+        assert!(loaded.diagnostics[1]
+            .message
+            .contains("expected one of `enabled`"));
+        assert_eq!(
+            loaded.settings.engine.stage_option("other", "level"),
+            Some(&3_i64.into())
+        );
+        assert_eq!(
+            loaded.settings.engine.stage_option("shorthand", "enabled"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut base: Table = toml::from_str("a = 1\n[t]\nx = 1\ny = 2\n").unwrap();
+        merge(&mut base, toml::from_str("a = 2\n[t]\ny = 3\n").unwrap());
+        assert_eq!(base, toml::from_str("a = 2\n[t]\nx = 1\ny = 3\n").unwrap());
+    }
+}
@@ -0,0 +1,235 @@
+//! Vikey Config - Macro File
+//!
+//! File gõ tắt của user (`macros.toml`, cạnh file cấu hình), đọc thành
+//! `MacroTable` cho stage gõ tắt của Engine. Mục sai được bỏ qua riêng lẻ
+//! và báo lại kèm dòng, cột như file cấu hình.
+//!
+//! ```toml
+//! version = 1
+//!
+//! [macros]
+//! vn = "Việt Nam"
+//! "đc" = "được"
+//! ```
+
+use crate::diagnostic::{Diagnostic, Origin, Severity};
+use crate::loader;
+use crate::user_file::{read_table, write_table, FileError};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml::{Spanned, Table, Value};
+use vikey_core::{MacroError, MacroTable};
+
+/// Phiên bản hiện tại của file gõ tắt
+pub const MACRO_VERSION: u32 = 1;
+
+/// Nội dung file gõ tắt, giữ vị trí từng mục
+#[derive(Deserialize)]
+struct MacroSchema {
+    #[serde(default)]
+    version: Option<Spanned<u32>>,
+
+    #[serde(default)]
+    macros: BTreeMap<Spanned<String>, Spanned<Value>>,
+}
+
+/// Đọc bảng gõ tắt từ nội dung file
+///
+/// # Returns
+/// Các mục hợp lệ, và thông báo cho các mục bị bỏ
+pub fn parse_macros(origin: &Origin, text: &str) -> (MacroTable, Vec<Diagnostic>) {
+    let mut table = MacroTable::new();
+    let mut diagnostics = Vec::new();
+
+    let schema: MacroSchema = match toml::from_str(text) {
+        Ok(schema) => schema,
+        Err(e) => {
+            let mut diagnostic = loader::error_at(origin, text, e.span(), e.message(), true);
+            diagnostic.message = format!("{}; file ignored", diagnostic.message);
+            diagnostics.push(diagnostic);
+            return (table, diagnostics);
+        }
+    };
+
+    if let Some(version) = schema.version.filter(|v| *v.get_ref() > MACRO_VERSION) {
+        let message = format!(
+            "written for version {}, newer than this Vikey (version {})",
+            version.get_ref(),
+            MACRO_VERSION
+        );
+        let mut diagnostic = loader::error_at(origin, text, Some(version.span()), &message, true);
+        diagnostic.severity = Severity::Warning;
+        diagnostics.push(diagnostic);
+    }
+
+    let mut errors = Vec::new();
+    for (key, value) in schema.macros {
+        let result = match value.get_ref() {
+            Value::String(expansion) => table
+                .insert(key.get_ref().as_str(), expansion.as_str())
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            other => Err(format!("expected a string, found {}", other.type_str())),
+        };
+        if let Err(message) = result {
+            let mut diagnostic = loader::error_at(origin, text, Some(key.span()), &message, true);
+            diagnostic.entry = Some(format!("macros.{}", key.get_ref()));
+            errors.push(diagnostic);
+        }
+    }
+    errors.sort_by_key(|diagnostic| diagnostic.line);
+    diagnostics.append(&mut errors);
+    (table, diagnostics)
+}
+
+/// File gõ tắt, mở để sửa
+///
+/// Các mục sai trong file được giữ nguyên khi ghi lại.
+#[derive(Debug, Clone)]
+pub struct MacroFile {
+    /// Đường dẫn file
+    path: PathBuf,
+
+    /// Nội dung
+    table: Table,
+}
+
+impl MacroFile {
+    /// Mở file (file chưa tồn tại được coi là rỗng)
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, FileError> {
+        let path = path.into();
+        let table = if path.exists() {
+            read_table(&path)?
+        } else {
+            Table::new()
+        };
+        Ok(Self { path, table })
+    }
+
+    /// Đường dẫn file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Các mục hợp lệ trong file
+    pub fn macros(&self) -> MacroTable {
+        let mut table = MacroTable::new();
+        for (key, value) in self.entries().into_iter().flatten() {
+            if let Some(expansion) = value.as_str() {
+                let _ = table.insert(key.as_str(), expansion);
+            }
+        }
+        table
+    }
+
+    /// Thêm (hoặc thay) một mục
+    ///
+    /// # Returns
+    /// Nội dung cũ của khóa (nếu có)
+    pub fn set(&mut self, key: &str, expansion: &str) -> Result<Option<String>, MacroError> {
+        MacroTable::validate(key, expansion)?;
+        let entries = self
+            .table
+            .entry("macros".to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !entries.is_table() {
+            *entries = Value::Table(Table::new());
+        }
+        let old = entries
+            .as_table_mut()
+            .and_then(|entries| entries.insert(key.to_string(), expansion.into()));
+        Ok(old.and_then(|value| value.as_str().map(str::to_string)))
+    }
+
+    /// Xóa một mục, trả về nội dung của nó
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let value = self.table.get_mut("macros")?.as_table_mut()?.remove(key)?;
+        value.as_str().map(str::to_string)
+    }
+
+    /// Thêm các mục của `macros` (ghi đè mục cùng khóa)
+    ///
+    /// # Returns
+    /// Số mục đã có trước và bị ghi đè
+    pub fn merge(&mut self, macros: &MacroTable) -> usize {
+        macros
+            .iter()
+            .filter(|(key, expansion)| matches!(self.set(key, expansion), Ok(Some(_))))
+            .count()
+    }
+
+    /// Thay toàn bộ các mục bằng `macros`
+    pub fn replace_all(&mut self, macros: &MacroTable) {
+        let entries = macros
+            .iter()
+            .map(|(key, expansion)| (key.to_string(), Value::from(expansion)))
+            .collect();
+        self.table
+            .insert("macros".to_string(), Value::Table(entries));
+    }
+
+    /// Ghi file (ở phiên bản hiện tại)
+    pub fn save(&mut self) -> Result<(), FileError> {
+        self.table
+            .insert("version".to_string(), Value::Integer(MACRO_VERSION.into()));
+        write_table(&self.path, &self.table)
+    }
+
+    fn entries(&self) -> Option<&Table> {
+        self.table.get("macros")?.as_table()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Layer;
+
+    #[test]
+    fn test_parse_reports_entries() {
+        let origin = Origin::file(Layer::Macros, "macros.toml");
+        let text = "version = 1\n\n[macros]\nvn = \"Việt Nam\"\n\"v n\" = \"x\"\nko = 1\n\"đc\" = \"được\"\n";
+        let (table, diagnostics) = parse_macros(&origin, text);
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get("đc"), Some("được"));
+        let messages: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "macros.toml:5:1: error: macros.v n: Shorthand key 'v n' contains spaces or control characters",
+                "macros.toml:6:1: error: macros.ko: expected a string, found integer",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let origin = Origin::file(Layer::Macros, "macros.toml");
+        let (table, diagnostics) = parse_macros(&origin, "[macros]\nvn = \n");
+        assert!(table.is_empty());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(2));
+
+        let (_, diagnostics) = parse_macros(&origin, "version = 7\n");
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut file = MacroFile {
+            path: PathBuf::from("macros.toml"),
+            table: toml::from_str("[macros]\nko = 1\nvn = \"VN\"\n").unwrap(),
+        };
+        assert_eq!(file.set("vn", "Việt Nam"), Ok(Some("VN".to_string())));
+        assert!(file.set("", "x").is_err());
+        assert_eq!(file.macros().len(), 1);
+        assert_eq!(file.macros().get("vn"), Some("Việt Nam"));
+
+        // Mục sai vẫn được giữ cho đến khi bị xóa
+        assert!(file.entries().unwrap().get("ko").is_some());
+        assert_eq!(file.remove("ko"), None);
+        assert!(file.entries().unwrap().get("ko").is_none());
+    }
+}
@@ -0,0 +1,149 @@
+//! Vikey Config - Migrations
+//!
+//! Nâng cấp file cấu hình cũ lên `CURRENT_VERSION`, từng phiên bản một.
+//!
+//! - Phiên bản 1: `settings.toml` phẳng của tray app (`input_method` là
+//!   "english" / "telex" / "vni", `auto_correct`, `shorthand`, `startup`),
+//!   không có khóa `version`
+//! - Phiên bản 2: `[engine]` (`vikey_core::Config`), `[app]`, `[profiles]`
+
+use toml::{Table, Value};
+
+/// Phiên bản schema hiện tại
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Khóa chỉ có ở file phiên bản 1
+const V1_KEYS: &[&str] = &["input_method", "auto_correct", "shorthand", "startup"];
+
+/// Một bước nâng cấp: phiên bản `n` → `n + 1`
+type Migration = fn(&mut Table);
+
+/// Các bước nâng cấp, phần tử `i` nâng phiên bản `i + 1`
+const MIGRATIONS: &[Migration] = &[v1_to_v2];
+
+/// Phiên bản của một file cấu hình
+///
+/// File không có khóa `version` là phiên bản 1 nếu có khóa của phiên bản 1,
+/// ngược lại là phiên bản hiện tại (ví dụ file rỗng).
+pub fn version_of(table: &Table) -> u32 {
+    match table.get("version") {
+        Some(Value::Integer(n)) => u32::try_from(*n).unwrap_or(u32::MAX),
+        Some(_) => CURRENT_VERSION,
+        None if V1_KEYS.iter().any(|key| table.contains_key(*key)) => 1,
+        None => CURRENT_VERSION,
+    }
+}
+
+/// Nâng cấp `table` lên `CURRENT_VERSION`
+///
+/// # Returns
+/// Phiên bản ban đầu của file. File mới hơn `CURRENT_VERSION` được giữ
+/// nguyên.
+pub fn migrate(table: &mut Table) -> u32 {
+    let from = version_of(table);
+    for version in from.max(1)..CURRENT_VERSION {
+        MIGRATIONS[version as usize - 1](table);
+        table.insert("version".to_string(), Value::Integer(version as i64 + 1));
+    }
+    from
+}
+
+/// Phiên bản 1 → 2: chuyển các khóa phẳng của tray app vào `[engine]` và
+/// `[app]`
+///
+/// Khóa không nhận ra được giữ nguyên (và sẽ được báo là không hợp lệ).
+fn v1_to_v2(table: &mut Table) {
+    let mut engine = Table::new();
+    let mut app = Table::new();
+
+    if let Some(method) = table.remove("input_method") {
+        // "english" nghĩa là tắt bộ gõ
+        if method.as_str() == Some("english") {
+            engine.insert("enabled".to_string(), Value::Boolean(false));
+        } else {
+            engine.insert("language".to_string(), "vietnamese".into());
+            engine.insert("input_method".to_string(), method);
+        }
+    }
+    if let Some(shorthand) = table.remove("shorthand") {
+        engine.insert("shorthand".to_string(), shorthand);
+    }
+    if let Some(auto_correct) = table.remove("auto_correct") {
+        let mode = match auto_correct.as_bool() {
+            Some(false) => "off",
+            _ => "restore_on_boundary",
+        };
+        let mut vietnamese = Table::new();
+        vietnamese.insert("auto_restore".to_string(), mode.into());
+        let mut plugins = Table::new();
+        plugins.insert("vietnamese".to_string(), Value::Table(vietnamese));
+        engine.insert("plugins".to_string(), Value::Table(plugins));
+    }
+    if let Some(startup) = table.remove("startup") {
+        app.insert("startup".to_string(), startup);
+    }
+
+    if !engine.is_empty() {
+        table.insert("engine".to_string(), Value::Table(engine));
+    }
+    if !app.is_empty() {
+        table.insert("app".to_string(), Value::Table(app));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_of() {
+        assert_eq!(version_of(&Table::new()), CURRENT_VERSION);
+        assert_eq!(version_of(&toml::from_str("shorthand = true").unwrap()), 1);
+        assert_eq!(version_of(&toml::from_str("version = 7").unwrap()), 7);
+    }
+
+    #[test]
+    fn test_v1_to_v2() {
+        let mut table: Table = toml::from_str(
+            r#"
+            input_method = "vni"
+            auto_correct = false
+            shorthand = true
+            startup = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(migrate(&mut table), 1);
+        let expected: Table = toml::from_str(
+            r#"
+            version = 2
+
+            [engine]
+            language = "vietnamese"
+            input_method = "vni"
+            shorthand = true
+
+            [engine.plugins.vietnamese]
+            auto_restore = "off"
+
+            [app]
+            startup = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(table, expected);
+
+        // Already current: untouched
+        assert_eq!(migrate(&mut table), CURRENT_VERSION);
+        assert_eq!(table, expected);
+    }
+
+    #[test]
+    fn test_v1_english_disables_engine() {
+        let mut table: Table = toml::from_str("input_method = \"english\"").unwrap();
+        migrate(&mut table);
+        assert_eq!(table["engine"]["enabled"].as_bool(), Some(false));
+        assert!(table["engine"].get("input_method").is_none());
+    }
+}
@@ -0,0 +1,92 @@
+//! Vikey Config - Schema
+//!
+//! Cấu trúc của file cấu hình (phiên bản `CURRENT_VERSION`).
+
+use crate::migrate::CURRENT_VERSION;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toml::{Spanned, Value};
+use vikey_core::Config;
+
+/// Cấu hình đã gộp từ các lớp, dùng bởi tray app và broker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Phiên bản schema
+    pub version: u32,
+
+    /// Profile đang dùng (`None`: không dùng profile nào)
+    pub profile: Option<String>,
+
+    /// Cấu hình Engine
+    pub engine: Config,
+
+    /// Tùy chọn riêng của tray app
+    pub app: AppSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            profile: None,
+            engine: Config::default(),
+            app: AppSettings::default(),
+        }
+    }
+}
+
+/// Tùy chọn riêng của tray app
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppSettings {
+    /// Tự khởi động cùng hệ điều hành
+    pub startup: bool,
+}
+
+/// Nội dung một file cấu hình (một lớp), chỉ dùng để kiểm tra schema
+///
+/// Khác `Settings` ở chỗ có thêm bảng `profiles`.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[allow(dead_code)]
+pub(crate) struct FileSchema {
+    version: u32,
+    profile: Option<String>,
+    engine: Config,
+    app: AppSettings,
+    profiles: BTreeMap<String, ProfileSchema>,
+}
+
+/// Một profile: ghi đè một phần `engine` và `app`
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[allow(dead_code)]
+pub(crate) struct ProfileSchema {
+    engine: Config,
+    app: AppSettings,
+}
+
+/// Giá trị tùy chọn plugin và stage trong một file, kèm vị trí, để kiểm tra
+/// theo `OptionSchema` của các plugin đã đăng ký
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct OptionTables {
+    pub engine: EngineOptionTables,
+    pub profiles: BTreeMap<String, ProfileOptionTables>,
+}
+
+/// Tùy chọn plugin và stage trong `engine` của một profile
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct ProfileOptionTables {
+    pub engine: EngineOptionTables,
+}
+
+/// Bảng `engine.plugins` và `engine.stages`
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct EngineOptionTables {
+    pub plugins: BTreeMap<String, BTreeMap<String, Spanned<Value>>>,
+    pub stages: BTreeMap<String, BTreeMap<String, Spanned<Value>>>,
+}
@@ -0,0 +1,201 @@
+//! Vikey Config - User File
+//!
+//! Sửa từng khóa trong file cấu hình của user mà không đụng tới phần còn
+//! lại (profile, chú thích về giá trị...), ví dụ khi user bật một tùy
+//! chọn trên tray.
+
+use crate::migrate::{self, CURRENT_VERSION};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Lỗi khi đọc/ghi file cấu hình
+#[derive(Debug)]
+pub enum FileError {
+    /// Lỗi đọc/ghi file
+    Io {
+        /// File liên quan
+        path: PathBuf,
+        /// Lỗi hệ thống
+        error: io::Error,
+    },
+
+    /// File sai cú pháp TOML
+    Parse {
+        /// File liên quan
+        path: PathBuf,
+        /// Mô tả lỗi (gồm dòng, cột)
+        message: String,
+    },
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            FileError::Parse { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+/// Đọc một file cấu hình thành bảng TOML
+pub(crate) fn read_table(path: &Path) -> Result<Table, FileError> {
+    let text = fs::read_to_string(path).map_err(|error| FileError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    toml::from_str(&text).map_err(|e| FileError::Parse {
+        path: path.to_path_buf(),
+        message: e.to_string().trim_end().to_string(),
+    })
+}
+
+/// Ghi bảng TOML ra file (qua file tạm, để người đọc không thấy file dở)
+pub(crate) fn write_table(path: &Path, table: &Table) -> Result<(), FileError> {
+    let io_error = |error| FileError::Io {
+        path: path.to_path_buf(),
+        error,
+    };
+    let text = toml::to_string(table).map_err(|e| FileError::Parse {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let temp = path.with_extension("toml.tmp");
+    fs::write(&temp, text).map_err(io_error)?;
+    fs::rename(&temp, path).map_err(io_error)
+}
+
+/// File cấu hình của user, mở để sửa
+///
+/// ```ignore
+/// let mut file = UserFile::open(loader.user_file().unwrap())?;
+/// file.set("engine.shorthand", true);
+/// file.save()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct UserFile {
+    /// Đường dẫn file
+    path: PathBuf,
+
+    /// Nội dung (đã nâng cấp lên phiên bản hiện tại)
+    table: Table,
+}
+
+impl UserFile {
+    /// Mở file (file chưa tồn tại được coi là rỗng)
+    ///
+    /// File ở phiên bản cũ được nâng cấp; `save` ghi lại ở phiên bản mới.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, FileError> {
+        let path = path.into();
+        let mut table = if path.exists() {
+            read_table(&path)?
+        } else {
+            Table::new()
+        };
+        migrate::migrate(&mut table);
+        Ok(Self { path, table })
+    }
+
+    /// Đường dẫn file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Giá trị theo khóa dạng "engine.keys.undo"
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        let mut parts = key.split('.');
+        let mut value = self.table.get(parts.next()?)?;
+        for part in parts {
+            value = value.as_table()?.get(part)?;
+        }
+        Some(value)
+    }
+
+    /// Đặt giá trị theo khóa dạng "engine.plugins.vietnamese.tone_style",
+    /// tạo các bảng còn thiếu
+    pub fn set(&mut self, key: &str, value: impl Into<Value>) {
+        let mut parts: Vec<&str> = key.split('.').collect();
+        let Some(last) = parts.pop() else {
+            return;
+        };
+
+        let mut table = &mut self.table;
+        for part in parts {
+            let entry = table
+                .entry(part.to_string())
+                .or_insert_with(|| Value::Table(Table::new()));
+            if !entry.is_table() {
+                *entry = Value::Table(Table::new());
+            }
+            let Value::Table(next) = entry else {
+                return;
+            };
+            table = next;
+        }
+        table.insert(last.to_string(), value.into());
+    }
+
+    /// Xóa khóa (mục đó trở về giá trị của lớp bên dưới)
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let (parent, last) = match key.rsplit_once('.') {
+            Some((parent, last)) => (Some(parent), last),
+            None => (None, key),
+        };
+        let table = match parent {
+            Some(parent) => self.table_mut(parent)?,
+            None => &mut self.table,
+        };
+        table.remove(last)
+    }
+
+    fn table_mut(&mut self, key: &str) -> Option<&mut Table> {
+        let mut table = &mut self.table;
+        for part in key.split('.') {
+            table = table.get_mut(part)?.as_table_mut()?;
+        }
+        Some(table)
+    }
+
+    /// Ghi file (ở phiên bản hiện tại)
+    pub fn save(&mut self) -> Result<(), FileError> {
+        self.table.insert(
+            "version".to_string(),
+            Value::Integer(CURRENT_VERSION.into()),
+        );
+        write_table(&self.path, &self.table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_get_remove() {
+        let mut file = UserFile {
+            path: PathBuf::from("config.toml"),
+            table: toml::from_str("[profiles.work.engine]\ninput_method = \"vni\"\n").unwrap(),
+        };
+
+        file.set("engine.plugins.vietnamese.auto_restore", "off");
+        file.set("engine.shorthand", true);
+        assert_eq!(file.get("engine.shorthand"), Some(&Value::Boolean(true)));
+        assert_eq!(
+            file.get("engine.plugins.vietnamese.auto_restore"),
+            Some(&Value::String("off".to_string()))
+        );
+        assert!(file.get("profiles.work.engine.input_method").is_some());
+
+        assert_eq!(file.remove("engine.shorthand"), Some(Value::Boolean(true)));
+        assert_eq!(file.remove("engine.shorthand"), None);
+        assert_eq!(file.remove("app.startup"), None);
+    }
+}
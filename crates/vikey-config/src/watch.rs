@@ -0,0 +1,72 @@
+//! Vikey Config - Hot Reload
+//!
+//! Theo dõi các file cấu hình và tải lại khi nội dung thay đổi. Dùng cách
+//! đọc lại định kỳ (file cấu hình rất nhỏ), không phụ thuộc API theo dõi
+//! file riêng của từng nền tảng.
+
+use crate::loader::{Loaded, Loader};
+use std::fs;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Thread theo dõi file cấu hình; dừng khi bị drop
+pub struct Watcher {
+    /// Yêu cầu thread dừng
+    stop: Arc<AtomicBool>,
+
+    /// Thread theo dõi
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watcher {
+    /// Bắt đầu theo dõi các file của `loader`, kiểm tra mỗi `interval`
+    ///
+    /// `on_change` nhận cấu hình mới (kèm diagnostics) mỗi khi một file
+    /// được tạo, sửa hoặc xóa. Được gọi trên thread theo dõi.
+    pub fn spawn(
+        loader: Loader,
+        interval: Duration,
+        mut on_change: impl FnMut(Loaded) + Send + 'static,
+    ) -> io::Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let mut last = snapshot(&loader);
+
+        let thread = thread::Builder::new()
+            .name("vikey-config-watch".to_string())
+            .spawn(move || loop {
+                thread::park_timeout(interval);
+                if stopped.load(Ordering::Acquire) {
+                    break;
+                }
+                let current = snapshot(&loader);
+                if current != last {
+                    last = current;
+                    on_change(loader.load());
+                }
+            })?;
+
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Nội dung hiện tại của các file (`None`: không đọc được)
+fn snapshot(loader: &Loader) -> Vec<Option<Vec<u8>>> {
+    loader.files().map(|path| fs::read(path).ok()).collect()
+}
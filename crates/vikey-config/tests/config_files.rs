@@ -0,0 +1,323 @@
+// Integration tests for layered settings files
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+use vikey_config::{
+    interop, Layer, Loader, MacroFile, MacroFormat, Severity, UserFile, Watcher, CURRENT_VERSION,
+};
+use vikey_core::{Action, Engine, Shorthand};
+use vikey_vietnamese::VietnamesePlugin;
+
+/// Fresh, empty directory for one test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vikey-config-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn loader(dir: &Path) -> Loader {
+    Loader::default()
+        .with_system_file(dir.join("system.toml"))
+        .with_user_file(dir.join("user").join("config.toml"))
+        .with_legacy_file(dir.join("settings.toml"))
+        .with_macro_file(dir.join("user").join("macros.toml"))
+}
+
+#[test]
+fn test_missing_files_give_defaults() {
+    let dir = temp_dir("missing");
+    let loaded = loader(&dir).load();
+    assert_eq!(loaded.settings, Default::default());
+    assert!(loaded.diagnostics.is_empty());
+}
+
+#[test]
+fn test_layers_and_profiles() {
+    let dir = temp_dir("layers");
+    fs::write(
+        dir.join("system.toml"),
+        r#"
+version = 2
+
+[engine]
+input_method = "telex"
+shorthand = true
+
+[profiles.work.engine]
+input_method = "vni"
+"#,
+    )
+    .unwrap();
+    let mut user = UserFile::open(dir.join("user").join("config.toml")).unwrap();
+    user.set("engine.input_method", "telex_v2");
+    user.set("profiles.work.app.startup", true);
+    user.save().unwrap();
+
+    let loaded = loader(&dir).load();
+    assert!(loaded.diagnostics.is_empty(), "{:?}", loaded.diagnostics);
+    assert_eq!(loaded.profiles, vec!["work".to_string()]);
+    assert_eq!(
+        loaded.settings.engine.input_method.as_deref(),
+        Some("telex_v2")
+    );
+    assert!(loaded.settings.engine.shorthand);
+    assert!(!loaded.settings.app.startup);
+
+    // Profile entries from both layers override the base settings
+    let loaded = loader(&dir).with_profile("work").load();
+    assert_eq!(loaded.settings.profile.as_deref(), Some("work"));
+    assert_eq!(loaded.settings.engine.input_method.as_deref(), Some("vni"));
+    assert!(loaded.settings.engine.shorthand);
+    assert!(loaded.settings.app.startup);
+
+    let loaded = loader(&dir).with_profile("home").load();
+    assert_eq!(
+        loaded.settings.engine.input_method.as_deref(),
+        Some("telex_v2")
+    );
+    assert_eq!(loaded.diagnostics.len(), 1);
+    assert_eq!(loaded.diagnostics[0].severity, Severity::Warning);
+}
+
+#[test]
+fn test_diagnostics_point_to_entries() {
+    let dir = temp_dir("diagnostics");
+    fs::write(
+        dir.join("system.toml"),
+        "[engine]\ninput_method = \"vni\"\npage_size = 9\n",
+    )
+    .unwrap();
+    fs::create_dir_all(dir.join("user")).unwrap();
+    fs::write(
+        dir.join("user").join("config.toml"),
+        "version = 2\n\n[engine]\ncomposition_mode = \"sideways\"\nshorthand = true\n",
+    )
+    .unwrap();
+
+    let loaded = loader(&dir).load();
+    assert!(loaded.has_errors());
+    assert_eq!(loaded.settings.engine.input_method.as_deref(), Some("vni"));
+    assert!(loaded.settings.engine.shorthand);
+
+    let messages: Vec<String> = loaded.diagnostics.iter().map(|d| d.to_string()).collect();
+    assert_eq!(messages.len(), 2, "{:?}", messages);
+    assert_eq!(loaded.diagnostics[0].origin.layer, Layer::System);
+    assert!(
+        messages[0].ends_with("system.toml:3:1: error: engine.page_size: unknown field `page_size`, expected one of `enabled`, `language`, `input_method`, `composition_mode`, `interrupt_policy`, `candidates`, `keys`, `shorthand`, `plugins`, `stages`"),
+        "{}",
+        messages[0]
+    );
+    assert_eq!(loaded.diagnostics[1].origin.layer, Layer::User);
+    assert_eq!(loaded.diagnostics[1].line, Some(4));
+    assert_eq!(
+        loaded.diagnostics[1].entry.as_deref(),
+        Some("engine.composition_mode")
+    );
+}
+
+#[test]
+fn test_legacy_settings_are_migrated() {
+    let dir = temp_dir("legacy");
+    fs::write(
+        dir.join("settings.toml"),
+        "input_method = \"vni\"\nauto_correct = true\nshorthand = false\nstartup = true\n",
+    )
+    .unwrap();
+    let loader = loader(&dir);
+
+    let loaded = loader.load();
+    assert_eq!(loaded.settings.engine.input_method.as_deref(), Some("vni"));
+    assert!(loaded.settings.app.startup);
+    assert_eq!(loaded.diagnostics.len(), 1);
+    assert_eq!(loaded.diagnostics[0].severity, Severity::Info);
+
+    // Legacy file is copied to the new location and left alone
+    assert_eq!(loader.upgrade().unwrap(), Some(1));
+    assert!(dir.join("settings.toml").exists());
+    let user = UserFile::open(loader.user_file().unwrap()).unwrap();
+    assert_eq!(
+        user.get("version").and_then(|v| v.as_integer()),
+        Some(CURRENT_VERSION as i64)
+    );
+    assert_eq!(loader.load().settings, loaded.settings);
+    assert!(loader.load().diagnostics.is_empty());
+    assert_eq!(loader.upgrade().unwrap(), None);
+}
+
+#[test]
+fn test_old_user_file_is_backed_up() {
+    let dir = temp_dir("backup");
+    let path = dir.join("user").join("config.toml");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "input_method = \"english\"\n").unwrap();
+    let loader = loader(&dir);
+
+    assert!(!loader.load().settings.engine.enabled);
+    assert_eq!(loader.upgrade().unwrap(), Some(1));
+    assert_eq!(
+        fs::read_to_string(dir.join("user").join("config.toml.v1.bak")).unwrap(),
+        "input_method = \"english\"\n"
+    );
+    assert!(!loader.load().settings.engine.enabled);
+}
+
+#[test]
+fn test_settings_apply_to_engine() {
+    let dir = temp_dir("engine");
+    let mut user = UserFile::open(dir.join("user").join("config.toml")).unwrap();
+    user.set("engine.language", "vietnamese");
+    user.set("engine.input_method", "telex_v2");
+    user.set("engine.plugins.vietnamese.tone_style", "old");
+    user.save().unwrap();
+
+    let settings = loader(&dir).load().settings;
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_config(settings.engine.clone()).unwrap();
+    let config = engine.config();
+    assert_eq!(config.input_method.as_deref(), Some("telex_v2"));
+    assert_eq!(
+        config.plugin_option("vietnamese", "tone_style"),
+        Some(&"old".into())
+    );
+}
+
+#[test]
+fn test_bad_plugin_option_is_dropped_alone() {
+    let dir = temp_dir("plugin-options");
+    fs::create_dir_all(dir.join("user")).unwrap();
+    fs::write(
+        dir.join("user").join("config.toml"),
+        r#"version = 2
+
+[engine]
+language = "vietnamese"
+input_method = "vni"
+
+[engine.plugins.vietnamese]
+tone_style = "old"
+auto_restore = "bogus"
+"#,
+    )
+    .unwrap();
+
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    let loaded = loader(&dir).with_options(engine.option_schema()).load();
+
+    assert_eq!(loaded.diagnostics.len(), 1, "{:?}", loaded.diagnostics);
+    let diagnostic = &loaded.diagnostics[0];
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!((diagnostic.line, diagnostic.column), (Some(9), Some(16)));
+    assert_eq!(
+        diagnostic.entry.as_deref(),
+        Some("engine.plugins.vietnamese.auto_restore")
+    );
+    assert!(diagnostic.message.contains("\"bogus\""), "{}", diagnostic);
+
+    engine.set_config(loaded.settings.engine).unwrap();
+    let config = engine.config();
+    assert_eq!(config.input_method.as_deref(), Some("vni"));
+    assert_eq!(
+        config.plugin_option("vietnamese", "tone_style"),
+        Some(&"old".into())
+    );
+}
+
+#[test]
+fn test_watcher_reloads_on_change() {
+    let dir = temp_dir("watch");
+    let path = dir.join("user").join("config.toml");
+    let (sender, receiver) = mpsc::channel();
+    let watcher = Watcher::spawn(loader(&dir), Duration::from_millis(10), move |loaded| {
+        let _ = sender.send(loaded);
+    })
+    .unwrap();
+
+    let mut user = UserFile::open(&path).unwrap();
+    user.set("engine.shorthand", true);
+    user.save().unwrap();
+
+    let loaded = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(loaded.settings.engine.shorthand);
+    drop(watcher);
+}
+
+#[test]
+fn test_macro_file_drives_shorthand() {
+    let dir = temp_dir("macros");
+    let mut macros = MacroFile::open(dir.join("user").join("macros.toml")).unwrap();
+    macros.set("vn", "Việt Nam").unwrap();
+    macros.save().unwrap();
+    let mut user = UserFile::open(dir.join("user").join("config.toml")).unwrap();
+    user.set("engine.language", "vietnamese");
+    user.set("engine.input_method", "telex_v2");
+    user.set("engine.shorthand", true);
+    user.save().unwrap();
+
+    let loaded = loader(&dir).load();
+    assert!(loaded.diagnostics.is_empty(), "{:?}", loaded.diagnostics);
+    assert_eq!(loaded.macros.get("vn"), Some("Việt Nam"));
+
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine
+        .add_stage(Box::new(Shorthand::new(loaded.macros)))
+        .unwrap();
+    engine.set_config(loaded.settings.engine).unwrap();
+    engine.process('v');
+    engine.process('n');
+    assert_eq!(
+        engine.process(' '),
+        Action::Replace {
+            backspace_count: 2,
+            text: "Việt Nam ".to_string(),
+        }
+    );
+
+    // Bad entries are reported with their line
+    std::fs::write(
+        dir.join("user").join("macros.toml"),
+        "[macros]\nvn = \"Việt Nam\"\nko = \"\"\n",
+    )
+    .unwrap();
+    let loaded = loader(&dir).load();
+    assert_eq!(loaded.macros.len(), 1);
+    assert_eq!(loaded.diagnostics.len(), 1);
+    assert_eq!(loaded.diagnostics[0].origin.layer, Layer::Macros);
+    assert_eq!(loaded.diagnostics[0].line, Some(3));
+}
+
+#[test]
+fn test_import_and_export_unikey_macros() {
+    let dir = temp_dir("interop");
+    let unikey = dir.join("unikey.txt");
+    let mut bytes = vec![0xFF, 0xFE];
+    let text = ";DO NOT DELETE THIS LINE*** version=1 ***\r\nvn:Việt Nam\r\nko\r\nđc:được\r\n";
+    bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+    fs::write(&unikey, bytes).unwrap();
+
+    let (table, diagnostics) = interop::import_file(&unikey).unwrap();
+    assert_eq!(table.len(), 2);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].line, Some(3));
+
+    // Imported entries go to the macro file the engine loads
+    let path = dir.join("user").join("macros.toml");
+    let mut macros = MacroFile::open(&path).unwrap();
+    macros.set("vn", "VN").unwrap();
+    assert_eq!(macros.merge(&table), 1);
+    macros.save().unwrap();
+    let loaded = loader(&dir).load();
+    assert_eq!(loaded.macros, table);
+
+    // And back out for OpenKey
+    let openkey = dir.join("openkey.txt");
+    let diagnostics = interop::export_file(&openkey, MacroFormat::OpenKey, &loaded.macros).unwrap();
+    assert!(diagnostics.is_empty());
+    let (exported, _) = interop::import_file(&openkey).unwrap();
+    assert_eq!(exported, table);
+}
@@ -1,5 +1,7 @@
 // buffer.rs - Input buffer management
 
+use std::fmt;
+
 /// Maximum buffer size (same as UniKey)
 const BUFFER_SIZE: usize = 40;
 
@@ -89,11 +91,6 @@ impl InputBuffer {
         self.chars[start..].iter().copied()
     }
 
-    /// Get all characters as a string
-    pub fn to_string(&self) -> String {
-        self.chars.iter().collect()
-    }
-
     /// Throw buffer - keep only last KEYS_MAINTAIN characters
     /// Called when buffer is full
     fn throw_buffer(&mut self) {
@@ -123,6 +120,13 @@ impl InputBuffer {
     }
 }
 
+impl fmt::Display for InputBuffer {
+    /// Render all characters in the buffer as a string
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chars.iter().try_for_each(|ch| write!(f, "{}", ch))
+    }
+}
+
 impl Default for InputBuffer {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,301 @@
+//! Vikey Core - Candidate List Module
+//!
+//! Danh sách candidate dùng chung cho các input method dựa trên lựa chọn
+//! (Chữ Nôm, gợi ý từ...). Engine lo phân trang, di chuyển lựa chọn và
+//! phím chọn; input method chỉ cần cung cấp danh sách và commit lựa chọn.
+
+/// Một candidate trong danh sách
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// Văn bản sẽ được commit (ví dụ: "𡨸")
+    pub text: String,
+
+    /// Chú thích ngắn hiển thị cạnh candidate (ví dụ: phiên âm "chữ")
+    pub annotation: Option<String>,
+
+    /// Ghi chú dài hơn (ví dụ: nghĩa, nguồn gốc)
+    pub comment: Option<String>,
+}
+
+impl Candidate {
+    /// Tạo candidate chỉ có văn bản
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            annotation: None,
+            comment: None,
+        }
+    }
+
+    /// Đặt chú thích (builder)
+    pub fn with_annotation(mut self, annotation: impl Into<String>) -> Self {
+        self.annotation = Some(annotation.into());
+        self
+    }
+
+    /// Đặt ghi chú (builder)
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+}
+
+/// Cấu hình hiển thị candidate của Engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct CandidateConfig {
+    /// Phím chọn candidate trên trang hiện tại, theo thứ tự
+    ///
+    /// Khi serialize được ghi thành một chuỗi (ví dụ `"123456789"`).
+    #[cfg_attr(feature = "serde", serde(with = "selection_keys"))]
+    pub selection_keys: Vec<char>,
+
+    /// Số candidate mỗi trang
+    pub page_size: usize,
+}
+
+impl Default for CandidateConfig {
+    fn default() -> Self {
+        Self {
+            selection_keys: "123456789".chars().collect(),
+            page_size: 9,
+        }
+    }
+}
+
+/// Phím chọn dạng chuỗi trong file cấu hình
+#[cfg(feature = "serde")]
+mod selection_keys {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(keys: &[char], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&keys.iter().collect::<String>())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<char>, D::Error> {
+        Ok(String::deserialize(deserializer)?.chars().collect())
+    }
+}
+
+/// Danh sách candidate có phân trang
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateList {
+    /// Tất cả candidates
+    entries: Vec<Candidate>,
+
+    /// Index (tuyệt đối) của candidate đang chọn
+    selected: usize,
+
+    /// Số candidate mỗi trang
+    page_size: usize,
+
+    /// Phím chọn cho từng vị trí trên trang
+    selection_keys: Vec<char>,
+
+    /// Dòng phụ hiển thị phía trên danh sách (thường là phiên âm đang gõ)
+    pub aux_text: String,
+}
+
+impl CandidateList {
+    /// Tạo danh sách với cấu hình mặc định
+    pub fn new(entries: Vec<Candidate>) -> Self {
+        let config = CandidateConfig::default();
+        Self {
+            entries,
+            selected: 0,
+            page_size: config.page_size,
+            selection_keys: config.selection_keys,
+            aux_text: String::new(),
+        }
+    }
+
+    /// Đặt dòng phụ (builder)
+    pub fn with_aux_text(mut self, aux_text: impl Into<String>) -> Self {
+        self.aux_text = aux_text.into();
+        self
+    }
+
+    /// Áp dụng cấu hình (phím chọn, kích thước trang)
+    pub fn apply_config(&mut self, config: &CandidateConfig) {
+        self.selection_keys = config.selection_keys.clone();
+        self.page_size = config.page_size.max(1);
+    }
+
+    /// Số candidate
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Danh sách rỗng?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Tất cả candidates
+    pub fn entries(&self) -> &[Candidate] {
+        &self.entries
+    }
+
+    /// Lấy candidate theo index tuyệt đối
+    pub fn get(&self, index: usize) -> Option<&Candidate> {
+        self.entries.get(index)
+    }
+
+    /// Index tuyệt đối của candidate đang chọn
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// Candidate đang chọn
+    pub fn selected(&self) -> Option<&Candidate> {
+        self.entries.get(self.selected)
+    }
+
+    /// Số candidate mỗi trang
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Phím chọn
+    pub fn selection_keys(&self) -> &[char] {
+        &self.selection_keys
+    }
+
+    /// Trang hiện tại (bắt đầu từ 0)
+    pub fn page_index(&self) -> usize {
+        self.selected / self.page_size
+    }
+
+    /// Tổng số trang
+    pub fn page_count(&self) -> usize {
+        self.entries.len().div_ceil(self.page_size)
+    }
+
+    /// Index tuyệt đối của candidate đầu trang hiện tại
+    pub fn page_start(&self) -> usize {
+        self.page_index() * self.page_size
+    }
+
+    /// Các candidate trên trang hiện tại
+    pub fn current_page(&self) -> &[Candidate] {
+        let start = self.page_start().min(self.entries.len());
+        let end = (start + self.page_size).min(self.entries.len());
+        &self.entries[start..end]
+    }
+
+    /// Các candidate trên trang hiện tại kèm nhãn phím chọn
+    pub fn labeled_page(&self) -> impl Iterator<Item = (Option<char>, &Candidate)> + '_ {
+        self.current_page()
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (self.selection_keys.get(i).copied(), c))
+    }
+
+    /// Index tuyệt đối tương ứng với một phím chọn trên trang hiện tại
+    pub fn index_for_key(&self, key: char) -> Option<usize> {
+        let pos = self.selection_keys.iter().position(|&k| k == key)?;
+        if pos >= self.page_size {
+            return None;
+        }
+        let index = self.page_start() + pos;
+        (index < self.entries.len()).then_some(index)
+    }
+
+    /// Chọn candidate theo index tuyệt đối
+    pub fn select(&mut self, index: usize) -> bool {
+        if index < self.entries.len() {
+            self.selected = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Di chuyển lựa chọn `delta` vị trí (âm là lùi), dừng ở hai đầu
+    pub fn move_selection(&mut self, delta: isize) -> bool {
+        if self.entries.is_empty() {
+            return false;
+        }
+        let last = self.entries.len() - 1;
+        let target = self.selected.saturating_add_signed(delta).min(last);
+        let changed = target != self.selected;
+        self.selected = target;
+        changed
+    }
+
+    /// Sang trang trước
+    pub fn page_up(&mut self) -> bool {
+        if self.page_index() == 0 {
+            return false;
+        }
+        self.selected = (self.page_index() - 1) * self.page_size;
+        true
+    }
+
+    /// Sang trang sau
+    pub fn page_down(&mut self) -> bool {
+        if self.page_index() + 1 >= self.page_count() {
+            return false;
+        }
+        self.selected = (self.page_index() + 1) * self.page_size;
+        true
+    }
+}
+
+impl Default for CandidateList {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(n: usize) -> CandidateList {
+        CandidateList::new((0..n).map(|i| Candidate::new(i.to_string())).collect())
+    }
+
+    #[test]
+    fn test_paging() {
+        let mut list = list(20);
+        list.apply_config(&CandidateConfig {
+            selection_keys: "asdf".chars().collect(),
+            page_size: 4,
+        });
+
+        assert_eq!(list.page_count(), 5);
+        assert_eq!(list.current_page().len(), 4);
+        assert!(!list.page_up());
+
+        assert!(list.page_down());
+        assert_eq!(list.page_start(), 4);
+        assert_eq!(list.selected().unwrap().text, "4");
+        assert_eq!(list.index_for_key('d'), Some(6));
+        assert_eq!(list.index_for_key('1'), None);
+
+        assert!(list.page_up());
+        assert_eq!(list.selected_index(), 0);
+    }
+
+    #[test]
+    fn test_move_selection() {
+        let mut list = list(3);
+        assert!(!list.move_selection(-1));
+        assert!(list.move_selection(2));
+        assert_eq!(list.selected_index(), 2);
+        assert!(!list.move_selection(1));
+    }
+
+    #[test]
+    fn test_last_page_partial() {
+        let mut list = list(10);
+        assert!(list.page_down());
+        assert_eq!(list.current_page().len(), 1);
+        assert_eq!(list.index_for_key('2'), None);
+
+        let labels: Vec<_> = list.labeled_page().map(|(k, _)| k).collect();
+        assert_eq!(labels, vec![Some('1')]);
+    }
+}
@@ -0,0 +1,570 @@
+//! Vikey Core - Config Module
+//!
+//! Cấu hình của Engine: tùy chọn chung (bật/tắt, chế độ soạn thảo, phím
+//! tắt, candidate...) và tùy chọn riêng của từng Language Plugin theo
+//! schema mà plugin khai báo qua `LanguagePlugin::options`.
+//!
+//! Cấu hình được áp dụng ngay bằng `Engine::set_config`, và đọc/ghi được
+//! dạng TOML với feature `toml`:
+//!
+//! ```toml
+//! language = "vietnamese"
+//! input_method = "telex_v2"
+//! shorthand = true
+//!
+//! [keys]
+//! undo = "Ctrl+Z"
+//! restore = ""          # tắt phím trả về phím gốc
+//!
+//! [plugins.vietnamese]
+//! auto_restore = "strict"
+//! tone_style = "old"
+//! ```
+
+use crate::candidate::CandidateConfig;
+use crate::key::{KeyBinding, KeyCode, Modifiers};
+use crate::types::{CompositionMode, InterruptPolicy};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Tùy chọn của một plugin: tên tùy chọn → giá trị
+pub type PluginOptions = BTreeMap<String, OptionValue>;
+
+/// Cấu hình của Engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Config {
+    /// Bật bộ gõ; khi tắt, mọi phím được chuyển thẳng cho host
+    pub enabled: bool,
+
+    /// ID ngôn ngữ (`None`: giữ ngôn ngữ hiện tại)
+    pub language: Option<String>,
+
+    /// ID input method (`None`: giữ input method hiện tại, hoặc input method
+    /// đầu tiên của ngôn ngữ mới)
+    pub input_method: Option<String>,
+
+    /// Chế độ soạn thảo mong muốn
+    pub composition_mode: CompositionMode,
+
+    /// Xử lý composition khi host làm gián đoạn
+    pub interrupt_policy: InterruptPolicy,
+
+    /// Cửa sổ candidate
+    pub candidates: CandidateConfig,
+
+    /// Phím tắt của Engine
+    pub keys: KeysConfig,
+
+    /// Bật gõ tắt (stage `shorthand::Shorthand`, nếu Engine có)
+    pub shorthand: bool,
+
+    /// Tùy chọn của từng plugin, theo ID plugin
+    ///
+    /// Tùy chọn không có ở đây nhận giá trị mặc định trong schema.
+    pub plugins: BTreeMap<String, PluginOptions>,
+
+    /// Tùy chọn của từng stage hậu xử lý (`Engine::add_stage`), theo ID
+    /// stage
+    ///
+    /// Ngoài tùy chọn trong schema của stage, mỗi stage có tùy chọn
+    /// `enabled` (mặc định `true`).
+    pub stages: BTreeMap<String, PluginOptions>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            language: None,
+            input_method: None,
+            composition_mode: CompositionMode::default(),
+            interrupt_policy: InterruptPolicy::default(),
+            candidates: CandidateConfig::default(),
+            keys: KeysConfig::default(),
+            shorthand: false,
+            plugins: BTreeMap::new(),
+            stages: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Giá trị tùy chọn `key` của plugin `plugin` (nếu được đặt)
+    pub fn plugin_option(&self, plugin: &str, key: &str) -> Option<&OptionValue> {
+        self.plugins.get(plugin)?.get(key)
+    }
+
+    /// Đặt tùy chọn `key` của plugin `plugin`
+    pub fn set_plugin_option(
+        &mut self,
+        plugin: &str,
+        key: &str,
+        value: impl Into<OptionValue>,
+    ) -> &mut Self {
+        self.plugins
+            .entry(plugin.to_string())
+            .or_default()
+            .insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Giá trị tùy chọn `key` của stage `stage` (nếu được đặt)
+    pub fn stage_option(&self, stage: &str, key: &str) -> Option<&OptionValue> {
+        self.stages.get(stage)?.get(key)
+    }
+
+    /// Đặt tùy chọn `key` của stage `stage` (`"enabled"` để bật/tắt stage)
+    pub fn set_stage_option(
+        &mut self,
+        stage: &str,
+        key: &str,
+        value: impl Into<OptionValue>,
+    ) -> &mut Self {
+        self.stages
+            .entry(stage.to_string())
+            .or_default()
+            .insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Đọc cấu hình từ chuỗi TOML
+    ///
+    /// Lỗi cú pháp, sai kiểu hoặc trường không xác định trả về
+    /// `ConfigError::Parse` kèm vị trí dòng/cột. Tên ngôn ngữ, input
+    /// method và tùy chọn plugin được kiểm tra khi áp dụng
+    /// (`Engine::validate_config`).
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(s: &str) -> Result<Self, ConfigError> {
+        toml::from_str(s).map_err(|e| ConfigError::Parse(e.to_string().trim_end().to_string()))
+    }
+
+    /// Ghi cấu hình ra chuỗi TOML
+    #[cfg(feature = "toml")]
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+}
+
+/// Phím tắt của Engine (`None`: tắt phím tắt đó)
+///
+/// Trong file cấu hình mỗi phím là một chuỗi như `"Ctrl+Z"`; chuỗi rỗng
+/// để tắt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct KeysConfig {
+    /// Undo phép biến đổi gần nhất (mặc định Ctrl+Z)
+    #[cfg_attr(feature = "serde", serde(with = "binding"))]
+    pub undo: Option<KeyBinding>,
+
+    /// Redo (mặc định Ctrl+Y)
+    #[cfg_attr(feature = "serde", serde(with = "binding"))]
+    pub redo: Option<KeyBinding>,
+
+    /// Trả từ đang soạn về phím gốc (mặc định Escape)
+    #[cfg_attr(feature = "serde", serde(with = "binding"))]
+    pub restore: Option<KeyBinding>,
+}
+
+impl Default for KeysConfig {
+    fn default() -> Self {
+        Self {
+            undo: Some(KeyBinding::ctrl('z')),
+            redo: Some(KeyBinding::ctrl('y')),
+            restore: Some(KeyBinding::new(KeyCode::Escape, Modifiers::NONE)),
+        }
+    }
+}
+
+/// Phím tắt dạng chuỗi trong file cấu hình
+#[cfg(feature = "serde")]
+mod binding {
+    use crate::key::KeyBinding;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        key: &Option<KeyBinding>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match key {
+            Some(key) => serializer.collect_str(key),
+            None => serializer.serialize_str(""),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<KeyBinding>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s.trim().is_empty() {
+            return Ok(None);
+        }
+        s.parse().map(Some).map_err(D::Error::custom)
+    }
+}
+
+/// Giá trị của một tùy chọn plugin
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum OptionValue {
+    /// Bật/tắt
+    Bool(bool),
+    /// Số nguyên
+    Int(i64),
+    /// Chuỗi (cũng dùng cho tùy chọn dạng lựa chọn)
+    Text(String),
+}
+
+impl OptionValue {
+    /// Giá trị bool (nếu đúng kiểu)
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            OptionValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Giá trị số nguyên (nếu đúng kiểu)
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            OptionValue::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Giá trị chuỗi (nếu đúng kiểu)
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            OptionValue::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionValue::Bool(b) => write!(f, "{}", b),
+            OptionValue::Int(n) => write!(f, "{}", n),
+            OptionValue::Text(s) => write!(f, "\"{}\"", s),
+        }
+    }
+}
+
+impl From<bool> for OptionValue {
+    fn from(value: bool) -> Self {
+        OptionValue::Bool(value)
+    }
+}
+
+impl From<i64> for OptionValue {
+    fn from(value: i64) -> Self {
+        OptionValue::Int(value)
+    }
+}
+
+impl From<&str> for OptionValue {
+    fn from(value: &str) -> Self {
+        OptionValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for OptionValue {
+    fn from(value: String) -> Self {
+        OptionValue::Text(value)
+    }
+}
+
+/// Kiểu (và miền giá trị) của một tùy chọn plugin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionKind {
+    /// Bật/tắt
+    Bool,
+    /// Số nguyên trong khoảng `min..=max`
+    Int {
+        /// Giá trị nhỏ nhất
+        min: i64,
+        /// Giá trị lớn nhất
+        max: i64,
+    },
+    /// Một trong các chuỗi cho trước
+    Choice(Vec<String>),
+    /// Chuỗi bất kỳ
+    Text,
+}
+
+/// Mô tả một tùy chọn mà plugin hỗ trợ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionSpec {
+    /// Tên tùy chọn (ví dụ: "tone_style")
+    pub key: String,
+
+    /// Kiểu giá trị
+    pub kind: OptionKind,
+
+    /// Giá trị mặc định
+    pub default: OptionValue,
+
+    /// Mô tả ngắn hiển thị cho user
+    pub description: String,
+}
+
+impl OptionSpec {
+    /// Tùy chọn bật/tắt
+    pub fn bool(key: &str, default: bool, description: &str) -> Self {
+        Self::new(key, OptionKind::Bool, default.into(), description)
+    }
+
+    /// Tùy chọn số nguyên trong khoảng `min..=max`
+    pub fn int(key: &str, min: i64, max: i64, default: i64, description: &str) -> Self {
+        Self::new(
+            key,
+            OptionKind::Int { min, max },
+            default.into(),
+            description,
+        )
+    }
+
+    /// Tùy chọn chọn một trong `choices`
+    pub fn choice(key: &str, choices: &[&str], default: &str, description: &str) -> Self {
+        let choices = choices.iter().map(|c| c.to_string()).collect();
+        Self::new(
+            key,
+            OptionKind::Choice(choices),
+            default.into(),
+            description,
+        )
+    }
+
+    /// Tùy chọn chuỗi bất kỳ
+    pub fn text(key: &str, default: &str, description: &str) -> Self {
+        Self::new(key, OptionKind::Text, default.into(), description)
+    }
+
+    fn new(key: &str, kind: OptionKind, default: OptionValue, description: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            kind,
+            default,
+            description: description.to_string(),
+        }
+    }
+
+    /// Kiểm tra giá trị theo kiểu của tùy chọn
+    ///
+    /// # Returns
+    /// `Err` với mô tả giá trị hợp lệ nếu sai kiểu hoặc ngoài miền giá trị
+    pub fn validate(&self, value: &OptionValue) -> Result<(), String> {
+        let valid = match (&self.kind, value) {
+            (OptionKind::Bool, OptionValue::Bool(_)) => true,
+            (OptionKind::Int { min, max }, OptionValue::Int(n)) => (*min..=*max).contains(n),
+            (OptionKind::Choice(choices), OptionValue::Text(s)) => choices.contains(s),
+            (OptionKind::Text, OptionValue::Text(_)) => true,
+            _ => false,
+        };
+        if valid {
+            return Ok(());
+        }
+
+        Err(match &self.kind {
+            OptionKind::Bool => format!("expected true or false, got {}", value),
+            OptionKind::Int { min, max } => {
+                format!("expected an integer from {} to {}, got {}", min, max, value)
+            }
+            OptionKind::Choice(choices) => format!(
+                "expected one of {}, got {}",
+                choices
+                    .iter()
+                    .map(|c| format!("\"{}\"", c))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                value
+            ),
+            OptionKind::Text => format!("expected a string, got {}", value),
+        })
+    }
+}
+
+/// Schema tùy chọn của các plugin và stage đã đăng ký (`Engine::option_schema`)
+///
+/// Dùng để kiểm tra `Config::plugins` / `Config::stages` trước khi có Engine,
+/// ví dụ khi đọc file cấu hình.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OptionSchema {
+    /// Tùy chọn của từng plugin, theo ID plugin
+    pub plugins: BTreeMap<String, Vec<OptionSpec>>,
+
+    /// Tùy chọn của từng stage (gồm `enabled`), theo ID stage
+    pub stages: BTreeMap<String, Vec<OptionSpec>>,
+}
+
+/// Lỗi khi đọc hoặc áp dụng cấu hình
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// File cấu hình sai cú pháp, sai kiểu hoặc có trường không xác định
+    /// (thông báo gồm vị trí dòng/cột)
+    Parse(String),
+
+    /// Không có plugin nào cho ngôn ngữ này
+    UnknownLanguage(String),
+
+    /// Ngôn ngữ không có input method này
+    UnknownInputMethod {
+        /// ID ngôn ngữ
+        language: String,
+        /// ID input method
+        method: String,
+    },
+
+    /// Tùy chọn cho một plugin chưa được đăng ký
+    UnknownPlugin(String),
+
+    /// Tùy chọn cho một stage chưa được thêm vào pipeline
+    UnknownStage(String),
+
+    /// Plugin (hoặc stage) không có tùy chọn này
+    UnknownOption {
+        /// ID plugin hoặc stage
+        plugin: String,
+        /// Tên tùy chọn
+        key: String,
+    },
+
+    /// Giá trị tùy chọn plugin (hoặc stage) không hợp lệ
+    InvalidOption {
+        /// ID plugin hoặc stage
+        plugin: String,
+        /// Tên tùy chọn
+        key: String,
+        /// Mô tả lỗi
+        message: String,
+    },
+
+    /// Giá trị tùy chọn chung không hợp lệ
+    InvalidValue {
+        /// Tên trường (ví dụ: "candidates.page_size")
+        field: String,
+        /// Mô tả lỗi
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Parse(message) => write!(f, "{}", message),
+            ConfigError::UnknownLanguage(id) => write!(f, "Language '{}' not found", id),
+            ConfigError::UnknownInputMethod { language, method } => write!(
+                f,
+                "Input method '{}' not found for language '{}'",
+                method, language
+            ),
+            ConfigError::UnknownPlugin(id) => {
+                write!(f, "Options given for unknown plugin '{}'", id)
+            }
+            ConfigError::UnknownStage(id) => {
+                write!(f, "Options given for unknown stage '{}'", id)
+            }
+            ConfigError::UnknownOption { plugin, key } => {
+                write!(f, "Plugin '{}' has no option '{}'", plugin, key)
+            }
+            ConfigError::InvalidOption {
+                plugin,
+                key,
+                message,
+            } => write!(f, "Invalid value for {}.{}: {}", plugin, key, message),
+            ConfigError::InvalidValue { field, message } => {
+                write!(f, "Invalid value for {}: {}", field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_default() {
+        let config = Config::default();
+        assert!(config.enabled);
+        assert!(!config.shorthand);
+        assert_eq!(config.keys.undo, Some(KeyBinding::ctrl('z')));
+        assert!(config.plugins.is_empty());
+    }
+
+    #[test]
+    fn test_option_spec_validate() {
+        let style = OptionSpec::choice("tone_style", &["new", "old"], "new", "Tone placement");
+        assert!(style.validate(&"old".into()).is_ok());
+        assert_eq!(
+            style.validate(&"modern".into()),
+            Err("expected one of \"new\", \"old\", got \"modern\"".to_string())
+        );
+        assert!(style.validate(&true.into()).is_err());
+
+        let size = OptionSpec::int("size", 1, 10, 5, "Size");
+        assert!(size.validate(&10.into()).is_ok());
+        assert!(size.validate(&11.into()).is_err());
+
+        let flag = OptionSpec::bool("flag", false, "Flag");
+        assert!(flag.validate(&false.into()).is_ok());
+        assert!(flag.validate(&"false".into()).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_round_trip() {
+        let mut config = Config::from_toml_str(
+            r#"
+            input_method = "telex_v2"
+            composition_mode = "preedit"
+
+            [candidates]
+            selection_keys = "asdf"
+            page_size = 4
+
+            [keys]
+            undo = "Ctrl+Shift+Z"
+            restore = ""
+
+            [plugins.vietnamese]
+            tone_style = "old"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.input_method.as_deref(), Some("telex_v2"));
+        assert_eq!(config.composition_mode, CompositionMode::Preedit);
+        assert_eq!(config.candidates.selection_keys, vec!['a', 's', 'd', 'f']);
+        assert_eq!(config.keys.redo, Some(KeyBinding::ctrl('y')));
+        assert_eq!(config.keys.restore, None);
+        assert_eq!(
+            config.plugin_option("vietnamese", "tone_style"),
+            Some(&"old".into())
+        );
+
+        config.set_plugin_option("vietnamese", "auto_restore", "off");
+        let text = config.to_toml_string().unwrap();
+        assert_eq!(Config::from_toml_str(&text).unwrap(), config);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_errors() {
+        let error = Config::from_toml_str("enabled = true\nshorthnad = true\n").unwrap_err();
+        let ConfigError::Parse(message) = error else {
+            panic!("expected a parse error");
+        };
+        assert!(message.contains("line 2"), "{}", message);
+        assert!(message.contains("shorthnad"), "{}", message);
+
+        let error = Config::from_toml_str("[keys]\nundo = \"Hyper+Z\"\n").unwrap_err();
+        assert!(error.to_string().contains("Hyper+Z"), "{}", error);
+    }
+}
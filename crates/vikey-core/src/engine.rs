@@ -3,9 +3,63 @@
 //! Main orchestrator that uses plugins to process input.
 
 use crate::buffer::InputBuffer;
+use crate::candidate::{CandidateConfig, CandidateList};
+use crate::config::{
+    Config, ConfigError, KeysConfig, OptionSchema, OptionSpec, OptionValue, PluginOptions,
+};
+use crate::event::{EngineEvent, EngineObserver, Observers, SubscriptionId};
+use crate::history::{self, History};
+use crate::key::{KeyBinding, KeyCode, KeyEvent};
+use crate::metadata::PluginMetadata;
 use crate::registry::{PluginRegistry, RegistryError};
+use crate::session::Session;
+use crate::shorthand;
 use crate::traits::{InputMethodTrait, LanguagePlugin};
-use crate::types::Action;
+use crate::transform::{Middleware, Pipeline, StageContext, StageSession};
+use crate::types::{Action, CompositionMode, InterruptPolicy};
+use std::collections::BTreeMap;
+
+/// Tùy chọn có sẵn của mọi stage trong `Config::stages`: bật/tắt stage
+const STAGE_ENABLED: &str = "enabled";
+
+fn enabled_spec() -> OptionSpec {
+    OptionSpec::bool(STAGE_ENABLED, true, "Bật stage")
+}
+
+/// Bỏ các tùy chọn không có trong `specs`; tùy chọn sai kiểu/giá trị được
+/// thay bằng giá trị hiện tại (`current`), hoặc bỏ nếu không có
+fn prune_options(
+    owner: &str,
+    options: &mut PluginOptions,
+    specs: &[OptionSpec],
+    errors: &mut Vec<ConfigError>,
+    current: impl Fn(&str) -> Option<OptionValue>,
+) {
+    options.retain(|key, value| {
+        let Some(spec) = specs.iter().find(|spec| &spec.key == key) else {
+            errors.push(ConfigError::UnknownOption {
+                plugin: owner.to_string(),
+                key: key.clone(),
+            });
+            return false;
+        };
+        let Err(message) = spec.validate(value) else {
+            return true;
+        };
+        errors.push(ConfigError::InvalidOption {
+            plugin: owner.to_string(),
+            key: key.clone(),
+            message,
+        });
+        match current(key) {
+            Some(current) => {
+                *value = current;
+                true
+            }
+            None => false,
+        }
+    });
+}
 
 /// Main Vikey Engine
 ///
@@ -14,29 +68,79 @@ pub struct Engine {
     /// Plugin registry
     registry: PluginRegistry,
 
+    /// Bộ gõ đang bật (khi tắt, mọi phím là `PassThrough`)
+    enabled: bool,
+
+    /// Gõ tắt đang bật (stage `shorthand::ID`, nếu có trong pipeline)
+    shorthand: bool,
+
     /// Input buffer
     buffer: InputBuffer,
 
     /// Current input method instance
     current_method: Option<Box<dyn InputMethodTrait>>,
+
+    /// Chế độ soạn thảo được yêu cầu
+    requested_mode: CompositionMode,
+
+    /// Chế độ soạn thảo thực tế của input method hiện tại
+    composition_mode: CompositionMode,
+
+    /// Cấu hình cửa sổ candidate (phím chọn, kích thước trang)
+    candidate_config: CandidateConfig,
+
+    /// Lịch sử phím của từ đang soạn (cho undo/redo)
+    history: History,
+
+    /// Phím tắt undo (mặc định Ctrl+Z)
+    undo_key: Option<KeyBinding>,
+
+    /// Phím tắt redo (mặc định Ctrl+Y)
+    redo_key: Option<KeyBinding>,
+
+    /// Phím trả từ đang soạn về các phím gốc (mặc định Escape)
+    restore_key: Option<KeyBinding>,
+
+    /// Xử lý composition khi host làm gián đoạn (mất focus, con trỏ di chuyển)
+    interrupt_policy: InterruptPolicy,
+
+    /// Các stage hậu xử lý Action của input method
+    pipeline: Pipeline,
+
+    /// Các subscriber nhận sự kiện
+    observers: Observers,
+
+    /// Đang trong một lời gọi đã được theo dõi (tránh chạy pipeline và phát
+    /// sự kiện hai lần)
+    observing: bool,
 }
 
 impl Engine {
     /// Tạo engine mới với registry rỗng
     pub fn new() -> Self {
-        Self {
-            registry: PluginRegistry::new(),
-            buffer: InputBuffer::new(),
-            current_method: None,
-        }
+        Self::with_registry(PluginRegistry::new())
     }
 
     /// Tạo engine với một registry có sẵn
     pub fn with_registry(registry: PluginRegistry) -> Self {
+        let config = Config::default();
         Self {
             registry,
+            enabled: config.enabled,
+            shorthand: config.shorthand,
             buffer: InputBuffer::new(),
             current_method: None,
+            requested_mode: config.composition_mode,
+            composition_mode: config.composition_mode,
+            candidate_config: config.candidates,
+            history: History::default(),
+            undo_key: config.keys.undo,
+            redo_key: config.keys.redo,
+            restore_key: config.keys.restore,
+            interrupt_policy: config.interrupt_policy,
+            pipeline: Pipeline::new(),
+            observers: Observers::default(),
+            observing: false,
         }
     }
 
@@ -49,6 +153,10 @@ impl Engine {
     pub fn set_language(&mut self, id: &str) -> Result<(), RegistryError> {
         self.registry.set_language(id)?;
         self.update_input_method();
+        self.observers.emit(EngineEvent::LanguageChanged {
+            language: id.to_string(),
+        });
+        self.emit_method_changed();
         Ok(())
     }
 
@@ -56,49 +164,1060 @@ impl Engine {
     pub fn set_input_method(&mut self, id: &str) -> Result<(), RegistryError> {
         self.registry.set_input_method(id)?;
         self.update_input_method();
+        self.emit_method_changed();
         Ok(())
     }
 
+    fn emit_method_changed(&mut self) {
+        if let Some(method) = self.registry.current_input_method_id() {
+            let method = method.to_string();
+            self.observers.emit(EngineEvent::MethodChanged { method });
+        }
+    }
+
+    /// Cấu hình hiện tại của Engine và các plugin đã đăng ký
+    pub fn config(&self) -> Config {
+        let mut plugins = BTreeMap::new();
+        for id in self.registry.languages() {
+            let Some(plugin) = self.registry.get(id) else {
+                continue;
+            };
+            let options: BTreeMap<_, _> = plugin
+                .options()
+                .into_iter()
+                .filter_map(|spec| Some((spec.key.clone(), plugin.option(&spec.key)?)))
+                .collect();
+            if !options.is_empty() {
+                plugins.insert(id.to_string(), options);
+            }
+        }
+
+        let mut stages = BTreeMap::new();
+        for id in self.pipeline.ids() {
+            let Some(stage) = self.pipeline.get(id) else {
+                continue;
+            };
+            let mut options: BTreeMap<_, _> = stage
+                .options()
+                .into_iter()
+                .filter_map(|spec| Some((spec.key.clone(), stage.option(&spec.key)?)))
+                .collect();
+            // Gõ tắt được bật/tắt qua `Config::shorthand`
+            if id != shorthand::ID {
+                options.insert(
+                    STAGE_ENABLED.to_string(),
+                    self.pipeline.is_enabled(id).into(),
+                );
+            }
+            if !options.is_empty() {
+                stages.insert(id.to_string(), options);
+            }
+        }
+
+        Config {
+            enabled: self.enabled,
+            language: self.current_language().map(str::to_string),
+            input_method: self.current_input_method().map(str::to_string),
+            composition_mode: self.requested_mode,
+            interrupt_policy: self.interrupt_policy,
+            candidates: self.candidate_config.clone(),
+            keys: KeysConfig {
+                undo: self.undo_key,
+                redo: self.redo_key,
+                restore: self.restore_key,
+            },
+            shorthand: match self.pipeline.get(shorthand::ID) {
+                Some(_) => self.pipeline.is_enabled(shorthand::ID),
+                None => self.shorthand,
+            },
+            plugins,
+            stages,
+        }
+    }
+
+    /// Schema tùy chọn của các plugin và stage đã đăng ký
+    pub fn option_schema(&self) -> OptionSchema {
+        let plugins = self
+            .registry
+            .languages()
+            .into_iter()
+            .filter_map(|id| Some((id.to_string(), self.registry.get(id)?.options())))
+            .collect();
+        let stages = self
+            .pipeline
+            .ids()
+            .into_iter()
+            .filter_map(|id| {
+                let mut specs = self.pipeline.get(id)?.options();
+                specs.push(enabled_spec());
+                Some((id.to_string(), specs))
+            })
+            .collect();
+        OptionSchema { plugins, stages }
+    }
+
+    /// Kiểm tra cấu hình với các plugin đã đăng ký, không áp dụng
+    ///
+    /// # Returns
+    /// Tất cả lỗi tìm thấy (rỗng nếu hợp lệ)
+    pub fn validate_config(&self, config: &Config) -> Vec<ConfigError> {
+        self.prune_config(&mut config.clone())
+    }
+
+    /// Bỏ các mục không hợp lệ khỏi `config`
+    ///
+    /// Mục không hợp lệ được thay bằng giá trị hiện tại của Engine (hoặc
+    /// bỏ hẳn nếu không có), để phần còn lại vẫn áp dụng được.
+    ///
+    /// # Returns
+    /// Một lỗi cho mỗi mục bị bỏ
+    fn prune_config(&self, config: &mut Config) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+
+        if let Some(id) = &config.language {
+            if self.registry.get(id).is_none() {
+                errors.push(ConfigError::UnknownLanguage(id.clone()));
+                config.language = None;
+            }
+        }
+        let language = config.language.as_deref().or(self.current_language());
+        if let Some(method) = &config.input_method {
+            let known = language
+                .and_then(|id| self.registry.get(id))
+                .is_some_and(|plugin| plugin.input_methods().contains(&method.as_str()));
+            if !known {
+                errors.push(ConfigError::UnknownInputMethod {
+                    language: language.unwrap_or_default().to_string(),
+                    method: method.clone(),
+                });
+                config.input_method = None;
+            }
+        }
+
+        if config.candidates.page_size == 0 {
+            errors.push(ConfigError::InvalidValue {
+                field: "candidates.page_size".to_string(),
+                message: "must be at least 1".to_string(),
+            });
+            config.candidates.page_size = self.candidate_config.page_size;
+        }
+        let keys = &config.candidates.selection_keys;
+        if keys.iter().enumerate().any(|(i, c)| keys[..i].contains(c)) {
+            errors.push(ConfigError::InvalidValue {
+                field: "candidates.selection_keys".to_string(),
+                message: "keys must be distinct".to_string(),
+            });
+            config.candidates.selection_keys = self.candidate_config.selection_keys.clone();
+        }
+
+        config.plugins.retain(|plugin_id, options| {
+            let Some(plugin) = self.registry.get(plugin_id) else {
+                errors.push(ConfigError::UnknownPlugin(plugin_id.clone()));
+                return false;
+            };
+            let specs = plugin.options();
+            prune_options(plugin_id, options, &specs, &mut errors, |key| {
+                plugin.option(key)
+            });
+            true
+        });
+
+        config.stages.retain(|stage_id, options| {
+            let Some(stage) = self.pipeline.get(stage_id) else {
+                errors.push(ConfigError::UnknownStage(stage_id.clone()));
+                return false;
+            };
+            let mut specs = stage.options();
+            specs.push(enabled_spec());
+            prune_options(stage_id, options, &specs, &mut errors, |key| {
+                if key == STAGE_ENABLED {
+                    Some(self.pipeline.is_enabled(stage_id).into())
+                } else {
+                    stage.option(key)
+                }
+            });
+            true
+        });
+
+        errors
+    }
+
+    /// Áp dụng cấu hình ngay lập tức
+    ///
+    /// Các mục không hợp lệ (xem `validate_config`) bị bỏ qua và giữ giá
+    /// trị hiện tại; phần còn lại vẫn được áp dụng. Tùy chọn plugin không
+    /// có trong `config` trở về giá trị mặc định. Composition đang dở bị bỏ
+    /// nếu input method phải tạo lại (đổi ngôn ngữ, input method, tùy chọn
+    /// plugin hoặc chế độ soạn thảo) hoặc bộ gõ bị tắt.
+    ///
+    /// # Returns
+    /// Tất cả lỗi của các mục bị bỏ qua, nếu có
+    pub fn set_config(&mut self, mut config: Config) -> Result<(), Vec<ConfigError>> {
+        let mut errors = self.prune_config(&mut config);
+
+        // Tùy chọn plugin (input method của plugin hiện tại phải tạo lại)
+        let current = self.current_language().map(str::to_string);
+        let mut options_changed = false;
+        let ids: Vec<String> = self
+            .registry
+            .languages()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        for id in ids {
+            let Some(plugin) = self.registry.get_mut(&id) else {
+                continue;
+            };
+            let values = config.plugins.get(&id);
+            for spec in plugin.options() {
+                let value = values
+                    .and_then(|v| v.get(&spec.key))
+                    .unwrap_or(&spec.default);
+                if plugin.option(&spec.key).as_ref() == Some(value) {
+                    continue;
+                }
+                if let Err(message) = plugin.set_option(&spec.key, value) {
+                    errors.push(ConfigError::InvalidOption {
+                        plugin: id.clone(),
+                        key: spec.key.clone(),
+                        message,
+                    });
+                    continue;
+                }
+                options_changed |= current.as_deref() == Some(id.as_str());
+            }
+        }
+
+        // Các stage: tùy chọn không có trong `config` trở về mặc định
+        let ids: Vec<String> = self.pipeline.ids().into_iter().map(String::from).collect();
+        for id in ids {
+            let values = config.stages.get(&id);
+            let default = id != shorthand::ID || config.shorthand;
+            let enabled = values
+                .and_then(|v| v.get(STAGE_ENABLED))
+                .and_then(OptionValue::as_bool)
+                .unwrap_or(default);
+            self.pipeline.set_enabled(&id, enabled);
+            let Some(stage) = self.pipeline.get_mut(&id) else {
+                continue;
+            };
+            for spec in stage.options() {
+                let value = values
+                    .and_then(|v| v.get(&spec.key))
+                    .unwrap_or(&spec.default);
+                if stage.option(&spec.key).as_ref() == Some(value) {
+                    continue;
+                }
+                if let Err(message) = stage.set_option(&spec.key, value) {
+                    errors.push(ConfigError::InvalidOption {
+                        plugin: id.clone(),
+                        key: spec.key.clone(),
+                        message,
+                    });
+                }
+            }
+        }
+
+        // Ngôn ngữ và input method (đã kiểm tra ở trên)
+        let mut rebuilt = false;
+        if let Some(language) = &config.language {
+            if self.current_language() != Some(language.as_str()) {
+                match self.set_language(language) {
+                    Ok(()) => rebuilt = true,
+                    Err(_) => errors.push(ConfigError::UnknownLanguage(language.clone())),
+                }
+            }
+        }
+        if let Some(method) = &config.input_method {
+            if self.current_input_method() != Some(method.as_str()) {
+                match self.set_input_method(method) {
+                    Ok(()) => rebuilt = true,
+                    Err(_) => errors.push(ConfigError::UnknownInputMethod {
+                        language: self.current_language().unwrap_or_default().to_string(),
+                        method: method.clone(),
+                    }),
+                }
+            }
+        }
+        if options_changed && !rebuilt {
+            self.update_input_method();
+        }
+
+        if config.composition_mode != self.requested_mode {
+            self.set_composition_mode(config.composition_mode);
+        }
+        if config.candidates != self.candidate_config {
+            self.set_candidate_config(config.candidates);
+        }
+        self.undo_key = config.keys.undo;
+        self.redo_key = config.keys.redo;
+        self.restore_key = config.keys.restore;
+        self.interrupt_policy = config.interrupt_policy;
+        self.shorthand = match self.pipeline.get(shorthand::ID) {
+            Some(_) => self.pipeline.is_enabled(shorthand::ID),
+            None => config.shorthand,
+        };
+        self.set_enabled(config.enabled);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Bật/tắt bộ gõ
+    ///
+    /// Khi tắt, composition đang dở bị bỏ và mọi phím được chuyển thẳng cho
+    /// host (`PassThrough`).
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if self.enabled && !enabled {
+            self.reset();
+        }
+        self.enabled = enabled;
+    }
+
+    /// Bộ gõ đang bật?
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Đăng ký subscriber nhận sự kiện của Engine
+    ///
+    /// Subscriber được gọi đồng bộ trong các lời gọi `process`, `undo`,
+    /// `set_language`... theo thứ tự đăng ký.
+    pub fn subscribe(&mut self, observer: Box<dyn EngineObserver>) -> SubscriptionId {
+        self.observers.subscribe(observer)
+    }
+
+    /// Hủy đăng ký subscriber, trả về `false` nếu ID không tồn tại
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.observers.unsubscribe(id)
+    }
+
+    /// Thêm một stage vào pipeline hậu xử lý
+    ///
+    /// Stage nhận mọi Action mà Engine trả về cho host (từ `process`,
+    /// `undo`, `select_candidate`...) khi bộ gõ đang bật, theo thứ tự
+    /// `Middleware::order`. Subscriber nhận sự kiện theo Action sau pipeline.
+    ///
+    /// Stage gõ tắt (`shorthand::ID`) bật theo `Config::shorthand`.
+    pub fn add_stage(&mut self, stage: Box<dyn Middleware>) -> Result<(), RegistryError> {
+        let id = stage.id().to_string();
+        self.pipeline.add(stage)?;
+        if id == shorthand::ID {
+            self.pipeline.set_enabled(&id, self.shorthand);
+        }
+        Ok(())
+    }
+
+    /// Thay stage cùng ID (giữ trạng thái bật/tắt), hoặc thêm nếu chưa có
+    ///
+    /// Dùng khi dữ liệu của stage đổi, ví dụ bảng gõ tắt được tải lại.
+    pub fn replace_stage(&mut self, stage: Box<dyn Middleware>) -> Option<Box<dyn Middleware>> {
+        if self.pipeline.get(stage.id()).is_none() {
+            let _ = self.add_stage(stage);
+            return None;
+        }
+        self.pipeline.replace(stage)
+    }
+
+    /// Bỏ stage `id` khỏi pipeline
+    pub fn remove_stage(&mut self, id: &str) -> Option<Box<dyn Middleware>> {
+        self.pipeline.remove(id)
+    }
+
+    /// Đặt tùy chọn `key` của stage `id` chỉ cho context đang active
+    ///
+    /// Ví dụ: bảng mã đầu ra riêng cho một ứng dụng dùng font cũ. Giá trị
+    /// đi theo context (`SessionManager` cất nó khi focus chuyển đi, kể cả
+    /// khi cấu hình được tải lại); `None` trở về giá trị trong
+    /// `Config::stages`.
+    pub fn set_session_option(
+        &mut self,
+        id: &str,
+        key: &str,
+        value: Option<OptionValue>,
+    ) -> Result<(), ConfigError> {
+        let Some(stage) = self.pipeline.get_mut(id) else {
+            return Err(ConfigError::UnknownStage(id.to_string()));
+        };
+        let Some(spec) = stage.options().into_iter().find(|spec| spec.key == key) else {
+            return Err(ConfigError::UnknownOption {
+                plugin: id.to_string(),
+                key: key.to_string(),
+            });
+        };
+        let invalid = |message| ConfigError::InvalidOption {
+            plugin: id.to_string(),
+            key: key.to_string(),
+            message,
+        };
+        if let Some(value) = &value {
+            spec.validate(value).map_err(invalid)?;
+        }
+        stage
+            .set_session_option(key, value.as_ref())
+            .map_err(invalid)
+    }
+
+    /// Pipeline hậu xử lý (bật/tắt, truy cập từng stage)
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+
+    /// Pipeline hậu xử lý (mutable)
+    pub fn pipeline_mut(&mut self) -> &mut Pipeline {
+        &mut self.pipeline
+    }
+
+    /// Chạy `f`, cho Action nó trả về qua pipeline rồi phát `Replaced` /
+    /// `Committed`
+    ///
+    /// Lời gọi lồng nhau (ví dụ `process` gọi `undo`) chỉ chạy pipeline và
+    /// phát sự kiện ở lời gọi ngoài cùng.
+    fn observed(&mut self, key: Option<&KeyEvent>, f: impl FnOnce(&mut Self) -> Action) -> Action {
+        if self.observing || (self.pipeline.is_empty() && self.observers.is_empty()) {
+            return f(self);
+        }
+
+        self.observing = true;
+        let before = self.history.composed().to_string();
+        let mut action = f(self);
+        self.observing = false;
+
+        if self.enabled && !self.pipeline.is_empty() {
+            let context = StageContext {
+                key,
+                before: &before,
+                composing: self.history.composed(),
+                mode: self.composition_mode,
+            };
+            action = self.pipeline.run(action, &context);
+        }
+        if !self.observers.is_empty() {
+            self.notify(&before, &action);
+        }
+        action
+    }
+
+    /// Phát sự kiện cho Action; `before` là văn bản composition trước đó
+    fn notify(&mut self, before: &str, action: &Action) {
+        if let Action::Replace {
+            backspace_count,
+            text,
+        } = action
+        {
+            self.observers.emit(EngineEvent::Replaced {
+                backspace_count: *backspace_count,
+                text: text.clone(),
+            });
+        }
+
+        // Composition vẫn tiếp tục: chưa có gì cố định
+        if !self.buffer.is_empty() {
+            return;
+        }
+
+        let text = match action {
+            Action::Commit(text) if self.composition_mode == CompositionMode::Preedit => {
+                text.clone()
+            }
+            Action::Commit(text) => format!("{}{}", before, text),
+            Action::Replace {
+                backspace_count,
+                text,
+            } => {
+                let keep = before.chars().count().saturating_sub(*backspace_count);
+                before.chars().take(keep).chain(text.chars()).collect()
+            }
+            Action::UpdatePreedit { text, .. } => text.clone(),
+            Action::ShowCandidates(_) | Action::HideCandidates => String::new(),
+            Action::DoNothing | Action::PassThrough | Action::CommitPreedit => before.to_string(),
+        };
+        if !text.is_empty() {
+            self.observers.emit(EngineEvent::Committed { text });
+        }
+    }
+
     /// Cập nhật input method instance dựa trên selection hiện tại
     fn update_input_method(&mut self) {
         self.current_method = None;
+        self.buffer.clear();
+        self.history.clear();
+        self.pipeline.reset();
 
         if let Some(plugin) = self.registry.current_plugin() {
             if let Some(method_id) = self.registry.current_input_method_id() {
                 self.current_method = plugin.create_input_method(method_id);
             }
         }
+
+        self.apply_composition_mode();
     }
 
-    /// Xử lý một keystroke
-    pub fn process(&mut self, key: char) -> Action {
-        if let (Some(method), Some(plugin)) =
-            (&mut self.current_method, self.registry.current_plugin())
+    /// Đặt chế độ soạn thảo (Direct / Preedit)
+    ///
+    /// Composition đang dở sẽ bị bỏ. Chế độ được giữ khi đổi input method.
+    ///
+    /// # Returns
+    /// `true` nếu input method hiện tại hỗ trợ chế độ này. Nếu không,
+    /// engine dùng `CompositionMode::Direct` cho đến khi đổi sang
+    /// input method có hỗ trợ.
+    pub fn set_composition_mode(&mut self, mode: CompositionMode) -> bool {
+        self.reset();
+        self.requested_mode = mode;
+        self.apply_composition_mode();
+        self.composition_mode == mode
+    }
+
+    /// Chế độ soạn thảo thực tế đang dùng
+    pub fn composition_mode(&self) -> CompositionMode {
+        self.composition_mode
+    }
+
+    fn apply_composition_mode(&mut self) {
+        let requested = self.requested_mode;
+        self.composition_mode = match &mut self.current_method {
+            Some(method) => {
+                if method.set_composition_mode(requested) {
+                    requested
+                } else {
+                    method.set_composition_mode(CompositionMode::Direct);
+                    CompositionMode::Direct
+                }
+            }
+            None => requested,
+        };
+    }
+
+    /// Đặt cấu hình cửa sổ candidate
+    pub fn set_candidate_config(&mut self, config: CandidateConfig) {
+        self.candidate_config = config;
+        if let Some(list) = self
+            .current_method
+            .as_mut()
+            .and_then(|m| m.candidates_mut())
         {
-            method.process(key, &mut self.buffer, plugin.lookup())
-        } else {
-            // Không có plugin/method nào active, passthrough
-            Action::DoNothing
+            list.apply_config(&self.candidate_config);
+        }
+    }
+
+    /// Cấu hình cửa sổ candidate hiện tại
+    pub fn candidate_config(&self) -> &CandidateConfig {
+        &self.candidate_config
+    }
+
+    /// Danh sách candidate đang hiển thị (nếu có)
+    pub fn candidates(&self) -> Option<&CandidateList> {
+        self.current_method
+            .as_ref()
+            .and_then(|m| m.candidates())
+            .filter(|list| !list.is_empty())
+    }
+
+    /// Commit candidate theo index tuyệt đối trong danh sách
+    pub fn select_candidate(&mut self, index: usize) -> Action {
+        self.observed(None, |engine| {
+            if engine.candidates().is_none_or(|list| index >= list.len()) {
+                return Action::DoNothing;
+            }
+            engine.history.clear();
+            match &mut engine.current_method {
+                Some(method) => method.select_candidate(index, &mut engine.buffer),
+                None => Action::DoNothing,
+            }
+        })
+    }
+
+    /// Sang trang candidate trước
+    pub fn page_up(&mut self) -> Action {
+        self.update_candidates(CandidateList::page_up)
+    }
+
+    /// Sang trang candidate sau
+    pub fn page_down(&mut self) -> Action {
+        self.update_candidates(CandidateList::page_down)
+    }
+
+    /// Di chuyển lựa chọn `delta` vị trí (âm là lùi)
+    pub fn move_selection(&mut self, delta: isize) -> Action {
+        self.update_candidates(|list| list.move_selection(delta))
+    }
+
+    /// Thay đổi danh sách candidate, trả về `ShowCandidates` nếu có thay đổi
+    fn update_candidates(&mut self, f: impl FnOnce(&mut CandidateList) -> bool) -> Action {
+        match self
+            .current_method
+            .as_mut()
+            .and_then(|m| m.candidates_mut())
+        {
+            Some(list) if !list.is_empty() => {
+                if f(list) {
+                    Action::ShowCandidates(list.clone())
+                } else {
+                    Action::DoNothing
+                }
+            }
+            _ => Action::DoNothing,
+        }
+    }
+
+    /// Áp dụng cấu hình candidate lên danh sách mới mà input method trả về
+    fn finish(&mut self, action: Action) -> Action {
+        if !matches!(action, Action::ShowCandidates(_)) {
+            return action;
+        }
+        match self
+            .current_method
+            .as_mut()
+            .and_then(|m| m.candidates_mut())
+        {
+            Some(list) => {
+                list.apply_config(&self.candidate_config);
+                Action::ShowCandidates(list.clone())
+            }
+            None => action,
+        }
+    }
+
+    /// Phím khi cửa sổ candidate đang mở: chọn, điều hướng, đóng
+    ///
+    /// Trả về `None` nếu phím không thuộc về cửa sổ candidate.
+    fn process_candidate_key(&mut self, key: &KeyEvent) -> Option<Action> {
+        let list = self.candidates()?;
+        if key.is_chord() {
+            return None;
+        }
+
+        let action = match key.code {
+            KeyCode::Up | KeyCode::Left => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Right => self.move_selection(1),
+            KeyCode::PageUp => self.page_up(),
+            KeyCode::PageDown => self.page_down(),
+            KeyCode::Escape => {
+                self.reset();
+                Action::HideCandidates
+            }
+            _ => {
+                let index = key.char().and_then(|c| list.index_for_key(c))?;
+                self.select_candidate(index)
+            }
+        };
+        Some(action)
+    }
+
+    /// Đặt phím tắt undo (`None` để tắt)
+    pub fn set_undo_key(&mut self, key: Option<KeyBinding>) {
+        self.undo_key = key;
+    }
+
+    /// Phím tắt undo hiện tại
+    pub fn undo_key(&self) -> Option<KeyBinding> {
+        self.undo_key
+    }
+
+    /// Đặt phím tắt redo (`None` để tắt)
+    pub fn set_redo_key(&mut self, key: Option<KeyBinding>) {
+        self.redo_key = key;
+    }
+
+    /// Phím tắt redo hiện tại
+    pub fn redo_key(&self) -> Option<KeyBinding> {
+        self.redo_key
+    }
+
+    /// Đặt phím trả về phím gốc (`None` để tắt)
+    pub fn set_restore_key(&mut self, key: Option<KeyBinding>) {
+        self.restore_key = key;
+    }
+
+    /// Phím trả về phím gốc hiện tại
+    pub fn restore_key(&self) -> Option<KeyBinding> {
+        self.restore_key
+    }
+
+    /// Đặt cách xử lý composition khi host làm gián đoạn
+    pub fn set_interrupt_policy(&mut self, policy: InterruptPolicy) {
+        self.interrupt_policy = policy;
+    }
+
+    /// Cách xử lý composition khi host làm gián đoạn
+    pub fn interrupt_policy(&self) -> InterruptPolicy {
+        self.interrupt_policy
+    }
+
+    /// Ô nhập liệu nhận focus
+    ///
+    /// Composition còn sót lại (host không báo mất focus) được kết thúc
+    /// như `on_focus_out`, vì văn bản xung quanh có thể đã thay đổi.
+    pub fn on_focus_in(&mut self) -> Action {
+        self.interrupt()
+    }
+
+    /// Ô nhập liệu mất focus
+    pub fn on_focus_out(&mut self) -> Action {
+        self.interrupt()
+    }
+
+    /// Con trỏ bị di chuyển bởi host (không phải do engine sửa văn bản)
+    pub fn on_cursor_moved(&mut self) -> Action {
+        self.interrupt()
+    }
+
+    /// Người dùng click chuột vào văn bản
+    pub fn on_mouse_click(&mut self) -> Action {
+        self.interrupt()
+    }
+
+    /// Kết thúc composition theo `interrupt_policy`
+    ///
+    /// # Returns
+    /// - `HideCandidates` nếu cửa sổ candidate đang mở (không chọn gì)
+    /// - Ở chế độ Preedit: `CommitPreedit` (`InterruptPolicy::Commit`) hoặc
+    ///   preedit rỗng (`InterruptPolicy::Discard`)
+    /// - `DoNothing` nếu không có gì đang soạn, hoặc ở chế độ Direct (văn
+    ///   bản đã nằm trong tài liệu)
+    fn interrupt(&mut self) -> Action {
+        self.observed(None, |engine| {
+            let had_candidates = engine.candidates().is_some();
+            let had_preedit =
+                engine.composition_mode == CompositionMode::Preedit && !engine.buffer.is_empty();
+            engine.reset();
+
+            if had_candidates {
+                Action::HideCandidates
+            } else if had_preedit {
+                match engine.interrupt_policy {
+                    InterruptPolicy::Commit => Action::CommitPreedit,
+                    InterruptPolicy::Discard => Action::preedit(String::new()),
+                }
+            } else {
+                Action::DoNothing
+            }
+        })
+    }
+
+    /// Các phím gốc của từ đang soạn, nếu khác với văn bản đang hiển thị
+    ///
+    /// Từ vừa được soạn tiếp từ surrounding text mà chưa gõ phím nào thì
+    /// không có gì để trả về.
+    fn raw_input(&self) -> Option<String> {
+        if self.history.is_empty() {
+            return None;
         }
+        self.current_method
+            .as_ref()
+            .and_then(|m| m.raw_input())
+            .filter(|raw| !raw.is_empty() && raw != self.history.composed())
+    }
+
+    /// Từ đang soạn có thể trả về phím gốc không?
+    pub fn can_restore_raw(&self) -> bool {
+        self.raw_input().is_some()
+    }
+
+    /// Trả từ đang soạn về đúng các phím đã gõ (ví dụ: "tiếng" → "tieesng")
+    ///
+    /// Từ được kết thúc: các phím gõ tiếp theo bắt đầu từ mới.
+    ///
+    /// # Returns
+    /// `Replace` (chế độ Direct) hoặc `Commit` (chế độ Preedit) với các
+    /// phím gốc; `PassThrough` nếu không có gì để trả về.
+    pub fn restore_raw(&mut self) -> Action {
+        self.observed(None, |engine| {
+            let Some(raw) = engine.raw_input() else {
+                return Action::PassThrough;
+            };
+            let old = engine.history.composed().to_string();
+            engine.reset();
+
+            match engine.composition_mode {
+                CompositionMode::Direct => history::replace_action(&old, &raw),
+                CompositionMode::Preedit => Action::Commit(raw),
+            }
+        })
+    }
+
+    /// Host cung cấp văn bản trước con trỏ (surrounding text)
+    ///
+    /// Khi không có từ đang soạn, input method hiện tại được soạn tiếp từ đã
+    /// commit ngay trước con trỏ: đặt con trỏ sau "viet" rồi gõ "j" cho ra
+    /// "việt" (`Replace` xóa đúng từ cũ). Chỉ áp dụng ở chế độ Direct, vì
+    /// ở chế độ Preedit từ đã commit không còn nằm trong preedit.
+    ///
+    /// # Returns
+    /// `true` nếu từ trước con trỏ được nhận làm từ đang soạn
+    pub fn set_surrounding_text(&mut self, text_before: &str) -> bool {
+        if !self.enabled
+            || self.composition_mode != CompositionMode::Direct
+            || !self.buffer.is_empty()
+            || self.candidates().is_some()
+        {
+            return false;
+        }
+
+        self.reset();
+        let Some(method) = &mut self.current_method else {
+            return false;
+        };
+        let count = method.resume(text_before, &mut self.buffer);
+        if count == 0 {
+            self.reset();
+            return false;
+        }
+
+        let chars: Vec<char> = text_before.chars().collect();
+        let word: String = chars[chars.len().saturating_sub(count)..].iter().collect();
+        self.history.resume(&word);
+        true
+    }
+
+    /// Từ đang soạn có phép biến đổi nào để undo không?
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    /// Có phép biến đổi nào vừa undo để redo không?
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Undo phép biến đổi gần nhất của từ đang soạn
+    ///
+    /// Bỏ phím biến đổi gần nhất (dấu thanh, dấu mũ, móc...) và tính lại
+    /// từ; gọi nhiều lần để undo lần lượt các phép biến đổi trước đó. Các
+    /// chữ cái gõ thẳng không bị ảnh hưởng.
+    ///
+    /// # Returns
+    /// Action đưa văn bản trên host về trạng thái mới (`Replace` hoặc
+    /// `UpdatePreedit`), hoặc `PassThrough` nếu không có gì để undo.
+    pub fn undo(&mut self) -> Action {
+        self.observed(None, |engine| {
+            let old = engine.history.composed().to_string();
+            match engine.history.undo() {
+                Some(keys) => engine.replay(&old, keys),
+                None => Action::PassThrough,
+            }
+        })
+    }
+
+    /// Làm lại phép biến đổi vừa undo
+    pub fn redo(&mut self) -> Action {
+        self.observed(None, |engine| {
+            let old = engine.history.composed().to_string();
+            match engine.history.redo() {
+                Some(keys) => engine.replay(&old, keys),
+                None => Action::PassThrough,
+            }
+        })
+    }
+
+    /// Gõ lại các phím trên input method đã reset, trả về Action đồng bộ host
+    fn replay(&mut self, old: &str, keys: Vec<KeyEvent>) -> Action {
+        self.buffer.clear();
+        if let Some(method) = &mut self.current_method {
+            method.reset();
+            let resumed = self.history.resumed();
+            if !resumed.is_empty() {
+                method.resume(resumed, &mut self.buffer);
+            }
+        }
+
+        for key in keys {
+            let action = self.dispatch(&key);
+            let composing = !self.buffer.is_empty();
+            self.history.replay(key, &action, composing);
+        }
+
+        let new = self.history.composed();
+        match self.composition_mode {
+            CompositionMode::Direct => history::replace_action(old, new),
+            CompositionMode::Preedit => Action::preedit(new.to_string()),
+        }
+    }
+
+    /// Xử lý một keystroke
+    ///
+    /// Nhận `KeyEvent` hoặc `char` (tự chuyển bằng `KeyEvent::from_char`).
+    ///
+    /// - Bộ gõ đang tắt, sự kiện nhả phím, phím modifier đơn lẻ → `PassThrough`
+    /// - Phím tắt undo/redo khi từ đang soạn có lịch sử → `undo()`/`redo()`
+    /// - Phím trả về phím gốc (mặc định Escape) khi từ đang soạn đã bị
+    ///   biến đổi → `restore_raw()`
+    /// - Khi cửa sổ candidate đang mở: phím chọn → commit candidate,
+    ///   mũi tên / Page Up / Page Down → di chuyển lựa chọn, Escape → đóng
+    /// - Tổ hợp phím tắt (Ctrl/Alt/Meta), phím điều hướng và phím không
+    ///   sinh ký tự (F1..F24, phím khác) → reset composition rồi
+    ///   `PassThrough` (hoặc `CommitPreedit` nếu đang hiển thị preedit)
+    /// - Backspace → `process_backspace()`
+    /// - Phím có sinh ký tự → chuyển cho input method hiện tại
+    pub fn process(&mut self, key: impl Into<KeyEvent>) -> Action {
+        let key = key.into();
+        self.observed(Some(&key), |engine| engine.process_key(key))
+    }
+
+    fn process_key(&mut self, key: KeyEvent) -> Action {
+        if !self.enabled || !key.is_press() {
+            return Action::PassThrough;
+        }
+
+        if self.undo_key.is_some_and(|b| b.matches(&key)) && self.can_undo() {
+            return self.undo();
+        }
+        if self.redo_key.is_some_and(|b| b.matches(&key)) && self.can_redo() {
+            return self.redo();
+        }
+
+        if let Some(action) = self.process_candidate_key(&key) {
+            return action;
+        }
+
+        if self.restore_key.is_some_and(|b| b.matches(&key)) && self.can_restore_raw() {
+            return self.restore_raw();
+        }
+
+        // Phím không sinh ký tự mà frontend vẫn gửi tới (phím chức năng,
+        // dấu câu chưa rõ ký tự...) có thể đã đưa chữ vào ứng dụng, nên từ
+        // đang soạn không còn liền với con trỏ
+        let ends_word =
+            key.char().is_none() && !matches!(key.code, KeyCode::Backspace | KeyCode::Modifier);
+        if key.is_chord() || key.is_navigation() || ends_word {
+            // Cửa sổ candidate đang mở thì đóng lại; ở chế độ Preedit,
+            // composition đang hiện phải được commit trước
+            let had_candidates = self.candidates().is_some();
+            let had_preedit =
+                self.composition_mode == CompositionMode::Preedit && !self.buffer.is_empty();
+            self.reset();
+            return if had_candidates {
+                Action::HideCandidates
+            } else if had_preedit {
+                Action::CommitPreedit
+            } else {
+                Action::PassThrough
+            };
+        }
+
+        if key.code == KeyCode::Backspace {
+            return self.process_backspace();
+        }
+
+        if key.char().is_none() {
+            return Action::PassThrough;
+        }
+
+        let action = self.dispatch(&key);
+        self.history.record(key, &action, !self.buffer.is_empty());
+
+        let rejected = self
+            .current_method
+            .as_ref()
+            .is_some_and(|m| m.rejected_last_key());
+        if rejected {
+            let word = self.buffer.to_string();
+            self.observers.emit(EngineEvent::Rejected { key, word });
+        }
+        action
     }
 
     /// Xử lý phím Backspace
     pub fn process_backspace(&mut self) -> Action {
-        if let Some(method) = &mut self.current_method {
+        if !self.enabled {
+            return Action::PassThrough;
+        }
+        let key = KeyEvent::new(KeyCode::Backspace, None);
+        self.observed(Some(&key), |engine| {
+            let action = engine.dispatch(&key);
+            engine
+                .history
+                .record(key, &action, !engine.buffer.is_empty());
+            action
+        })
+    }
+
+    /// Chuyển phím xuống input method hiện tại
+    fn dispatch(&mut self, key: &KeyEvent) -> Action {
+        let Some(method) = &mut self.current_method else {
+            // Không có plugin/method nào active, passthrough
+            return Action::PassThrough;
+        };
+
+        let action = if key.code == KeyCode::Backspace {
             method.process_backspace(&mut self.buffer)
+        } else if let Some(plugin) = self.registry.current_plugin() {
+            method.process(key, &mut self.buffer, plugin.lookup())
         } else {
-            Action::DoNothing
-        }
+            Action::PassThrough
+        };
+        self.finish(action)
     }
 
     /// Reset buffer và input method state
     pub fn reset(&mut self) {
         self.buffer.clear();
+        self.history.clear();
+        self.pipeline.reset();
         if let Some(method) = &mut self.current_method {
             method.reset();
         }
     }
 
+    /// Language và input method mà trạng thái soạn thảo hiện tại thuộc về
+    fn method_key(&self) -> (Option<String>, Option<String>) {
+        (
+            self.registry.current_language_id().map(str::to_string),
+            self.registry.current_input_method_id().map(str::to_string),
+        )
+    }
+
+    /// Lấy trạng thái soạn thảo hiện tại ra, thay bằng trạng thái mới
+    ///
+    /// Dùng bởi `SessionManager` khi focus chuyển sang context khác.
+    pub(crate) fn take_session(&mut self) -> Session {
+        let session = Session {
+            buffer: std::mem::take(&mut self.buffer),
+            method: self.current_method.take(),
+            method_key: self.method_key(),
+            composition_mode: self.composition_mode,
+            history: std::mem::take(&mut self.history),
+        };
+        self.update_input_method();
+        session
+    }
+
+    /// Khôi phục trạng thái soạn thảo đã lấy ra bằng `take_session`
+    ///
+    /// Nếu input method hoặc chế độ soạn thảo đã đổi trong lúc context
+    /// không active, trạng thái cũ bị bỏ và context bắt đầu lại từ đầu.
+    pub(crate) fn restore_session(&mut self, session: Session) {
+        if session.method_key != self.method_key()
+            || session.composition_mode != self.composition_mode
+        {
+            self.update_input_method();
+            return;
+        }
+
+        self.buffer = session.buffer;
+        self.current_method = session.method;
+        self.history = session.history;
+        if let Some(list) = self
+            .current_method
+            .as_mut()
+            .and_then(|m| m.candidates_mut())
+        {
+            list.apply_config(&self.candidate_config);
+        }
+    }
+
+    /// Lấy trạng thái riêng của context đang active ra khỏi các stage
+    pub(crate) fn take_stage_sessions(&mut self) -> Vec<(String, StageSession)> {
+        self.pipeline.take_sessions()
+    }
+
+    /// Khôi phục trạng thái đã lấy ra bằng `take_stage_sessions`
+    pub(crate) fn restore_stage_sessions(&mut self, sessions: Vec<(String, StageSession)>) {
+        self.pipeline.restore_sessions(sessions);
+    }
+
     /// Lấy nội dung buffer hiện tại
     pub fn buffer_content(&self) -> String {
         self.buffer.to_string()
@@ -109,13 +1228,22 @@ impl Engine {
         self.registry.languages()
     }
 
-    /// Lấy danh sách input methods của ngôn ngữ hiện tại
+    /// Metadata của các ngôn ngữ đã đăng ký, theo thứ tự hiển thị
+    pub fn catalog(&self) -> &[PluginMetadata] {
+        self.registry.catalog()
+    }
+
+    /// Metadata của ngôn ngữ `id`
+    pub fn metadata(&self, id: &str) -> Option<&PluginMetadata> {
+        self.registry.metadata(id)
+    }
+
+    /// Lấy danh sách input methods của ngôn ngữ hiện tại, theo thứ tự hiển thị
     pub fn input_methods(&self) -> Vec<&str> {
-        if let Some(plugin) = self.registry.current_plugin() {
-            plugin.input_methods()
-        } else {
-            Vec::new()
-        }
+        self.current_language()
+            .and_then(|id| self.registry.metadata(id))
+            .map(|metadata| metadata.methods.iter().map(|m| m.id.as_str()).collect())
+            .unwrap_or_default()
     }
 
     /// Lấy ID ngôn ngữ hiện tại
@@ -138,6 +1266,7 @@ impl Default for Engine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::key::Modifiers;
 
     #[test]
     fn test_engine_new() {
@@ -150,6 +1279,192 @@ mod tests {
     fn test_engine_process_without_plugin() {
         let mut engine = Engine::new();
         let action = engine.process('a');
-        assert_eq!(action, Action::DoNothing);
+        assert_eq!(action, Action::PassThrough);
+    }
+
+    #[test]
+    fn test_engine_passes_through_release_and_chords() {
+        let mut engine = Engine::new();
+        assert_eq!(
+            engine.process(KeyEvent::from_char('a').released()),
+            Action::PassThrough
+        );
+        assert_eq!(
+            engine.process(KeyEvent::from_char('c').with_modifiers(Modifiers::CONTROL)),
+            Action::PassThrough
+        );
+        assert_eq!(
+            engine.process(KeyEvent::new(KeyCode::Left, None)),
+            Action::PassThrough
+        );
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut engine = Engine::new();
+        let mut config = Config {
+            language: Some("klingon".to_string()),
+            ..Config::default()
+        };
+        config.candidates.page_size = 0;
+        config.set_plugin_option("klingon", "style", true);
+
+        let errors = engine.validate_config(&config);
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors[0],
+            ConfigError::UnknownLanguage("klingon".to_string())
+        );
+        assert_eq!(engine.set_config(config), Err(errors));
+        assert_eq!(engine.config(), Config::default());
+    }
+
+    #[test]
+    fn test_config_disabled() {
+        let mut engine = Engine::new();
+        let config = Config {
+            enabled: false,
+            keys: KeysConfig {
+                undo: None,
+                ..KeysConfig::default()
+            },
+            ..Config::default()
+        };
+        engine.set_config(config.clone()).unwrap();
+
+        assert!(!engine.is_enabled());
+        assert_eq!(engine.undo_key(), None);
+        assert_eq!(engine.config(), config);
+        assert_eq!(engine.process_backspace(), Action::PassThrough);
+    }
+
+    /// Stage thay phím chữ được `PassThrough` bằng chữ hoa
+    #[derive(Default)]
+    struct Shout {
+        suffix: String,
+    }
+
+    impl Middleware for Shout {
+        fn id(&self) -> &str {
+            "shout"
+        }
+
+        fn process(&mut self, action: Action, context: &StageContext) -> Action {
+            match (action, context.key.and_then(|key| key.char())) {
+                (Action::PassThrough, Some(c)) => {
+                    Action::Commit(format!("{}{}", c.to_uppercase(), self.suffix))
+                }
+                (action, _) => action,
+            }
+        }
+
+        fn options(&self) -> Vec<OptionSpec> {
+            vec![OptionSpec::text("suffix", "", "Hậu tố")]
+        }
+
+        fn option(&self, key: &str) -> Option<OptionValue> {
+            (key == "suffix").then(|| self.suffix.as_str().into())
+        }
+
+        fn set_option(&mut self, key: &str, value: &OptionValue) -> Result<(), String> {
+            match (key, value) {
+                ("suffix", OptionValue::Text(text)) => {
+                    self.suffix = text.clone();
+                    Ok(())
+                }
+                _ => Err(format!("unknown option '{}'", key)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_pipeline_rewrites_actions() {
+        let mut engine = Engine::new();
+        engine.add_stage(Box::new(Shout::default())).unwrap();
+        assert!(engine.add_stage(Box::new(Shout::default())).is_err());
+
+        assert_eq!(engine.process('a'), Action::Commit("A".to_string()));
+        assert_eq!(engine.process_backspace(), Action::PassThrough);
+
+        engine.pipeline_mut().set_enabled("shout", false);
+        assert_eq!(engine.process('a'), Action::PassThrough);
+        engine.pipeline_mut().set_enabled("shout", true);
+
+        // Bộ gõ tắt: không chạy pipeline
+        engine.set_enabled(false);
+        assert_eq!(engine.process('a'), Action::PassThrough);
+    }
+
+    #[test]
+    fn test_stage_config() {
+        let mut engine = Engine::new();
+        engine.add_stage(Box::new(Shout::default())).unwrap();
+
+        let mut config = Config::default();
+        config.set_stage_option("shout", "suffix", "!");
+        engine.set_config(config).unwrap();
+        assert_eq!(engine.process('a'), Action::Commit("A!".to_string()));
+        assert_eq!(
+            engine.config().stage_option("shout", "enabled"),
+            Some(&true.into())
+        );
+
+        let mut config = Config::default();
+        config.set_stage_option("shout", "enabled", false);
+        engine.set_config(config).unwrap();
+        assert!(!engine.pipeline().is_enabled("shout"));
+        assert_eq!(
+            engine.config().stage_option("shout", "suffix"),
+            Some(&"".into())
+        );
+
+        let mut config = Config::default();
+        config.set_stage_option("shout", "volume", 11_i64);
+        config.set_stage_option("whisper", "enabled", true);
+        assert_eq!(
+            engine.validate_config(&config),
+            vec![
+                ConfigError::UnknownOption {
+                    plugin: "shout".to_string(),
+                    key: "volume".to_string(),
+                },
+                ConfigError::UnknownStage("whisper".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_option_schema() {
+        let mut engine = Engine::new();
+        engine.add_stage(Box::new(Shout::default())).unwrap();
+
+        let schema = engine.option_schema();
+        assert!(schema.plugins.is_empty());
+        let keys: Vec<_> = schema.stages["shout"].iter().map(|s| &s.key).collect();
+        assert_eq!(keys, ["suffix", "enabled"]);
+    }
+
+    #[test]
+    fn test_session_option_errors() {
+        let mut engine = Engine::new();
+        engine.add_stage(Box::new(Shout::default())).unwrap();
+
+        assert_eq!(
+            engine.set_session_option("whisper", "suffix", None),
+            Err(ConfigError::UnknownStage("whisper".to_string()))
+        );
+        assert!(matches!(
+            engine.set_session_option("shout", "volume", None),
+            Err(ConfigError::UnknownOption { .. })
+        ));
+        assert!(matches!(
+            engine.set_session_option("shout", "suffix", Some(true.into())),
+            Err(ConfigError::InvalidOption { .. })
+        ));
+        // Stage không hỗ trợ tùy chọn riêng cho context
+        assert!(matches!(
+            engine.set_session_option("shout", "suffix", Some("!".into())),
+            Err(ConfigError::InvalidOption { .. })
+        ));
     }
 }
@@ -0,0 +1,137 @@
+//! Vikey Core - Engine Event Module
+//!
+//! Sự kiện Engine phát ra cho UI (tray, chỉ báo ngôn ngữ), broker, thống
+//! kê và logging, để các thành phần này theo dõi Engine mà không phải bọc
+//! từng lời gọi.
+
+use crate::key::KeyEvent;
+
+/// Sự kiện của Engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineEvent {
+    /// Composition kết thúc, `text` đã nằm cố định trong tài liệu
+    ///
+    /// Ở chế độ Direct là từ vừa soạn xong (ký tự phân cách do host gõ
+    /// không nằm trong `text`, trừ khi engine tự ghi nó, ví dụ khi
+    /// auto-restore); ở chế độ Preedit là văn bản preedit được commit.
+    Committed {
+        /// Văn bản đã commit
+        text: String,
+    },
+
+    /// Engine yêu cầu host xóa `backspace_count` ký tự rồi gõ `text`
+    Replaced {
+        /// Số ký tự bị xóa
+        backspace_count: usize,
+        /// Văn bản mới
+        text: String,
+    },
+
+    /// Ngôn ngữ hiện tại thay đổi
+    LanguageChanged {
+        /// ID language plugin mới
+        language: String,
+    },
+
+    /// Input method hiện tại thay đổi
+    MethodChanged {
+        /// ID input method mới
+        method: String,
+    },
+
+    /// Kiểm tra chính tả từ chối phím: từ không còn là từ hợp lệ nên phím
+    /// được giữ nguyên như đã gõ
+    Rejected {
+        /// Phím bị từ chối
+        key: KeyEvent,
+        /// Từ đang soạn sau phím này
+        word: String,
+    },
+}
+
+/// Subscriber nhận sự kiện từ Engine
+///
+/// Mọi closure `FnMut(&EngineEvent)` đều là một `EngineObserver`.
+pub trait EngineObserver: Send + Sync {
+    /// Được gọi đồng bộ, ngay sau khi sự kiện xảy ra
+    fn on_event(&mut self, event: &EngineEvent);
+}
+
+impl<F> EngineObserver for F
+where
+    F: FnMut(&EngineEvent) + Send + Sync,
+{
+    fn on_event(&mut self, event: &EngineEvent) {
+        self(event)
+    }
+}
+
+/// ID của một subscriber, dùng để hủy đăng ký
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Danh sách subscriber của Engine
+#[derive(Default)]
+pub(crate) struct Observers {
+    /// Các subscriber theo thứ tự đăng ký
+    entries: Vec<(SubscriptionId, Box<dyn EngineObserver>)>,
+
+    /// ID cho subscriber tiếp theo
+    next_id: u64,
+}
+
+impl Observers {
+    /// Thêm subscriber
+    pub(crate) fn subscribe(&mut self, observer: Box<dyn EngineObserver>) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.entries.push((id, observer));
+        id
+    }
+
+    /// Hủy subscriber, trả về `false` nếu ID không tồn tại
+    pub(crate) fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|(entry_id, _)| *entry_id != id);
+        self.entries.len() != len
+    }
+
+    /// Không có subscriber nào?
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gửi sự kiện cho mọi subscriber
+    pub(crate) fn emit(&mut self, event: EngineEvent) {
+        for (_, observer) in &mut self.entries {
+            observer.on_event(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_subscribe_unsubscribe() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut observers = Observers::default();
+
+        let sink = seen.clone();
+        let id = observers.subscribe(Box::new(move |event: &EngineEvent| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+
+        let event = EngineEvent::MethodChanged {
+            method: "telex".to_string(),
+        };
+        observers.emit(event.clone());
+        assert!(observers.unsubscribe(id));
+        assert!(!observers.unsubscribe(id));
+        observers.emit(event.clone());
+
+        assert_eq!(*seen.lock().unwrap(), vec![event]);
+    }
+}
@@ -0,0 +1,239 @@
+//! Vikey Core - Undo History Module
+//!
+//! Lịch sử phím của từ đang soạn, do Engine quản lý cho mọi input method.
+//!
+//! Engine không biết trạng thái bên trong input method nên undo/redo được
+//! thực hiện bằng cách bỏ (hoặc chèn lại) một phím trong lịch sử rồi gõ lại
+//! toàn bộ từ trên input method đã reset. Chỉ các phím *biến đổi* (dấu
+//! thanh, dấu mũ, móc...) mới bị undo; các chữ cái gõ thẳng giữ nguyên.
+
+use crate::key::KeyEvent;
+use crate::types::Action;
+
+/// Một phím trong lịch sử
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Phím đã gõ
+    key: KeyEvent,
+
+    /// Phím này có biến đổi văn bản không (khác với chỉ chèn ký tự của nó)
+    transforming: bool,
+}
+
+/// Lịch sử phím của từ đang soạn
+#[derive(Debug, Default)]
+pub(crate) struct History {
+    /// Các phím của từ hiện tại, theo thứ tự gõ
+    entries: Vec<Entry>,
+
+    /// Các phím đã undo: (vị trí trong `entries`, phím)
+    redo: Vec<(usize, KeyEvent)>,
+
+    /// Văn bản composition hiện tại, theo các Action đã trả về cho host
+    composed: String,
+
+    /// Từ đã commit được soạn tiếp (surrounding text), có sẵn trước mọi phím
+    resumed: String,
+}
+
+impl History {
+    /// Văn bản composition hiện tại
+    pub(crate) fn composed(&self) -> &str {
+        &self.composed
+    }
+
+    /// Từ đã commit đang được soạn tiếp (rỗng nếu không có)
+    pub(crate) fn resumed(&self) -> &str {
+        &self.resumed
+    }
+
+    /// Xóa toàn bộ lịch sử (kết thúc từ)
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.redo.clear();
+        self.composed.clear();
+        self.resumed.clear();
+    }
+
+    /// Bắt đầu từ mới từ một từ đã commit trước con trỏ
+    pub(crate) fn resume(&mut self, word: &str) {
+        self.clear();
+        self.composed = word.to_string();
+        self.resumed = word.to_string();
+    }
+
+    /// Ghi nhận một phím mới do người dùng gõ
+    ///
+    /// `composing` là `false` khi từ đã kết thúc sau phím này (buffer rỗng).
+    pub(crate) fn record(&mut self, key: KeyEvent, action: &Action, composing: bool) {
+        self.redo.clear();
+        self.push(key, action, composing);
+    }
+
+    /// Ghi nhận một phím, giữ nguyên redo stack (dùng khi gõ lại)
+    fn push(&mut self, key: KeyEvent, action: &Action, composing: bool) {
+        let before = self.composed.clone();
+        if !composing || !apply(&mut self.composed, action) {
+            self.clear();
+            return;
+        }
+
+        let transforming = match key.char() {
+            Some(ch) => {
+                self.composed.strip_prefix(before.as_str()) != Some(ch.to_string().as_str())
+            }
+            // Backspace: không phải phép biến đổi
+            None => false,
+        };
+        self.entries.push(Entry { key, transforming });
+    }
+
+    /// Chưa có phím nào được gõ cho từ hiện tại?
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Có phím biến đổi nào để undo không?
+    pub(crate) fn can_undo(&self) -> bool {
+        self.entries.iter().any(|e| e.transforming)
+    }
+
+    /// Có phím nào để redo không?
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Bỏ phím biến đổi gần nhất, trả về danh sách phím cần gõ lại
+    pub(crate) fn undo(&mut self) -> Option<Vec<KeyEvent>> {
+        let index = self.entries.iter().rposition(|e| e.transforming)?;
+        let entry = self.entries.remove(index);
+        self.redo.push((index, entry.key));
+        Some(self.take_keys())
+    }
+
+    /// Chèn lại phím vừa undo, trả về danh sách phím cần gõ lại
+    pub(crate) fn redo(&mut self) -> Option<Vec<KeyEvent>> {
+        let (index, key) = self.redo.pop()?;
+        let mut keys = self.take_keys();
+        keys.insert(index.min(keys.len()), key);
+        Some(keys)
+    }
+
+    /// Lấy các phím ra để gõ lại; `composed` được tính lại qua `replay`
+    fn take_keys(&mut self) -> Vec<KeyEvent> {
+        self.composed = self.resumed.clone();
+        self.entries.drain(..).map(|e| e.key).collect()
+    }
+
+    /// Ghi nhận một phím khi gõ lại sau undo/redo
+    pub(crate) fn replay(&mut self, key: KeyEvent, action: &Action, composing: bool) {
+        let redo = std::mem::take(&mut self.redo);
+        self.push(key, action, composing);
+        self.redo = redo;
+    }
+}
+
+/// Áp dụng Action lên văn bản composition
+///
+/// Trả về `false` nếu Action kết thúc composition (văn bản đã rời khỏi
+/// tầm kiểm soát của engine).
+fn apply(composed: &mut String, action: &Action) -> bool {
+    match action {
+        Action::DoNothing => true,
+        Action::Commit(text) => {
+            composed.push_str(text);
+            true
+        }
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            for _ in 0..*backspace_count {
+                composed.pop();
+            }
+            composed.push_str(text);
+            true
+        }
+        Action::UpdatePreedit { text, .. } => {
+            *composed = text.clone();
+            true
+        }
+        Action::PassThrough
+        | Action::CommitPreedit
+        | Action::ShowCandidates(_)
+        | Action::HideCandidates => false,
+    }
+}
+
+/// Action đưa văn bản `old` trên màn hình về `new` (chế độ Direct)
+///
+/// Giữ nguyên phần đầu chung, chỉ xóa và gõ lại phần khác nhau.
+pub(crate) fn replace_action(old: &str, new: &str) -> Action {
+    let common = old
+        .chars()
+        .zip(new.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    Action::Replace {
+        backspace_count: old.chars().count() - common,
+        text: new.chars().skip(common).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(backspace_count: usize, text: &str) -> Action {
+        Action::Replace {
+            backspace_count,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_transforming_keys() {
+        let mut history = History::default();
+        history.record('a'.into(), &replace(0, "a"), true);
+        history.record('a'.into(), &replace(1, "â"), true);
+        history.record('n'.into(), &Action::Commit("n".to_string()), true);
+
+        assert_eq!(history.composed(), "ân");
+        assert!(history.can_undo());
+
+        let keys = history.undo().unwrap();
+        let chars: Vec<_> = keys.iter().filter_map(|k| k.char()).collect();
+        assert_eq!(chars, vec!['a', 'n']);
+        assert!(history.can_redo());
+    }
+
+    #[test]
+    fn test_word_end_clears() {
+        let mut history = History::default();
+        history.record('a'.into(), &replace(0, "a"), true);
+        history.record('s'.into(), &replace(1, "á"), true);
+        history.record(' '.into(), &Action::PassThrough, true);
+
+        assert!(!history.can_undo());
+        assert_eq!(history.composed(), "");
+    }
+
+    #[test]
+    fn test_resumed_word() {
+        let mut history = History::default();
+        history.resume("viet");
+        history.record('j'.into(), &replace(4, "việt"), true);
+        assert_eq!(history.composed(), "việt");
+
+        // Gõ lại bắt đầu từ từ đã commit, không phải chuỗi rỗng
+        history.undo().unwrap();
+        assert_eq!(history.composed(), "viet");
+        assert_eq!(history.resumed(), "viet");
+    }
+
+    #[test]
+    fn test_replace_action() {
+        assert_eq!(replace_action("viết", "viêt"), replace(2, "êt"));
+        assert_eq!(replace_action("ab", "abc"), replace(0, "c"));
+    }
+}
@@ -0,0 +1,490 @@
+//! Vikey Core - Key Event Module
+//!
+//! Mô tả đầy đủ một sự kiện bàn phím (phím vật lý, ký tự sinh ra,
+//! modifiers, nhấn/nhả) để Engine và các input method không phải đoán
+//! từ một `char` đơn lẻ.
+
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+use std::str::FromStr;
+
+/// Phím vật lý (hoặc phím chức năng) được nhấn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyCode {
+    /// Phím ký tự (chữ, số, dấu câu) - giá trị là ký tự chưa áp dụng Shift
+    Char(char),
+    /// Phím cách
+    Space,
+    /// Enter / Return
+    Enter,
+    /// Tab
+    Tab,
+    /// Backspace
+    Backspace,
+    /// Delete (xóa về phía sau con trỏ)
+    Delete,
+    /// Escape
+    Escape,
+    /// Mũi tên trái
+    Left,
+    /// Mũi tên phải
+    Right,
+    /// Mũi tên lên
+    Up,
+    /// Mũi tên xuống
+    Down,
+    /// Home
+    Home,
+    /// End
+    End,
+    /// Page Up
+    PageUp,
+    /// Page Down
+    PageDown,
+    /// Insert
+    Insert,
+    /// Phím chức năng F1..F24
+    Function(u8),
+    /// Bản thân một phím modifier (Shift, Ctrl, Alt, Meta, Caps Lock)
+    Modifier,
+    /// Phím khác, giữ nguyên mã gốc của nền tảng
+    Other(u32),
+}
+
+impl KeyCode {
+    /// Phím di chuyển con trỏ hoặc thay đổi vùng soạn thảo
+    ///
+    /// Khi gặp các phím này, composition hiện tại không còn đúng vị trí
+    /// nên Engine phải reset.
+    pub fn is_navigation(&self) -> bool {
+        matches!(
+            self,
+            KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::Home
+                | KeyCode::End
+                | KeyCode::PageUp
+                | KeyCode::PageDown
+                | KeyCode::Delete
+                | KeyCode::Escape
+                | KeyCode::Insert
+        )
+    }
+}
+
+/// Tập modifiers đang được giữ khi nhấn phím
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    /// Không có modifier nào
+    pub const NONE: Modifiers = Modifiers(0);
+    /// Shift
+    pub const SHIFT: Modifiers = Modifiers(1 << 0);
+    /// Ctrl
+    pub const CONTROL: Modifiers = Modifiers(1 << 1);
+    /// Alt (Option trên macOS)
+    pub const ALT: Modifiers = Modifiers(1 << 2);
+    /// Meta (Windows / Command / Super)
+    pub const META: Modifiers = Modifiers(1 << 3);
+    /// Caps Lock đang bật
+    pub const CAPS_LOCK: Modifiers = Modifiers(1 << 4);
+
+    /// Có chứa tất cả các modifiers trong `other` không?
+    pub fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Thêm modifiers
+    pub fn insert(&mut self, other: Modifiers) {
+        self.0 |= other.0;
+    }
+
+    /// Bỏ modifiers
+    pub fn remove(&mut self, other: Modifiers) {
+        self.0 &= !other.0;
+    }
+
+    /// Không có modifier nào?
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Giá trị bit thô (dùng cho IPC)
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Tạo từ giá trị bit thô, bỏ qua các bit không xác định
+    pub fn from_bits_truncate(bits: u8) -> Self {
+        Modifiers(bits & 0b1_1111)
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, rhs: Modifiers) -> Modifiers {
+        Modifiers(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Modifiers) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Nhấn hay nhả phím
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyState {
+    /// Phím được nhấn (bao gồm auto-repeat)
+    #[default]
+    Pressed,
+    /// Phím được nhả
+    Released,
+}
+
+/// Một sự kiện bàn phím
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyEvent {
+    /// Phím vật lý
+    pub code: KeyCode,
+
+    /// Ký tự mà phím sinh ra sau khi áp dụng Shift/Caps Lock/layout (nếu có)
+    pub text: Option<char>,
+
+    /// Modifiers đang giữ
+    pub modifiers: Modifiers,
+
+    /// Nhấn hay nhả
+    pub state: KeyState,
+}
+
+impl KeyEvent {
+    /// Tạo sự kiện nhấn phím không có modifiers
+    pub fn new(code: KeyCode, text: Option<char>) -> Self {
+        Self {
+            code,
+            text,
+            modifiers: Modifiers::NONE,
+            state: KeyState::Pressed,
+        }
+    }
+
+    /// Tạo sự kiện từ một ký tự (khi host chỉ biết ký tự được gõ)
+    ///
+    /// Các ký tự điều khiển quen thuộc được ánh xạ về phím tương ứng:
+    /// `' '` → Space, `'\n'`/`'\r'` → Enter, `'\t'` → Tab,
+    /// `'\u{8}'` → Backspace, `'\u{7f}'` → Delete, `'\u{1b}'` → Escape.
+    pub fn from_char(ch: char) -> Self {
+        match ch {
+            ' ' => Self::new(KeyCode::Space, Some(' ')),
+            '\n' | '\r' => Self::new(KeyCode::Enter, Some('\n')),
+            '\t' => Self::new(KeyCode::Tab, Some('\t')),
+            '\u{8}' => Self::new(KeyCode::Backspace, None),
+            '\u{7f}' => Self::new(KeyCode::Delete, None),
+            '\u{1b}' => Self::new(KeyCode::Escape, None),
+            _ => Self::new(
+                KeyCode::Char(ch.to_lowercase().next().unwrap_or(ch)),
+                Some(ch),
+            ),
+        }
+    }
+
+    /// Đặt modifiers (builder)
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Đánh dấu là sự kiện nhả phím (builder)
+    pub fn released(mut self) -> Self {
+        self.state = KeyState::Released;
+        self
+    }
+
+    /// Ký tự sinh ra bởi phím (nếu có)
+    pub fn char(&self) -> Option<char> {
+        self.text
+    }
+
+    /// Sự kiện nhấn phím?
+    pub fn is_press(&self) -> bool {
+        self.state == KeyState::Pressed
+    }
+
+    /// Tổ hợp phím tắt (Ctrl/Alt/Meta + phím)?
+    ///
+    /// AltGr trên Windows được báo là Ctrl+Alt nhưng vẫn sinh ký tự
+    /// (ví dụ bàn phím châu Âu), nên không coi là tổ hợp phím tắt.
+    pub fn is_chord(&self) -> bool {
+        let m = self.modifiers;
+        if m.contains(Modifiers::CONTROL | Modifiers::ALT)
+            && !m.contains(Modifiers::META)
+            && self.text.is_some()
+        {
+            return false;
+        }
+        m.contains(Modifiers::CONTROL) || m.contains(Modifiers::ALT) || m.contains(Modifiers::META)
+    }
+
+    /// Phím di chuyển con trỏ / thay đổi vùng soạn thảo?
+    pub fn is_navigation(&self) -> bool {
+        self.code.is_navigation()
+    }
+
+    /// Shift đang được giữ?
+    pub fn shift(&self) -> bool {
+        self.modifiers.contains(Modifiers::SHIFT)
+    }
+
+    /// Caps Lock đang bật?
+    pub fn caps_lock(&self) -> bool {
+        self.modifiers.contains(Modifiers::CAPS_LOCK)
+    }
+}
+
+/// Một phím tắt cấu hình được (ví dụ: Ctrl+Z cho undo)
+///
+/// So khớp bỏ qua trạng thái Caps Lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyBinding {
+    /// Phím vật lý
+    pub code: KeyCode,
+
+    /// Modifiers phải giữ (chính xác, không thừa không thiếu)
+    pub modifiers: Modifiers,
+}
+
+impl KeyBinding {
+    /// Tạo phím tắt
+    pub fn new(code: KeyCode, modifiers: Modifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// Phím tắt Ctrl + ký tự (ví dụ: `KeyBinding::ctrl('z')`)
+    pub fn ctrl(ch: char) -> Self {
+        Self::new(KeyCode::Char(ch), Modifiers::CONTROL)
+    }
+
+    /// Sự kiện nhấn phím có khớp phím tắt này không?
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let mut modifiers = key.modifiers;
+        modifiers.remove(Modifiers::CAPS_LOCK);
+        key.is_press() && key.code == self.code && modifiers == self.modifiers
+    }
+}
+
+/// Tên phím dùng trong chuỗi phím tắt (ngoài ký tự và F1..F24)
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("Space", KeyCode::Space),
+    ("Enter", KeyCode::Enter),
+    ("Tab", KeyCode::Tab),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Escape", KeyCode::Escape),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Insert", KeyCode::Insert),
+];
+
+/// Tên modifier trong chuỗi phím tắt, theo thứ tự hiển thị
+const MODIFIER_NAMES: &[(&str, Modifiers)] = &[
+    ("Ctrl", Modifiers::CONTROL),
+    ("Alt", Modifiers::ALT),
+    ("Shift", Modifiers::SHIFT),
+    ("Meta", Modifiers::META),
+];
+
+/// Lỗi khi đọc phím tắt từ chuỗi
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeyError(String);
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid key binding '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+/// Đọc phím tắt dạng "Ctrl+Z", "Ctrl+Shift+F5", "Escape"
+///
+/// Không phân biệt hoa thường; chấp nhận thêm "Control", "Esc", "Win",
+/// "Cmd", "Super" và "Option".
+impl FromStr for KeyBinding {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseKeyError(s.to_string());
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        // "Ctrl++" : phím cuối chính là dấu '+'
+        if s.trim_end().ends_with("++") {
+            parts.pop();
+            parts.pop();
+            parts.push("+");
+        }
+        let key = parts.pop().filter(|k| !k.is_empty()).ok_or_else(error)?;
+
+        let mut modifiers = Modifiers::NONE;
+        for part in parts {
+            let modifier = match part.to_lowercase().as_str() {
+                "ctrl" | "control" => Modifiers::CONTROL,
+                "alt" | "option" => Modifiers::ALT,
+                "shift" => Modifiers::SHIFT,
+                "meta" | "win" | "cmd" | "super" => Modifiers::META,
+                _ => return Err(error()),
+            };
+            modifiers.insert(modifier);
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(ch), None) => KeyCode::Char(ch.to_lowercase().next().unwrap_or(ch)),
+            _ => {
+                let lower = key.to_lowercase();
+                if lower == "esc" {
+                    KeyCode::Escape
+                } else if let Some(&(_, code)) = KEY_NAMES
+                    .iter()
+                    .find(|(name, _)| name.to_lowercase() == lower)
+                {
+                    code
+                } else {
+                    match lower.strip_prefix('f').map(str::parse::<u8>) {
+                        Some(Ok(n @ 1..=24)) => KeyCode::Function(n),
+                        _ => return Err(error()),
+                    }
+                }
+            }
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, modifier) in MODIFIER_NAMES {
+            if self.modifiers.contains(*modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(ch) => write!(f, "{}", ch.to_uppercase()),
+            KeyCode::Function(n) => write!(f, "F{}", n),
+            code => match KEY_NAMES.iter().find(|(_, c)| *c == code) {
+                Some((name, _)) => write!(f, "{}", name),
+                None => write!(f, "{:?}", code),
+            },
+        }
+    }
+}
+
+impl From<char> for KeyEvent {
+    fn from(ch: char) -> Self {
+        Self::from_char(ch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_char() {
+        let key = KeyEvent::from_char('A');
+        assert_eq!(key.code, KeyCode::Char('a'));
+        assert_eq!(key.char(), Some('A'));
+        assert!(key.is_press());
+        assert!(!key.is_chord());
+
+        assert_eq!(KeyEvent::from_char(' ').code, KeyCode::Space);
+        assert_eq!(KeyEvent::from_char('\u{8}').code, KeyCode::Backspace);
+        assert_eq!(KeyEvent::from_char('\u{8}').char(), None);
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let mut m = Modifiers::SHIFT | Modifiers::CAPS_LOCK;
+        assert!(m.contains(Modifiers::SHIFT));
+        assert!(!m.contains(Modifiers::CONTROL));
+
+        m.insert(Modifiers::CONTROL);
+        m.remove(Modifiers::SHIFT);
+        assert!(m.contains(Modifiers::CONTROL | Modifiers::CAPS_LOCK));
+        assert_eq!(Modifiers::from_bits_truncate(m.bits()), m);
+    }
+
+    #[test]
+    fn test_chord() {
+        let ctrl_c = KeyEvent::from_char('c').with_modifiers(Modifiers::CONTROL);
+        assert!(ctrl_c.is_chord());
+
+        let shift_a = KeyEvent::from_char('A').with_modifiers(Modifiers::SHIFT);
+        assert!(!shift_a.is_chord());
+
+        // AltGr + e → € (vẫn là ký tự)
+        let alt_gr = KeyEvent::new(KeyCode::Char('e'), Some('€'))
+            .with_modifiers(Modifiers::CONTROL | Modifiers::ALT);
+        assert!(!alt_gr.is_chord());
+    }
+
+    #[test]
+    fn test_key_binding() {
+        let undo = KeyBinding::ctrl('z');
+        assert!(undo.matches(&KeyEvent::from_char('z').with_modifiers(Modifiers::CONTROL)));
+        assert!(undo.matches(
+            &KeyEvent::from_char('z').with_modifiers(Modifiers::CONTROL | Modifiers::CAPS_LOCK)
+        ));
+        assert!(!undo.matches(
+            &KeyEvent::from_char('Z').with_modifiers(Modifiers::CONTROL | Modifiers::SHIFT)
+        ));
+        assert!(!undo.matches(&KeyEvent::from_char('z')));
+    }
+
+    #[test]
+    fn test_key_binding_string() {
+        let undo: KeyBinding = "Ctrl+Z".parse().unwrap();
+        assert_eq!(undo, KeyBinding::ctrl('z'));
+        assert_eq!(undo.to_string(), "Ctrl+Z");
+
+        let key: KeyBinding = "shift + ctrl + f5".parse().unwrap();
+        assert_eq!(
+            key,
+            KeyBinding::new(KeyCode::Function(5), Modifiers::CONTROL | Modifiers::SHIFT)
+        );
+        assert_eq!(key.to_string(), "Ctrl+Shift+F5");
+
+        let esc: KeyBinding = "Esc".parse().unwrap();
+        assert_eq!(esc.to_string(), "Escape");
+        assert_eq!(
+            "Ctrl++".parse::<KeyBinding>().unwrap().to_string(),
+            "Ctrl++"
+        );
+
+        assert!("Hyper+Z".parse::<KeyBinding>().is_err());
+        assert!("Ctrl+".parse::<KeyBinding>().is_err());
+        assert!("F25".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_navigation() {
+        assert!(KeyEvent::new(KeyCode::Left, None).is_navigation());
+        assert!(KeyEvent::new(KeyCode::Escape, None).is_navigation());
+        assert!(!KeyEvent::from_char('a').is_navigation());
+    }
+}
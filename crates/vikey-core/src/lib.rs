@@ -4,25 +4,43 @@
 //! for multi-language input method support.
 
 mod buffer;
+pub mod candidate;
+pub mod config;
+pub mod event;
+mod history;
+pub mod key;
+pub mod metadata;
 pub mod types;
-// TODO Phase 2: Remove Vietnamese-specific modules (will be in vikey-vietnamese)
-// mod lookup;
-// mod spelling;
-// mod processor;
 
 // Plugin system modules
 pub mod engine;
 pub mod registry;
+pub mod session;
+pub mod shorthand;
 pub mod traits;
+pub mod transform;
 
 // Re-exports
 pub use buffer::InputBuffer;
-pub use types::{Action, CharInfo, Config, WordForm};
+pub use candidate::{Candidate, CandidateConfig, CandidateList};
+pub use config::{
+    Config, ConfigError, KeysConfig, OptionKind, OptionSchema, OptionSpec, OptionValue,
+    PluginOptions,
+};
+pub use event::{EngineEvent, EngineObserver, SubscriptionId};
+pub use key::{KeyBinding, KeyCode, KeyEvent, KeyState, Modifiers, ParseKeyError};
+pub use metadata::{Capabilities, MethodMetadata, PluginMetadata};
+pub use types::{
+    Action, CharInfo, CompositionMode, InterruptPolicy, PreeditSegment, PreeditStyle, WordForm,
+};
 
 // Plugin system exports
 pub use engine::Engine;
 pub use registry::{PluginRegistry, RegistryError};
+pub use session::{ContextId, SessionManager};
+pub use shorthand::{MacroError, MacroTable, Shorthand};
 pub use traits::{InputMethodTrait, LanguagePlugin, LanguageRules, LookupProvider};
+pub use transform::{Middleware, Pipeline, StageContext, StageSession};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,325 @@
+//! Vikey Core - Plugin Metadata
+//!
+//! Mô tả plugin và các input method (tên theo ngôn ngữ giao diện, hệ chữ,
+//! khả năng...) để tray menu và giao diện cấu hình được sinh tự động thay
+//! vì viết cứng.
+
+use std::collections::BTreeMap;
+use std::ops::{BitOr, BitOrAssign};
+
+/// Các khả năng của một input method
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Capabilities(u8);
+
+impl Capabilities {
+    /// Không có khả năng đặc biệt nào
+    pub const NONE: Capabilities = Capabilities(0);
+    /// Chọn kết quả từ cửa sổ candidate
+    pub const CANDIDATES: Capabilities = Capabilities(1 << 0);
+    /// Hỗ trợ `CompositionMode::Preedit`
+    pub const PREEDIT: Capabilities = Capabilities(1 << 1);
+    /// Gõ dấu thanh
+    pub const TONAL: Capabilities = Capabilities(1 << 2);
+    /// Soạn tiếp từ trước con trỏ (`InputMethodTrait::resume`)
+    pub const RESUME: Capabilities = Capabilities(1 << 3);
+
+    /// Tên các khả năng (cho UI và log)
+    const NAMES: &'static [(Capabilities, &'static str)] = &[
+        (Capabilities::CANDIDATES, "candidates"),
+        (Capabilities::PREEDIT, "preedit"),
+        (Capabilities::TONAL, "tonal"),
+        (Capabilities::RESUME, "resume"),
+    ];
+
+    /// Có chứa tất cả các khả năng trong `other` không?
+    pub fn contains(self, other: Capabilities) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Thêm khả năng
+    pub fn insert(&mut self, other: Capabilities) {
+        self.0 |= other.0;
+    }
+
+    /// Không có khả năng nào?
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Tên các khả năng có trong tập, ví dụ `["preedit", "tonal"]`
+    pub fn names(self) -> Vec<&'static str> {
+        Self::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Capabilities;
+
+    fn bitor(self, rhs: Capabilities) -> Capabilities {
+        Capabilities(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Capabilities {
+    fn bitor_assign(&mut self, rhs: Capabilities) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Tên theo locale, rơi về tên theo ngôn ngữ chính ("vi-VN" → "vi")
+fn localized<'a>(names: &'a BTreeMap<String, String>, locale: &str) -> Option<&'a str> {
+    let find = |locale: &str| {
+        names
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(locale))
+            .map(|(_, name)| name.as_str())
+    };
+    find(locale).or_else(|| find(locale.split(['-', '_']).next()?))
+}
+
+/// Mô tả một input method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodMetadata {
+    /// ID (như trong `LanguagePlugin::input_methods`)
+    pub id: String,
+
+    /// Tên mặc định
+    pub name: String,
+
+    /// Mô tả ngắn
+    pub description: String,
+
+    /// Tên theo locale (ví dụ: "vi" → "Ký âm")
+    pub display_names: BTreeMap<String, String>,
+
+    /// Các khả năng
+    pub capabilities: Capabilities,
+
+    /// Độ ưu tiên khi chọn input method mặc định (lớn hơn được chọn trước)
+    pub priority: i32,
+
+    /// Chưa hoàn thiện: UI nên hiển thị nhưng không cho chọn, và không
+    /// được chọn làm mặc định
+    pub experimental: bool,
+}
+
+impl MethodMetadata {
+    /// Tạo mô tả với ID và tên mặc định
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            description: String::new(),
+            display_names: BTreeMap::new(),
+            capabilities: Capabilities::NONE,
+            priority: 0,
+            experimental: false,
+        }
+    }
+
+    /// Đặt mô tả (builder)
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Thêm tên theo locale (builder)
+    pub fn with_display_name(mut self, locale: impl Into<String>, name: impl Into<String>) -> Self {
+        self.display_names.insert(locale.into(), name.into());
+        self
+    }
+
+    /// Đặt các khả năng (builder)
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Đặt độ ưu tiên làm mặc định (builder)
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Đánh dấu chưa hoàn thiện (builder)
+    pub fn experimental(mut self) -> Self {
+        self.experimental = true;
+        self
+    }
+
+    /// Tên hiển thị cho `locale` (ví dụ: "vi", "en-US"), mặc định là `name`
+    pub fn display_name(&self, locale: &str) -> &str {
+        localized(&self.display_names, locale).unwrap_or(&self.name)
+    }
+}
+
+/// Mô tả một Language Plugin
+///
+/// ```ignore
+/// PluginMetadata::new("vietnamese", "Tiếng Việt")
+///     .with_language_tag("vi")
+///     .with_script("Latn")
+///     .with_display_name("en", "Vietnamese")
+///     .with_method(MethodMetadata::new("telex", "Telex").with_priority(10))
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginMetadata {
+    /// ID (như `LanguagePlugin::id`)
+    pub id: String,
+
+    /// Tên mặc định (như `LanguagePlugin::name`)
+    pub name: String,
+
+    /// Ngôn ngữ theo BCP-47 (ví dụ: "vi", "vi-Hani"); "und" nếu không rõ
+    pub language_tag: String,
+
+    /// Hệ chữ theo ISO 15924 (ví dụ: "Latn", "Hani"); "Zzzz" nếu không rõ
+    pub script: String,
+
+    /// Phiên bản plugin
+    pub version: Option<String>,
+
+    /// Tên theo locale
+    pub display_names: BTreeMap<String, String>,
+
+    /// Gợi ý icon cho frontend (ví dụ: "vietnamese")
+    pub icon: Option<String>,
+
+    /// Vị trí trong danh sách ngôn ngữ (nhỏ hơn đứng trước; bằng nhau thì
+    /// theo thứ tự đăng ký)
+    pub order: i32,
+
+    /// Các input method, theo thứ tự hiển thị
+    pub methods: Vec<MethodMetadata>,
+}
+
+impl PluginMetadata {
+    /// Tạo mô tả với ID và tên mặc định
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            language_tag: "und".to_string(),
+            script: "Zzzz".to_string(),
+            version: None,
+            display_names: BTreeMap::new(),
+            icon: None,
+            order: 0,
+            methods: Vec::new(),
+        }
+    }
+
+    /// Đặt ngôn ngữ BCP-47 (builder)
+    pub fn with_language_tag(mut self, tag: impl Into<String>) -> Self {
+        self.language_tag = tag.into();
+        self
+    }
+
+    /// Đặt hệ chữ ISO 15924 (builder)
+    pub fn with_script(mut self, script: impl Into<String>) -> Self {
+        self.script = script.into();
+        self
+    }
+
+    /// Đặt phiên bản (builder)
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Thêm tên theo locale (builder)
+    pub fn with_display_name(mut self, locale: impl Into<String>, name: impl Into<String>) -> Self {
+        self.display_names.insert(locale.into(), name.into());
+        self
+    }
+
+    /// Đặt gợi ý icon (builder)
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Đặt vị trí trong danh sách ngôn ngữ (builder)
+    pub fn with_order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Thêm một input method (builder)
+    pub fn with_method(mut self, method: MethodMetadata) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    /// Tên hiển thị cho `locale` (ví dụ: "vi", "en-US"), mặc định là `name`
+    pub fn display_name(&self, locale: &str) -> &str {
+        localized(&self.display_names, locale).unwrap_or(&self.name)
+    }
+
+    /// Mô tả của input method `id`
+    pub fn method(&self, id: &str) -> Option<&MethodMetadata> {
+        self.methods.iter().find(|method| method.id == id)
+    }
+
+    /// Input method được chọn khi chuyển sang ngôn ngữ này
+    ///
+    /// Là input method không thử nghiệm có `priority` cao nhất (bằng nhau
+    /// thì theo thứ tự hiển thị); nếu mọi input method đều thử nghiệm thì
+    /// lấy input method đầu tiên.
+    pub fn default_method(&self) -> Option<&MethodMetadata> {
+        self.methods
+            .iter()
+            .rev()
+            .filter(|method| !method.experimental)
+            .max_by_key(|method| method.priority)
+            .or_else(|| self.methods.first())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_name() {
+        let metadata = PluginMetadata::new("vietnamese", "Tiếng Việt")
+            .with_display_name("en", "Vietnamese")
+            .with_display_name("vi", "Chữ Việt");
+        assert_eq!(metadata.display_name("vi"), "Chữ Việt");
+        assert_eq!(metadata.display_name("en-US"), "Vietnamese");
+        assert_eq!(metadata.display_name("EN_gb"), "Vietnamese");
+        assert_eq!(metadata.display_name("fr"), "Tiếng Việt");
+    }
+
+    #[test]
+    fn test_default_method() {
+        let metadata = PluginMetadata::new("x", "X")
+            .with_method(
+                MethodMetadata::new("a", "A")
+                    .experimental()
+                    .with_priority(20),
+            )
+            .with_method(MethodMetadata::new("b", "B").with_priority(10))
+            .with_method(MethodMetadata::new("c", "C").with_priority(10));
+        assert_eq!(metadata.default_method().map(|m| m.id.as_str()), Some("b"));
+
+        let metadata = PluginMetadata::new("x", "X")
+            .with_method(MethodMetadata::new("a", "A").experimental())
+            .with_method(MethodMetadata::new("b", "B").experimental());
+        assert_eq!(metadata.default_method().map(|m| m.id.as_str()), Some("a"));
+        assert_eq!(PluginMetadata::new("x", "X").default_method(), None);
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = Capabilities::TONAL | Capabilities::PREEDIT;
+        assert!(capabilities.contains(Capabilities::TONAL));
+        assert!(!capabilities.contains(Capabilities::CANDIDATES));
+        assert_eq!(capabilities.names(), vec!["preedit", "tonal"]);
+        assert!(Capabilities::default().is_empty());
+    }
+}
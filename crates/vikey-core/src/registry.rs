@@ -1,7 +1,11 @@
 //! Vikey Core - Plugin Registry
 //!
 //! Quản lý việc đăng ký và tra cứu các Language Plugins.
+//!
+//! Danh sách ngôn ngữ và input method có thứ tự ổn định (theo
+//! `PluginMetadata::order` rồi thứ tự đăng ký), dùng để sinh menu.
 
+use crate::metadata::{MethodMetadata, PluginMetadata};
 use crate::traits::LanguagePlugin;
 use std::collections::HashMap;
 
@@ -10,6 +14,9 @@ pub struct PluginRegistry {
     /// Map từ language ID → Plugin instance
     plugins: HashMap<String, Box<dyn LanguagePlugin>>,
 
+    /// Metadata của các plugin, theo thứ tự hiển thị
+    catalog: Vec<PluginMetadata>,
+
     /// Language hiện tại đang được sử dụng
     current_language: Option<String>,
 
@@ -22,6 +29,7 @@ impl PluginRegistry {
     pub fn new() -> Self {
         Self {
             plugins: HashMap::new(),
+            catalog: Vec::new(),
             current_language: None,
             current_input_method: None,
         }
@@ -41,12 +49,20 @@ impl PluginRegistry {
             return Err(RegistryError::DuplicateId(id));
         }
 
+        let metadata = describe(plugin.as_ref());
+        let position = self
+            .catalog
+            .iter()
+            .position(|other| other.order > metadata.order)
+            .unwrap_or(self.catalog.len());
+        self.catalog.insert(position, metadata);
         self.plugins.insert(id, plugin);
         Ok(())
     }
 
     /// Hủy đăng ký một plugin
     pub fn unregister(&mut self, id: &str) -> Option<Box<dyn LanguagePlugin>> {
+        self.catalog.retain(|metadata| metadata.id != id);
         self.plugins.remove(id)
     }
 
@@ -55,9 +71,24 @@ impl PluginRegistry {
         self.plugins.get(id).map(|p| p.as_ref())
     }
 
-    /// Lấy danh sách tất cả language IDs đã đăng ký
+    /// Lấy plugin theo ID để thay đổi (ví dụ: đặt tùy chọn)
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut (dyn LanguagePlugin + 'static)> {
+        self.plugins.get_mut(id).map(|p| p.as_mut())
+    }
+
+    /// Lấy danh sách tất cả language IDs đã đăng ký, theo thứ tự hiển thị
     pub fn languages(&self) -> Vec<&str> {
-        self.plugins.keys().map(|s| s.as_str()).collect()
+        self.catalog.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    /// Metadata của tất cả plugin, theo thứ tự hiển thị
+    pub fn catalog(&self) -> &[PluginMetadata] {
+        &self.catalog
+    }
+
+    /// Metadata của plugin `id`
+    pub fn metadata(&self, id: &str) -> Option<&PluginMetadata> {
+        self.catalog.iter().find(|m| m.id == id)
     }
 
     /// Đặt language hiện tại
@@ -68,12 +99,9 @@ impl PluginRegistry {
 
         self.current_language = Some(id.to_string());
 
-        // Auto-select first input method of this language
-        if let Some(plugin) = self.plugins.get(id) {
-            let methods = plugin.input_methods();
-            if let Some(first_method) = methods.first() {
-                self.current_input_method = Some(first_method.to_string());
-            }
+        // Auto-select the default input method of this language
+        if let Some(method) = self.metadata(id).and_then(|m| m.default_method()) {
+            self.current_input_method = Some(method.id.clone());
         }
 
         Ok(())
@@ -113,6 +141,24 @@ impl PluginRegistry {
     }
 }
 
+/// Metadata của `plugin`, khớp với `id()` và `input_methods()`
+///
+/// Input method thiếu mô tả được thêm vào cuối với mô tả mặc định.
+fn describe(plugin: &dyn LanguagePlugin) -> PluginMetadata {
+    let mut metadata = plugin.metadata();
+    let ids = plugin.input_methods();
+    metadata.id = plugin.id().to_string();
+    metadata
+        .methods
+        .retain(|method| ids.contains(&method.id.as_str()));
+    for id in ids {
+        if metadata.method(id).is_none() {
+            metadata.methods.push(MethodMetadata::new(id, id));
+        }
+    }
+    metadata
+}
+
 impl Default for PluginRegistry {
     fn default() -> Self {
         Self::new()
@@ -148,10 +194,140 @@ impl std::error::Error for RegistryError {}
 mod tests {
     use super::*;
 
+    use crate::buffer::InputBuffer;
+    use crate::key::KeyEvent;
+    use crate::traits::{DefaultLanguageRules, InputMethodTrait, LanguageRules, LookupProvider};
+    use crate::types::{Action, CharInfo};
+
+    struct NoLookup;
+
+    impl LookupProvider for NoLookup {
+        fn lookup(&self, _c: char) -> CharInfo {
+            CharInfo::default()
+        }
+        fn is_valid_char(&self, _c: char) -> bool {
+            true
+        }
+        fn is_vowel(&self, _c: char) -> bool {
+            false
+        }
+        fn is_consonant(&self, _c: char) -> bool {
+            false
+        }
+        fn is_separator(&self, c: char) -> bool {
+            c == ' '
+        }
+    }
+
+    struct NoMethod;
+
+    impl InputMethodTrait for NoMethod {
+        fn name(&self) -> &str {
+            "none"
+        }
+        fn id(&self) -> &str {
+            "none"
+        }
+        fn process(&mut self, _: &KeyEvent, _: &mut InputBuffer, _: &dyn LookupProvider) -> Action {
+            Action::PassThrough
+        }
+        fn process_backspace(&mut self, _: &mut InputBuffer) -> Action {
+            Action::PassThrough
+        }
+        fn reset(&mut self) {}
+        fn can_undo(&self, _: &InputBuffer) -> bool {
+            false
+        }
+        fn undo(&mut self, _: &mut InputBuffer) -> Action {
+            Action::DoNothing
+        }
+    }
+
+    /// Plugin giả với metadata tùy chọn
+    struct TestPlugin {
+        id: &'static str,
+        methods: Vec<&'static str>,
+        metadata: Option<PluginMetadata>,
+    }
+
+    impl LanguagePlugin for TestPlugin {
+        fn name(&self) -> &str {
+            self.id
+        }
+        fn id(&self) -> &str {
+            self.id
+        }
+        fn input_methods(&self) -> Vec<&str> {
+            self.methods.clone()
+        }
+        fn create_input_method(&self, _id: &str) -> Option<Box<dyn InputMethodTrait>> {
+            Some(Box::new(NoMethod))
+        }
+        fn lookup(&self) -> &dyn LookupProvider {
+            &NoLookup
+        }
+        fn rules(&self) -> &dyn LanguageRules {
+            &DefaultLanguageRules
+        }
+        fn metadata(&self) -> PluginMetadata {
+            match &self.metadata {
+                Some(metadata) => metadata.clone(),
+                None => PluginMetadata::new(self.id, self.id),
+            }
+        }
+    }
+
+    fn plugin(id: &'static str, order: i32) -> Box<TestPlugin> {
+        Box::new(TestPlugin {
+            id,
+            methods: vec!["a", "b"],
+            metadata: Some(PluginMetadata::new(id, id).with_order(order)),
+        })
+    }
+
     #[test]
     fn test_registry_new() {
         let registry = PluginRegistry::new();
         assert!(registry.languages().is_empty());
         assert!(registry.current_plugin().is_none());
     }
+
+    #[test]
+    fn test_stable_order() {
+        let mut registry = PluginRegistry::new();
+        registry.register(plugin("c", 0)).unwrap();
+        registry.register(plugin("a", 10)).unwrap();
+        registry.register(plugin("b", 0)).unwrap();
+        registry.register(plugin("d", -5)).unwrap();
+        assert_eq!(registry.languages(), vec!["d", "c", "b", "a"]);
+
+        registry.unregister("c");
+        assert_eq!(registry.languages(), vec!["d", "b", "a"]);
+        assert!(registry.metadata("c").is_none());
+    }
+
+    #[test]
+    fn test_metadata_matches_methods() {
+        let mut registry = PluginRegistry::new();
+        registry
+            .register(Box::new(TestPlugin {
+                id: "x",
+                methods: vec!["a", "b", "c"],
+                metadata: Some(
+                    PluginMetadata::new("other", "X")
+                        .with_method(MethodMetadata::new("c", "C"))
+                        .with_method(MethodMetadata::new("gone", "Gone"))
+                        .with_method(MethodMetadata::new("b", "B").with_priority(5)),
+                ),
+            }))
+            .unwrap();
+
+        let metadata = registry.metadata("x").unwrap();
+        let methods: Vec<_> = metadata.methods.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(methods, vec!["c", "b", "a"]);
+
+        // Default method: highest priority, not the first declared
+        registry.set_language("x").unwrap();
+        assert_eq!(registry.current_input_method_id(), Some("b"));
+    }
 }
@@ -0,0 +1,306 @@
+//! Vikey Core - Session Manager Module
+//!
+//! Mỗi ô nhập liệu (context) có trạng thái soạn thảo riêng: buffer,
+//! instance input method và lịch sử undo. Chuyển focus giữa các ô không
+//! làm hỏng từ đang soạn ở ô khác.
+//!
+//! Registry, ngôn ngữ/input method được chọn và các cấu hình khác của
+//! `Engine` dùng chung cho mọi context; tùy chọn stage đặt riêng cho một
+//! context (`Engine::set_session_option`) đi theo context đó. Trạng thái
+//! của context đang focus nằm trong `Engine`; các context còn lại được cất
+//! trong `SessionManager` và bị loại theo LRU khi vượt quá giới hạn.
+
+use crate::buffer::InputBuffer;
+use crate::config::{Config, ConfigError};
+use crate::engine::Engine;
+use crate::history::History;
+use crate::traits::InputMethodTrait;
+use crate::transform::StageSession;
+use crate::types::CompositionMode;
+use std::collections::HashMap;
+
+/// ID của một context (ô nhập liệu), do frontend cấp
+pub type ContextId = u64;
+
+/// Số context giữ lại mặc định
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// Trạng thái soạn thảo của một context
+pub(crate) struct Session {
+    /// Input buffer
+    pub(crate) buffer: InputBuffer,
+
+    /// Instance input method riêng của context
+    pub(crate) method: Option<Box<dyn InputMethodTrait>>,
+
+    /// (language, input method) lúc trạng thái được tạo
+    pub(crate) method_key: (Option<String>, Option<String>),
+
+    /// Chế độ soạn thảo lúc trạng thái được tạo
+    pub(crate) composition_mode: CompositionMode,
+
+    /// Lịch sử phím của từ đang soạn
+    pub(crate) history: History,
+}
+
+/// Một context đã đăng ký
+struct Slot {
+    /// Trạng thái đã cất; `None` nếu context đang active hoặc chưa gõ gì
+    session: Option<Session>,
+
+    /// Trạng thái riêng của context trong các stage (giữ cả khi cấu hình
+    /// đổi)
+    stages: Vec<(String, StageSession)>,
+
+    /// Thời điểm dùng gần nhất (bộ đếm tăng dần)
+    last_used: u64,
+}
+
+/// Quản lý trạng thái soạn thảo riêng cho từng context
+///
+/// ```ignore
+/// let mut sessions = SessionManager::new(engine);
+/// sessions.focus(1);
+/// sessions.engine_mut().process('a');
+/// sessions.focus(2); // "a" của context 1 được giữ nguyên
+/// ```
+pub struct SessionManager {
+    /// Engine dùng chung, giữ trạng thái của context đang active
+    engine: Engine,
+
+    /// Các context đã đăng ký
+    contexts: HashMap<ContextId, Slot>,
+
+    /// Context đang có focus
+    active: Option<ContextId>,
+
+    /// Số context tối đa được giữ lại
+    capacity: usize,
+
+    /// Bộ đếm cho LRU
+    clock: u64,
+}
+
+impl SessionManager {
+    /// Tạo session manager với giới hạn mặc định (`DEFAULT_CAPACITY`)
+    pub fn new(engine: Engine) -> Self {
+        Self::with_capacity(engine, DEFAULT_CAPACITY)
+    }
+
+    /// Tạo session manager giữ tối đa `capacity` context (ít nhất 1)
+    pub fn with_capacity(engine: Engine, capacity: usize) -> Self {
+        Self {
+            engine,
+            contexts: HashMap::new(),
+            active: None,
+            capacity: capacity.max(1),
+            clock: 0,
+        }
+    }
+
+    /// Engine dùng chung
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    /// Engine dùng chung; xử lý phím qua đây áp dụng cho context đang active
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    /// Áp dụng cấu hình cho Engine dùng chung (xem `Engine::set_config`)
+    ///
+    /// Nếu cấu hình thay đổi, trạng thái đã cất của các context khác bị bỏ:
+    /// instance input method trong đó được tạo theo tùy chọn cũ. Tùy chọn
+    /// stage đặt riêng cho từng context vẫn được giữ.
+    pub fn set_config(&mut self, config: Config) -> Result<(), Vec<ConfigError>> {
+        let before = self.engine.config();
+        let result = self.engine.set_config(config);
+        if self.engine.config() != before {
+            for slot in self.contexts.values_mut() {
+                slot.session = None;
+            }
+        }
+        result
+    }
+
+    /// Đăng ký một context mới (chưa focus)
+    ///
+    /// # Returns
+    /// `false` nếu context đã tồn tại
+    pub fn create(&mut self, id: ContextId) -> bool {
+        if self.contexts.contains_key(&id) {
+            return false;
+        }
+        let last_used = self.tick();
+        self.contexts.insert(
+            id,
+            Slot {
+                session: None,
+                stages: Vec::new(),
+                last_used,
+            },
+        );
+        self.evict();
+        true
+    }
+
+    /// Chuyển focus sang context `id` (tự tạo nếu chưa có)
+    ///
+    /// Trạng thái của context đang active được cất lại nguyên vẹn; context
+    /// `id` tiếp tục từ đang soạn của nó (nếu có).
+    pub fn focus(&mut self, id: ContextId) {
+        let last_used = self.tick();
+        if self.active == Some(id) {
+            if let Some(slot) = self.contexts.get_mut(&id) {
+                slot.last_used = last_used;
+            }
+            return;
+        }
+
+        match self.active.take() {
+            Some(previous) => {
+                let session = self.engine.take_session();
+                let stages = self.engine.take_stage_sessions();
+                if let Some(slot) = self.contexts.get_mut(&previous) {
+                    slot.session = Some(session);
+                    slot.stages = stages;
+                }
+            }
+            // Trạng thái không thuộc context nào: bỏ
+            None => self.discard_active(),
+        }
+
+        let slot = self.contexts.entry(id).or_insert(Slot {
+            session: None,
+            stages: Vec::new(),
+            last_used,
+        });
+        slot.last_used = last_used;
+        if let Some(session) = slot.session.take() {
+            self.engine.restore_session(session);
+        }
+        self.engine
+            .restore_stage_sessions(std::mem::take(&mut slot.stages));
+
+        self.active = Some(id);
+        self.evict();
+    }
+
+    /// Hủy context `id` (ô nhập liệu bị đóng)
+    ///
+    /// # Returns
+    /// `false` nếu context không tồn tại
+    pub fn destroy(&mut self, id: ContextId) -> bool {
+        if self.contexts.remove(&id).is_none() {
+            return false;
+        }
+        if self.active == Some(id) {
+            self.active = None;
+            self.discard_active();
+        }
+        true
+    }
+
+    /// Context đang active
+    pub fn active(&self) -> Option<ContextId> {
+        self.active
+    }
+
+    /// Context `id` có đang được giữ không?
+    pub fn contains(&self, id: ContextId) -> bool {
+        self.contexts.contains_key(&id)
+    }
+
+    /// Số context đang được giữ
+    pub fn len(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Không có context nào?
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+
+    /// Số context tối đa được giữ lại
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Đặt số context tối đa (ít nhất 1), loại bớt context cũ nếu cần
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.evict();
+    }
+
+    /// Bỏ trạng thái đang nằm trong Engine (không thuộc context nào)
+    fn discard_active(&mut self) {
+        self.engine.reset();
+        self.engine.take_stage_sessions();
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Loại các context dùng lâu nhất cho đến khi không vượt giới hạn
+    ///
+    /// Context đang active không bao giờ bị loại.
+    fn evict(&mut self) {
+        while self.contexts.len() > self.capacity {
+            let oldest = self
+                .contexts
+                .iter()
+                .filter(|(id, _)| Some(**id) != self.active)
+                .min_by_key(|(_, slot)| slot.last_used)
+                .map(|(id, _)| *id);
+            match oldest {
+                Some(id) => {
+                    self.contexts.remove(&id);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifecycle() {
+        let mut sessions = SessionManager::new(Engine::new());
+        assert!(sessions.create(1));
+        assert!(!sessions.create(1));
+        assert_eq!(sessions.active(), None);
+
+        sessions.focus(2);
+        assert_eq!(sessions.active(), Some(2));
+        assert_eq!(sessions.len(), 2);
+
+        assert!(sessions.destroy(2));
+        assert_eq!(sessions.active(), None);
+        assert!(!sessions.destroy(2));
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut sessions = SessionManager::with_capacity(Engine::new(), 2);
+        sessions.focus(1);
+        sessions.focus(2);
+        sessions.focus(1);
+        sessions.focus(3);
+
+        // 2 là context dùng lâu nhất
+        assert!(!sessions.contains(2));
+        assert!(sessions.contains(1));
+        assert!(sessions.contains(3));
+
+        // Context active không bị loại
+        sessions.set_capacity(1);
+        assert!(sessions.contains(3));
+        assert_eq!(sessions.len(), 1);
+    }
+}
@@ -0,0 +1,331 @@
+//! Vikey Core - Shorthand (Gõ tắt)
+//!
+//! Bảng gõ tắt của user (ví dụ: "vn" → "Việt Nam") và stage `Shorthand`
+//! mở rộng từ vừa gõ khi gặp ranh giới từ. Khóa được so với từ đã soạn
+//! (sau khi input method biến đổi), nên khóa có thể chứa chữ tiếng Việt:
+//! gõ "ddc" rồi Space với khóa "đc" cho ra "được".
+
+use crate::key::KeyEvent;
+use crate::transform::{order, Middleware, StageContext};
+use crate::types::{Action, CompositionMode};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// ID của stage gõ tắt (bật/tắt theo `Config::shorthand`)
+pub const ID: &str = "shorthand";
+
+/// Lỗi khi thêm một mục gõ tắt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacroError {
+    /// Khóa rỗng
+    EmptyKey,
+
+    /// Khóa chứa khoảng trắng hoặc ký tự điều khiển (không bao giờ khớp
+    /// với một từ)
+    InvalidKey(String),
+
+    /// Nội dung mở rộng rỗng
+    EmptyExpansion(String),
+}
+
+impl fmt::Display for MacroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MacroError::EmptyKey => write!(f, "Shorthand key is empty"),
+            MacroError::InvalidKey(key) => {
+                write!(
+                    f,
+                    "Shorthand key '{}' contains spaces or control characters",
+                    key
+                )
+            }
+            MacroError::EmptyExpansion(key) => {
+                write!(f, "Shorthand '{}' has an empty expansion", key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MacroError {}
+
+/// Bảng gõ tắt: khóa → nội dung mở rộng
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MacroTable {
+    entries: BTreeMap<String, String>,
+}
+
+impl MacroTable {
+    /// Tạo bảng rỗng
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Kiểm tra một mục gõ tắt
+    pub fn validate(key: &str, expansion: &str) -> Result<(), MacroError> {
+        if key.is_empty() {
+            return Err(MacroError::EmptyKey);
+        }
+        if key.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(MacroError::InvalidKey(key.to_string()));
+        }
+        if expansion.is_empty() {
+            return Err(MacroError::EmptyExpansion(key.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Thêm (hoặc thay) một mục
+    ///
+    /// # Returns
+    /// Nội dung cũ của khóa (nếu có)
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        expansion: impl Into<String>,
+    ) -> Result<Option<String>, MacroError> {
+        let (key, expansion) = (key.into(), expansion.into());
+        Self::validate(&key, &expansion)?;
+        Ok(self.entries.insert(key, expansion))
+    }
+
+    /// Xóa một mục, trả về nội dung của nó
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.entries.remove(key)
+    }
+
+    /// Nội dung của đúng khóa `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Số mục
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Bảng rỗng?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Các mục theo thứ tự khóa
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Nội dung mở rộng cho từ `word`
+    ///
+    /// Khóa khớp đúng chữ hoa/thường được dùng nguyên văn. Nếu không, khóa
+    /// được so không phân biệt hoa/thường và kiểu chữ của từ được giữ:
+    /// "vn" → "Việt Nam", "Vn" → "Việt Nam", "VN" → "VIỆT NAM" (với mục
+    /// "vn" = "Việt Nam"); "ko" → "không", "Ko" → "Không".
+    pub fn expand(&self, word: &str) -> Option<String> {
+        if let Some(expansion) = self.get(word) {
+            return Some(expansion.to_string());
+        }
+        let lower = word.to_lowercase();
+        let expansion = self
+            .entries
+            .iter()
+            .find(|(key, _)| key.to_lowercase() == lower)
+            .map(|(_, expansion)| expansion)?;
+
+        Some(match CasePattern::of(word) {
+            CasePattern::Upper => expansion.to_uppercase(),
+            CasePattern::Capitalized => capitalize(expansion),
+            CasePattern::Other => expansion.clone(),
+        })
+    }
+}
+
+/// Kiểu chữ hoa/thường của từ đã gõ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CasePattern {
+    /// Tất cả chữ hoa, ít nhất hai chữ ("VN")
+    Upper,
+    /// Chữ đầu hoa, còn lại thường ("Vn", "K")
+    Capitalized,
+    /// Thường hoặc lẫn lộn
+    Other,
+}
+
+impl CasePattern {
+    fn of(word: &str) -> Self {
+        let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+        match letters.split_first() {
+            Some((first, rest)) if first.is_uppercase() => {
+                if rest.iter().all(|c| c.is_lowercase()) {
+                    CasePattern::Capitalized
+                } else if rest.iter().all(|c| c.is_uppercase()) {
+                    CasePattern::Upper
+                } else {
+                    CasePattern::Other
+                }
+            }
+            _ => CasePattern::Other,
+        }
+    }
+}
+
+/// Viết hoa chữ cái đầu
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Stage gõ tắt
+///
+/// Khi một từ kết thúc bằng phím không phải chữ/số (Space, dấu câu...) và
+/// từ đó có trong bảng, Action của phím được thay bằng một Action duy nhất
+/// viết nội dung mở rộng kèm phím kết thúc: `Replace` ở chế độ Direct,
+/// `Commit` ở chế độ Preedit.
+#[derive(Debug, Clone, Default)]
+pub struct Shorthand {
+    table: MacroTable,
+}
+
+impl Shorthand {
+    /// Tạo stage với bảng gõ tắt
+    pub fn new(table: MacroTable) -> Self {
+        Self { table }
+    }
+
+    /// Bảng gõ tắt
+    pub fn table(&self) -> &MacroTable {
+        &self.table
+    }
+
+    /// Bảng gõ tắt (mutable)
+    pub fn table_mut(&mut self) -> &mut MacroTable {
+        &mut self.table
+    }
+}
+
+/// Ký tự kết thúc từ của phím (không phải chữ/số)
+fn separator(key: Option<&KeyEvent>) -> Option<char> {
+    key.and_then(|key| key.char())
+        .filter(|c| !c.is_alphanumeric() && !c.is_control())
+}
+
+impl Middleware for Shorthand {
+    fn id(&self) -> &str {
+        ID
+    }
+
+    fn order(&self) -> i32 {
+        order::SHORTHAND
+    }
+
+    fn process(&mut self, action: Action, context: &StageContext) -> Action {
+        if !context.word_ended() {
+            return action;
+        }
+        let Some(separator) = separator(context.key) else {
+            return action;
+        };
+        let Some(expansion) = self.table.expand(context.before) else {
+            return action;
+        };
+
+        // Từ đã soạn đang nằm trên host (Direct) hoặc trong preedit, bất kể
+        // input method định làm gì với nó (ví dụ: auto-restore)
+        let text = format!("{}{}", expansion, separator);
+        match context.mode {
+            CompositionMode::Direct => Action::Replace {
+                backspace_count: context.before.chars().count(),
+                text,
+            },
+            CompositionMode::Preedit => Action::Commit(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> MacroTable {
+        let mut table = MacroTable::new();
+        table.insert("vn", "Việt Nam").unwrap();
+        table.insert("ko", "không").unwrap();
+        table.insert("đc", "được").unwrap();
+        table.insert("HN", "Hà Nội").unwrap();
+        table
+    }
+
+    #[test]
+    fn test_expand_preserves_case() {
+        let table = table();
+        assert_eq!(table.expand("vn").as_deref(), Some("Việt Nam"));
+        assert_eq!(table.expand("VN").as_deref(), Some("VIỆT NAM"));
+        assert_eq!(table.expand("ko").as_deref(), Some("không"));
+        assert_eq!(table.expand("Ko").as_deref(), Some("Không"));
+        assert_eq!(table.expand("KO").as_deref(), Some("KHÔNG"));
+        assert_eq!(table.expand("kO").as_deref(), Some("không"));
+        assert_eq!(table.expand("Đc").as_deref(), Some("Được"));
+        assert_eq!(table.expand("HN").as_deref(), Some("Hà Nội"));
+        assert_eq!(table.expand("hn").as_deref(), Some("Hà Nội"));
+        assert_eq!(table.expand("viet"), None);
+    }
+
+    #[test]
+    fn test_invalid_entries() {
+        let mut table = MacroTable::new();
+        assert_eq!(table.insert("", "x"), Err(MacroError::EmptyKey));
+        assert_eq!(
+            table.insert("v n", "x"),
+            Err(MacroError::InvalidKey("v n".to_string()))
+        );
+        assert_eq!(
+            table.insert("vn", ""),
+            Err(MacroError::EmptyExpansion("vn".to_string()))
+        );
+        assert_eq!(table.insert("vn", "Việt Nam"), Ok(None));
+        assert_eq!(
+            table.insert("vn", "Việt Nam!"),
+            Ok(Some("Việt Nam".to_string()))
+        );
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn test_stage_expands_on_word_end() {
+        let mut stage = Shorthand::new(table());
+        let space = KeyEvent::from_char(' ');
+        let mut context = StageContext {
+            key: Some(&space),
+            before: "đc",
+            composing: "",
+            mode: CompositionMode::Direct,
+        };
+        assert_eq!(
+            stage.process(Action::PassThrough, &context),
+            Action::Replace {
+                backspace_count: 2,
+                text: "được ".to_string(),
+            }
+        );
+
+        context.mode = CompositionMode::Preedit;
+        assert_eq!(
+            stage.process(Action::CommitPreedit, &context),
+            Action::Commit("được ".to_string())
+        );
+
+        // Từ chưa kết thúc, hoặc kết thúc không do phím phân cách
+        context.composing = "đc";
+        assert_eq!(
+            stage.process(Action::DoNothing, &context),
+            Action::DoNothing
+        );
+        context.composing = "";
+        context.key = None;
+        assert_eq!(
+            stage.process(Action::DoNothing, &context),
+            Action::DoNothing
+        );
+    }
+}
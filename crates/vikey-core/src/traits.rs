@@ -4,7 +4,11 @@
 //! multi-language support (Vietnamese, Nôm, Tai, Cham, etc.)
 
 use crate::buffer::InputBuffer;
-use crate::types::{Action, CharInfo};
+use crate::candidate::CandidateList;
+use crate::config::{OptionSpec, OptionValue};
+use crate::key::KeyEvent;
+use crate::metadata::{MethodMetadata, PluginMetadata};
+use crate::types::{Action, CharInfo, CompositionMode};
 
 /// Trait cho một Language Plugin
 ///
@@ -27,6 +31,36 @@ pub trait LanguagePlugin: Send + Sync {
 
     /// Quy tắc chính tả cho ngôn ngữ này
     fn rules(&self) -> &dyn LanguageRules;
+
+    /// Mô tả plugin và các input method (cho tray menu, giao diện cấu hình)
+    ///
+    /// Mặc định chỉ gồm `id`, `name` và `input_methods` theo thứ tự khai
+    /// báo. Input method không có trong `input_methods` bị Registry bỏ qua.
+    fn metadata(&self) -> PluginMetadata {
+        self.input_methods().into_iter().fold(
+            PluginMetadata::new(self.id(), self.name()),
+            |metadata, id| metadata.with_method(MethodMetadata::new(id, id)),
+        )
+    }
+
+    /// Schema các tùy chọn mà plugin hỗ trợ (cho UI và kiểm tra cấu hình)
+    fn options(&self) -> Vec<OptionSpec> {
+        Vec::new()
+    }
+
+    /// Giá trị hiện tại của một tùy chọn
+    fn option(&self, _key: &str) -> Option<OptionValue> {
+        None
+    }
+
+    /// Đặt một tùy chọn
+    ///
+    /// Engine đã kiểm tra `value` theo `options()` trước khi gọi. Giá trị
+    /// mới áp dụng cho các input method tạo sau đó; Engine tự tạo lại input
+    /// method hiện tại.
+    fn set_option(&mut self, key: &str, _value: &OptionValue) -> Result<(), String> {
+        Err(format!("unknown option '{}'", key))
+    }
 }
 
 /// Trait cho một Input Method (Telex, VNI, NomPinyin...)
@@ -42,16 +76,19 @@ pub trait InputMethodTrait: Send + Sync {
 
     /// Xử lý một keystroke
     ///
+    /// Engine chỉ chuyển xuống các sự kiện nhấn phím có sinh ký tự
+    /// (`key.char()` là `Some`), không phải tổ hợp phím tắt.
+    ///
     /// # Arguments
-    /// * `key` - Ký tự vừa gõ
+    /// * `key` - Sự kiện phím vừa gõ
     /// * `buffer` - Buffer hiện tại
     /// * `lookup` - Bảng tra cứu ký tự
     ///
     /// # Returns
-    /// Action cần thực hiện (Replace, Commit, DoNothing)
+    /// Action cần thực hiện (Replace, Commit, DoNothing, PassThrough)
     fn process(
         &mut self,
-        key: char,
+        key: &KeyEvent,
         buffer: &mut InputBuffer,
         lookup: &dyn LookupProvider,
     ) -> Action;
@@ -66,7 +103,67 @@ pub trait InputMethodTrait: Send + Sync {
     fn can_undo(&self, buffer: &InputBuffer) -> bool;
 
     /// Thực hiện undo transformation gần nhất
+    ///
+    /// Engine tự quản lý lịch sử undo/redo nhiều cấp cho mọi input method
+    /// (`Engine::undo`); hàm này chỉ dùng khi gọi input method trực tiếp.
     fn undo(&mut self, buffer: &mut InputBuffer) -> Action;
+
+    /// Chọn chế độ soạn thảo (Direct hoặc Preedit)
+    ///
+    /// Trả về `false` nếu input method không hỗ trợ chế độ này; khi đó
+    /// input method tiếp tục dùng `CompositionMode::Direct`.
+    fn set_composition_mode(&mut self, mode: CompositionMode) -> bool {
+        mode == CompositionMode::Direct
+    }
+
+    /// Các phím gốc đã gõ cho từ đang soạn (ví dụ: "tieesng" cho "tiếng")
+    ///
+    /// Dùng cho `Engine::restore_raw`. Trả về `None` nếu input method
+    /// không lưu lịch sử phím.
+    fn raw_input(&self) -> Option<String> {
+        None
+    }
+
+    /// Phím vừa xử lý có bị kiểm tra chính tả từ chối không?
+    ///
+    /// `true` khi phím làm từ đang soạn không còn là từ hợp lệ và được giữ
+    /// nguyên như đã gõ thay vì biến đổi. Engine phát `EngineEvent::Rejected`.
+    fn rejected_last_key(&self) -> bool {
+        false
+    }
+
+    /// Soạn tiếp từ đã commit ngay trước con trỏ
+    ///
+    /// `text_before` là văn bản trước con trỏ do host cung cấp. Input method
+    /// dựng lại trạng thái soạn thảo từ từ cuối cùng (đồng bộ `buffer`) để
+    /// các phím tiếp theo (dấu thanh, dấu mũ...) sửa được từ đó.
+    ///
+    /// # Returns
+    /// Số ký tự cuối của `text_before` đã nhận làm từ đang soạn, 0 nếu
+    /// không soạn tiếp được
+    fn resume(&mut self, _text_before: &str, _buffer: &mut InputBuffer) -> usize {
+        0
+    }
+
+    /// Danh sách candidate đang hiển thị (input method dựa trên lựa chọn)
+    ///
+    /// Trả về `None` nếu input method không dùng candidate hoặc cửa sổ
+    /// candidate đang đóng.
+    fn candidates(&self) -> Option<&CandidateList> {
+        None
+    }
+
+    /// Danh sách candidate (mutable) để Engine phân trang / di chuyển lựa chọn
+    fn candidates_mut(&mut self) -> Option<&mut CandidateList> {
+        None
+    }
+
+    /// Commit candidate theo index tuyệt đối trong danh sách
+    ///
+    /// Input method tự reset trạng thái và trả về Action ghi văn bản.
+    fn select_candidate(&mut self, _index: usize, _buffer: &mut InputBuffer) -> Action {
+        Action::DoNothing
+    }
 }
 
 /// Trait cho Lookup Provider (bảng tra cứu ký tự)
@@ -1,105 +1,356 @@
-//! Transformation trait and types
-
-use crate::Result;
-
-/// Result of a transformation
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct TransformResult {
-    /// Transformed output
-    pub output: String,
-    
-    /// Number of input characters consumed
-    pub consumed: usize,
+//! Vikey Core - Middleware Pipeline
+//!
+//! Các bước hậu xử lý chạy sau input method: mỗi stage nhận `Action` mà
+//! input method (hoặc stage trước) trả về và có thể sửa lại trước khi
+//! Engine trả cho host. Ví dụ: gõ tắt, tự viết hoa đầu câu, tự sửa lỗi,
+//! chuyển bảng mã đầu ra.
+
+use crate::config::{OptionSpec, OptionValue};
+use crate::key::KeyEvent;
+use crate::registry::RegistryError;
+use crate::types::{Action, CompositionMode};
+use std::any::Any;
+
+/// Thứ tự chuẩn của các stage (nhỏ hơn chạy trước)
+pub mod order {
+    /// Gõ tắt
+    pub const SHORTHAND: i32 = 100;
+    /// Tự viết hoa đầu câu
+    pub const CAPITALIZATION: i32 = 200;
+    /// Tự sửa lỗi
+    pub const AUTOCORRECT: i32 = 300;
+    /// Chuyển bảng mã đầu ra (luôn chạy cuối)
+    pub const ENCODING: i32 = 1000;
+}
+
+/// Ngữ cảnh của một lần chạy pipeline
+#[derive(Debug, Clone, Copy)]
+pub struct StageContext<'a> {
+    /// Phím đã gây ra Action (`None`: lời gọi không do phím, ví dụ
+    /// `select_candidate`, `on_focus_out`)
+    pub key: Option<&'a KeyEvent>,
+
+    /// Văn bản từ đang soạn trước phím này
+    pub before: &'a str,
+
+    /// Văn bản từ đang soạn sau phím này (rỗng nếu từ đã kết thúc)
+    pub composing: &'a str,
+
+    /// Chế độ soạn thảo đang dùng
+    pub mode: CompositionMode,
+}
+
+impl StageContext<'_> {
+    /// Từ vừa kết thúc ở phím này? (`before` là từ vừa kết thúc)
+    pub fn word_ended(&self) -> bool {
+        !self.before.is_empty() && self.composing.is_empty()
+    }
 }
 
-/// Trait for transforming input text
-pub trait Transformer: Send + Sync {
-    /// Transform input string
+/// Trạng thái riêng của một context mà stage cất lại khi focus chuyển đi
+/// (xem `Middleware::take_session`)
+pub type StageSession = Box<dyn Any + Send + Sync>;
+
+/// Trait cho một stage của pipeline
+///
+/// Stage có tùy chọn riêng (schema giống `LanguagePlugin::options`), được
+/// đặt qua `Config::stages`.
+pub trait Middleware: Send + Sync {
+    /// ID duy nhất (ví dụ: "shorthand", "encoding")
+    fn id(&self) -> &str;
+
+    /// Vị trí trong pipeline (xem `order`); bằng nhau thì theo thứ tự thêm
+    fn order(&self) -> i32 {
+        0
+    }
+
+    /// Xử lý Action, trả về Action mới (hoặc chính nó nếu không đổi)
+    fn process(&mut self, action: Action, context: &StageContext) -> Action;
+
+    /// Bỏ trạng thái đang giữ (composition bị reset, đổi context...)
+    fn reset(&mut self) {}
+
+    /// Schema các tùy chọn mà stage hỗ trợ
+    fn options(&self) -> Vec<OptionSpec> {
+        Vec::new()
+    }
+
+    /// Giá trị hiện tại của một tùy chọn
+    fn option(&self, _key: &str) -> Option<OptionValue> {
+        None
+    }
+
+    /// Đặt một tùy chọn (Engine đã kiểm tra `value` theo `options()`)
+    fn set_option(&mut self, key: &str, _value: &OptionValue) -> Result<(), String> {
+        Err(format!("unknown option '{}'", key))
+    }
+
+    /// Đặt tùy chọn chỉ cho context đang active (`None`: trở về giá trị
+    /// chung), giữ cùng context qua `take_session`/`restore_session`
     ///
-    /// Returns Some(result) if transformation was applied,
-    /// None if no transformation needed
-    fn transform(&self, input: &str) -> Option<TransformResult>;
-    
-    /// Get transformer name
-    fn name(&self) -> &str;
+    /// Engine đã kiểm tra `value` theo `options()`.
+    fn set_session_option(
+        &mut self,
+        key: &str,
+        _value: Option<&OptionValue>,
+    ) -> Result<(), String> {
+        Err(format!("option '{}' cannot be set per context", key))
+    }
+
+    /// Lấy trạng thái riêng của context đang active ra, trở về trạng thái
+    /// chung (`None`: không có gì riêng)
+    fn take_session(&mut self) -> Option<StageSession> {
+        None
+    }
+
+    /// Khôi phục trạng thái đã lấy ra bằng `take_session`
+    fn restore_session(&mut self, _session: StageSession) {}
+}
+
+/// Một stage trong pipeline
+struct Stage {
+    middleware: Box<dyn Middleware>,
+    enabled: bool,
 }
 
-/// Transform engine that manages transformers
-pub struct Transform {
-    transformers: Vec<Box<dyn Transformer>>,
+/// Danh sách stage theo thứ tự chạy
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
 }
 
-impl Transform {
-    /// Create new transform engine
+impl Pipeline {
+    /// Tạo pipeline rỗng
     pub fn new() -> Self {
-        Self {
-            transformers: Vec::new(),
+        Self::default()
+    }
+
+    /// Thêm stage (đang bật) vào đúng vị trí theo `order()`
+    pub fn add(&mut self, middleware: Box<dyn Middleware>) -> Result<(), RegistryError> {
+        if self.get(middleware.id()).is_some() {
+            return Err(RegistryError::DuplicateId(middleware.id().to_string()));
+        }
+        let order = middleware.order();
+        let index = self
+            .stages
+            .iter()
+            .position(|stage| stage.middleware.order() > order)
+            .unwrap_or(self.stages.len());
+        self.stages.insert(
+            index,
+            Stage {
+                middleware,
+                enabled: true,
+            },
+        );
+        Ok(())
+    }
+
+    /// Thay stage cùng ID (giữ trạng thái bật/tắt), hoặc thêm nếu chưa có
+    ///
+    /// # Returns
+    /// Stage cũ (nếu có)
+    pub fn replace(&mut self, middleware: Box<dyn Middleware>) -> Option<Box<dyn Middleware>> {
+        let Some(index) = self.position(middleware.id()) else {
+            let _ = self.add(middleware);
+            return None;
+        };
+        Some(std::mem::replace(
+            &mut self.stages[index].middleware,
+            middleware,
+        ))
+    }
+
+    /// Bỏ stage `id`
+    pub fn remove(&mut self, id: &str) -> Option<Box<dyn Middleware>> {
+        let index = self.position(id)?;
+        Some(self.stages.remove(index).middleware)
+    }
+
+    /// Bật/tắt stage `id`, trả về `false` nếu không tồn tại
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        let Some(index) = self.position(id) else {
+            return false;
+        };
+        let stage = &mut self.stages[index];
+        if stage.enabled && !enabled {
+            stage.middleware.reset();
         }
+        stage.enabled = enabled;
+        true
     }
-    
-    /// Add transformer
-    pub fn add_transformer(&mut self, transformer: Box<dyn Transformer>) {
-        self.transformers.push(transformer);
-    }
-    
-    /// Apply transformations
-    pub fn apply(&self, input: &str) -> Option<TransformResult> {
-        for transformer in &self.transformers {
-            if let Some(result) = transformer.transform(input) {
-                return Some(result);
+
+    /// Stage `id` đang bật?
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.position(id)
+            .is_some_and(|index| self.stages[index].enabled)
+    }
+
+    /// ID các stage theo thứ tự chạy
+    pub fn ids(&self) -> Vec<&str> {
+        self.stages
+            .iter()
+            .map(|stage| stage.middleware.id())
+            .collect()
+    }
+
+    /// Lấy stage theo ID
+    pub fn get(&self, id: &str) -> Option<&dyn Middleware> {
+        let index = self.position(id)?;
+        Some(self.stages[index].middleware.as_ref())
+    }
+
+    /// Lấy stage theo ID (mutable)
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut dyn Middleware> {
+        let index = self.position(id)?;
+        Some(self.stages[index].middleware.as_mut())
+    }
+
+    /// Pipeline rỗng?
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Cho Action đi qua các stage đang bật
+    pub fn run(&mut self, action: Action, context: &StageContext) -> Action {
+        self.stages
+            .iter_mut()
+            .filter(|stage| stage.enabled)
+            .fold(action, |action, stage| {
+                stage.middleware.process(action, context)
+            })
+    }
+
+    /// Reset tất cả các stage
+    pub fn reset(&mut self) {
+        for stage in &mut self.stages {
+            stage.middleware.reset();
+        }
+    }
+
+    /// Lấy trạng thái riêng của context đang active ra khỏi các stage
+    pub fn take_sessions(&mut self) -> Vec<(String, StageSession)> {
+        self.stages
+            .iter_mut()
+            .filter_map(|stage| {
+                let session = stage.middleware.take_session()?;
+                Some((stage.middleware.id().to_string(), session))
+            })
+            .collect()
+    }
+
+    /// Trả trạng thái đã lấy ra bằng `take_sessions` cho các stage (bỏ qua
+    /// stage không còn trong pipeline)
+    pub fn restore_sessions(&mut self, sessions: Vec<(String, StageSession)>) {
+        for (id, session) in sessions {
+            if let Some(stage) = self.get_mut(&id) {
+                stage.restore_session(session);
             }
         }
-        None
     }
-}
 
-impl Default for Transform {
-    fn default() -> Self {
-        Self::new()
+    fn position(&self, id: &str) -> Option<usize> {
+        self.stages
+            .iter()
+            .position(|stage| stage.middleware.id() == id)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
-    struct TestTransformer;
-    
-    impl Transformer for TestTransformer {
-        fn transform(&self, input: &str) -> Option<TransformResult> {
-            if input == "aa" {
-                Some(TransformResult {
-                    output: "â".to_string(),
-                    consumed: 2,
-                })
-            } else {
-                None
+
+    /// Thêm hậu tố vào văn bản của `Commit`
+    struct Suffix {
+        id: &'static str,
+        order: i32,
+        suffix: String,
+    }
+
+    impl Middleware for Suffix {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn order(&self) -> i32 {
+            self.order
+        }
+
+        fn process(&mut self, action: Action, _context: &StageContext) -> Action {
+            match action {
+                Action::Commit(text) => Action::Commit(text + &self.suffix),
+                action => action,
             }
         }
-        
-        fn name(&self) -> &str {
-            "test"
+    }
+
+    fn suffix(id: &'static str, order: i32) -> Box<dyn Middleware> {
+        Box::new(Suffix {
+            id,
+            order,
+            suffix: id.to_string(),
+        })
+    }
+
+    fn context() -> StageContext<'static> {
+        StageContext {
+            key: None,
+            before: "",
+            composing: "",
+            mode: CompositionMode::Direct,
         }
     }
 
     #[test]
-    fn test_transformer() {
-        let transformer = TestTransformer;
-        let result = transformer.transform("aa");
-        assert!(result.is_some());
-        assert_eq!(result.unwrap().output, "â");
+    fn test_stage_order() {
+        let mut pipeline = Pipeline::new();
+        pipeline.add(suffix("c", order::ENCODING)).unwrap();
+        pipeline.add(suffix("a", order::SHORTHAND)).unwrap();
+        pipeline.add(suffix("b", order::SHORTHAND)).unwrap();
+        assert_eq!(pipeline.ids(), vec!["a", "b", "c"]);
+        assert_eq!(
+            pipeline.add(suffix("a", 0)),
+            Err(RegistryError::DuplicateId("a".to_string()))
+        );
+
+        let action = pipeline.run(Action::Commit(">".to_string()), &context());
+        assert_eq!(action, Action::Commit(">abc".to_string()));
+        assert_eq!(
+            pipeline.run(Action::PassThrough, &context()),
+            Action::PassThrough
+        );
     }
-    
+
+    #[test]
+    fn test_enable_and_remove() {
+        let mut pipeline = Pipeline::new();
+        pipeline.add(suffix("a", 0)).unwrap();
+        pipeline.add(suffix("b", 0)).unwrap();
+
+        assert!(pipeline.set_enabled("a", false));
+        assert!(!pipeline.is_enabled("a"));
+        assert!(!pipeline.set_enabled("z", false));
+        let action = pipeline.run(Action::Commit(String::new()), &context());
+        assert_eq!(action, Action::Commit("b".to_string()));
+
+        assert!(pipeline.replace(suffix("a", 0)).is_some());
+        assert!(!pipeline.is_enabled("a"));
+        assert!(pipeline.replace(suffix("c", 0)).is_none());
+        assert_eq!(pipeline.ids(), vec!["a", "b", "c"]);
+
+        assert!(pipeline.remove("c").is_some());
+        assert!(pipeline.remove("b").is_some());
+        assert!(pipeline.remove("b").is_none());
+        assert_eq!(pipeline.ids(), vec!["a"]);
+    }
+
     #[test]
-    fn test_transform_engine() {
-        let mut transform = Transform::new();
-        transform.add_transformer(Box::new(TestTransformer));
-        
-        let result = transform.apply("aa");
-        assert!(result.is_some());
-        assert_eq!(result.unwrap().output, "â");
-        
-        let result = transform.apply("bb");
-        assert!(result.is_none());
+    fn test_word_ended() {
+        let mut context = context();
+        assert!(!context.word_ended());
+        context.before = "viet";
+        assert!(context.word_ended());
+        context.composing = "việt";
+        assert!(!context.word_ended());
     }
 }
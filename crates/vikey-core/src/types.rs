@@ -1,5 +1,7 @@
 // types.rs - Generic types for Vikey Core
 
+use crate::candidate::CandidateList;
+
 /// Word form classification (generic, can be used by any language)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WordForm {
@@ -22,10 +24,16 @@ pub enum WordForm {
 /// Action to be performed by the IME
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
-    /// Do nothing
+    /// Key was consumed, nothing to change in the text
     DoNothing,
 
+    /// Key was not handled by the IME; the host must deliver it to the application
+    PassThrough,
+
     /// Commit text as-is
+    ///
+    /// In preedit mode the current preedit string is discarded and
+    /// replaced by `text`.
     Commit(String),
 
     /// Replace previous text
@@ -35,6 +43,114 @@ pub enum Action {
         /// New text to insert
         text: String,
     },
+
+    /// Show or update the composition string (preedit mode only)
+    ///
+    /// The host replaces its whole preedit with `text`; an empty `text`
+    /// clears the preedit.
+    UpdatePreedit {
+        /// Full composition string
+        text: String,
+        /// Cursor position inside `text`, in characters
+        cursor: usize,
+        /// Styled ranges of `text`
+        segments: Vec<PreeditSegment>,
+    },
+
+    /// Commit the current composition string as-is (preedit mode only)
+    ///
+    /// After committing, the host delivers the key that triggered the
+    /// commit (space, punctuation, ...) to the application normally, if any.
+    CommitPreedit,
+
+    /// Show or update the candidate window
+    ///
+    /// The window stays open until `HideCandidates` or any action that
+    /// writes text (`Commit`, `Replace`, `CommitPreedit`).
+    ShowCandidates(CandidateList),
+
+    /// Close the candidate window without writing text
+    HideCandidates,
+}
+
+/// How the IME edits text in the host application
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CompositionMode {
+    /// On-the-spot editing: text is written directly into the document and
+    /// corrected with `Action::Replace` (backspace-and-retype)
+    #[default]
+    Direct,
+
+    /// Underlined composition: the host renders a preedit string from
+    /// `Action::UpdatePreedit` and only commits on word boundaries
+    Preedit,
+}
+
+/// What happens to the current composition when the host interrupts it
+/// (focus lost, caret moved, mouse click)
+///
+/// Only matters in preedit mode: in direct mode the text is already in the
+/// document, so the engine just ends the word either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum InterruptPolicy {
+    /// Commit the preedit string as shown
+    #[default]
+    Commit,
+
+    /// Clear the preedit string without committing
+    Discard,
+}
+
+/// Rendering style of a preedit segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreeditStyle {
+    /// Normal composition text (underlined)
+    Underline,
+    /// Segment currently being converted (highlighted)
+    Highlight,
+}
+
+/// A styled range of the preedit string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreeditSegment {
+    /// Start offset, in characters
+    pub start: usize,
+    /// End offset (exclusive), in characters
+    pub end: usize,
+    /// Rendering style
+    pub style: PreeditStyle,
+}
+
+impl PreeditSegment {
+    /// Underlined segment covering `start..end`
+    pub fn underline(start: usize, end: usize) -> Self {
+        Self {
+            start,
+            end,
+            style: PreeditStyle::Underline,
+        }
+    }
+}
+
+impl Action {
+    /// Preedit update showing `text` fully underlined, cursor at the end
+    pub fn preedit(text: String) -> Self {
+        let len = text.chars().count();
+        let segments = if len == 0 {
+            Vec::new()
+        } else {
+            vec![PreeditSegment::underline(0, len)]
+        };
+        Action::UpdatePreedit {
+            text,
+            cursor: len,
+            segments,
+        }
+    }
 }
 
 /// Character information for lookup
@@ -100,25 +216,6 @@ impl Default for CharInfo {
     }
 }
 
-/// Configuration for the IME
-#[derive(Debug, Clone)]
-pub struct Config {
-    /// Enable/disable the IME
-    pub enabled: bool,
-
-    /// Auto-commit on separator
-    pub auto_commit: bool,
-}
-
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            enabled: true,
-            auto_commit: true,
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,9 +227,20 @@ mod tests {
     }
 
     #[test]
-    fn test_config_default() {
-        let config = Config::default();
-        assert!(config.enabled);
-        assert!(config.auto_commit);
+    fn test_preedit_action() {
+        let action = Action::preedit("tiếng".to_string());
+        assert_eq!(
+            action,
+            Action::UpdatePreedit {
+                text: "tiếng".to_string(),
+                cursor: 5,
+                segments: vec![PreeditSegment::underline(0, 5)],
+            }
+        );
+
+        assert!(matches!(
+            Action::preedit(String::new()),
+            Action::UpdatePreedit { cursor: 0, ref segments, .. } if segments.is_empty()
+        ));
     }
 }
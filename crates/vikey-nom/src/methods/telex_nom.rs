@@ -6,12 +6,15 @@ use crate::dictionary::NomDictionary;
 use crate::types::NomCandidate;
 use vikey_core::traits::{InputMethodTrait, LookupProvider};
 use vikey_core::types::Action;
-use vikey_core::InputBuffer;
+use vikey_core::{Candidate, CandidateList, InputBuffer, KeyEvent};
 
 /// Telex-Nôm Input Method
 ///
 /// Người dùng gõ phiên âm Quốc ngữ (ví dụ: "nguoi", "viet")
 /// và chọn chữ Nôm tương ứng từ candidate list.
+///
+/// Phiên âm đang gõ không được ghi vào văn bản mà hiển thị trong dòng phụ
+/// của cửa sổ candidate; chỉ chữ Nôm được chọn mới được commit.
 pub struct TelexNomMethod {
     /// Dictionary tra cứu
     dictionary: NomDictionary,
@@ -20,10 +23,7 @@ pub struct TelexNomMethod {
     syllable_buffer: String,
 
     /// Candidates hiện tại
-    candidates: Vec<NomCandidate>,
-
-    /// Index của candidate đang chọn
-    selected_index: usize,
+    candidates: CandidateList,
 }
 
 impl TelexNomMethod {
@@ -32,28 +32,48 @@ impl TelexNomMethod {
         Self {
             dictionary: NomDictionary::new(),
             syllable_buffer: String::new(),
-            candidates: Vec::new(),
-            selected_index: 0,
+            candidates: CandidateList::default(),
         }
     }
 
-    /// Lấy candidates hiện tại
-    pub fn candidates(&self) -> &[NomCandidate] {
-        &self.candidates
+    /// Cập nhật candidates dựa trên syllable buffer
+    fn update_candidates(&mut self) {
+        let entries = self
+            .dictionary
+            .lookup(&self.syllable_buffer)
+            .iter()
+            .map(to_candidate)
+            .collect();
+        self.candidates = CandidateList::new(entries).with_aux_text(self.syllable_buffer.clone());
     }
 
-    /// Chọn candidate theo index
-    pub fn select(&mut self, index: usize) -> Option<char> {
-        self.candidates.get(index).map(|c| c.character)
+    /// Trạng thái cửa sổ candidate sau khi syllable buffer thay đổi
+    fn show_candidates(&self) -> Action {
+        if self.syllable_buffer.is_empty() {
+            Action::HideCandidates
+        } else {
+            Action::ShowCandidates(self.candidates.clone())
+        }
     }
 
-    /// Cập nhật candidates dựa trên syllable buffer
-    fn update_candidates(&mut self) {
-        self.candidates = self.dictionary.lookup(&self.syllable_buffer);
-        self.selected_index = 0;
+    /// Commit văn bản và xóa trạng thái
+    fn commit(&mut self, text: String, buffer: &mut InputBuffer) -> Action {
+        self.reset();
+        buffer.clear();
+        Action::Commit(text)
     }
 }
 
+/// Chuyển NomCandidate sang candidate chung của core
+fn to_candidate(candidate: &NomCandidate) -> Candidate {
+    let mut result =
+        Candidate::new(candidate.character.to_string()).with_annotation(&candidate.quoc_ngu);
+    if let Some(meaning) = &candidate.meaning {
+        result = result.with_comment(meaning);
+    }
+    result
+}
+
 impl Default for TelexNomMethod {
     fn default() -> Self {
         Self::new()
@@ -71,58 +91,31 @@ impl InputMethodTrait for TelexNomMethod {
 
     fn process(
         &mut self,
-        key: char,
+        key: &KeyEvent,
         buffer: &mut InputBuffer,
         _lookup: &dyn LookupProvider,
     ) -> Action {
+        let Some(key) = key.char() else {
+            return Action::PassThrough;
+        };
+
         // Xử lý phím đặc biệt
+        // (phím chọn 1-9 và điều hướng do Engine xử lý qua select_candidate)
         match key {
             // Space hoặc Enter: commit candidate đã chọn
             ' ' | '\n' => {
-                if let Some(candidate) = self.candidates.get(self.selected_index) {
-                    let nom_char = candidate.character;
-                    let backspace_count = self.syllable_buffer.len();
-
-                    self.syllable_buffer.clear();
-                    self.candidates.clear();
-                    buffer.clear();
-
-                    return Action::Replace {
-                        backspace_count,
-                        text: nom_char.to_string(),
-                    };
+                if let Some(candidate) = self.candidates.selected() {
+                    let text = candidate.text.clone();
+                    return self.commit(text, buffer);
                 }
 
-                // Không có candidate, commit buffer thường
+                // Không có candidate, commit phiên âm thường
                 if !self.syllable_buffer.is_empty() {
                     let text = self.syllable_buffer.clone();
-                    self.syllable_buffer.clear();
-                    self.candidates.clear();
-                    buffer.clear();
-
-                    return Action::Commit(text);
+                    return self.commit(text, buffer);
                 }
 
-                Action::DoNothing
-            }
-
-            // Số 1-9: chọn candidate
-            '1'..='9' => {
-                let index = (key as usize) - ('1' as usize);
-                if let Some(candidate) = self.candidates.get(index) {
-                    let nom_char = candidate.character;
-                    let backspace_count = self.syllable_buffer.len();
-
-                    self.syllable_buffer.clear();
-                    self.candidates.clear();
-                    buffer.clear();
-
-                    return Action::Replace {
-                        backspace_count,
-                        text: nom_char.to_string(),
-                    };
-                }
-                Action::DoNothing
+                Action::PassThrough
             }
 
             // Ký tự Latin: thêm vào syllable buffer
@@ -130,15 +123,11 @@ impl InputMethodTrait for TelexNomMethod {
                 self.syllable_buffer.push(c.to_ascii_lowercase());
                 buffer.push(c, c.is_lowercase());
                 self.update_candidates();
-
-                Action::Replace {
-                    backspace_count: 0,
-                    text: c.to_string(),
-                }
+                self.show_candidates()
             }
 
             // Các ký tự khác: passthrough
-            _ => Action::DoNothing,
+            _ => Action::PassThrough,
         }
     }
 
@@ -146,19 +135,15 @@ impl InputMethodTrait for TelexNomMethod {
         if self.syllable_buffer.pop().is_some() {
             buffer.pop();
             self.update_candidates();
-            Action::Replace {
-                backspace_count: 1,
-                text: String::new(),
-            }
+            self.show_candidates()
         } else {
-            Action::DoNothing
+            Action::PassThrough
         }
     }
 
     fn reset(&mut self) {
         self.syllable_buffer.clear();
-        self.candidates.clear();
-        self.selected_index = 0;
+        self.candidates = CandidateList::default();
     }
 
     fn can_undo(&self, _buffer: &InputBuffer) -> bool {
@@ -168,6 +153,24 @@ impl InputMethodTrait for TelexNomMethod {
     fn undo(&mut self, _buffer: &mut InputBuffer) -> Action {
         Action::DoNothing
     }
+
+    fn candidates(&self) -> Option<&CandidateList> {
+        (!self.syllable_buffer.is_empty()).then_some(&self.candidates)
+    }
+
+    fn candidates_mut(&mut self) -> Option<&mut CandidateList> {
+        (!self.syllable_buffer.is_empty()).then_some(&mut self.candidates)
+    }
+
+    fn select_candidate(&mut self, index: usize, buffer: &mut InputBuffer) -> Action {
+        match self.candidates.get(index) {
+            Some(candidate) => {
+                let text = candidate.text.clone();
+                self.commit(text, buffer)
+            }
+            None => Action::DoNothing,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -189,6 +192,30 @@ mod tests {
         method.syllable_buffer = "nguoi".to_string();
         method.update_candidates();
 
-        assert!(!method.candidates().is_empty());
+        assert!(method.candidates().is_some_and(|list| !list.is_empty()));
+    }
+
+    #[test]
+    fn test_select_candidate() {
+        let mut method = TelexNomMethod::new();
+        let mut buffer = InputBuffer::new();
+        let lookup = crate::lookup::NomLookup::new();
+
+        for c in "nguoi".chars() {
+            let action = method.process(&KeyEvent::from_char(c), &mut buffer, &lookup);
+            assert!(matches!(action, Action::ShowCandidates(_)));
+        }
+
+        let list = method.candidates().unwrap();
+        assert_eq!(list.aux_text, "nguoi");
+        let expected = list.get(0).unwrap().text.clone();
+        assert_eq!(list.get(0).unwrap().annotation.as_deref(), Some("người"));
+
+        assert_eq!(
+            method.select_candidate(0, &mut buffer),
+            Action::Commit(expected)
+        );
+        assert!(method.candidates().is_none());
+        assert!(buffer.is_empty());
     }
 }
@@ -4,6 +4,7 @@
 
 use crate::lookup::NomLookup;
 use crate::methods::telex_nom::TelexNomMethod;
+use vikey_core::metadata::{Capabilities, MethodMetadata, PluginMetadata};
 use vikey_core::traits::{InputMethodTrait, LanguagePlugin, LanguageRules, LookupProvider};
 
 /// Chữ Nôm Language Plugin
@@ -52,6 +53,30 @@ impl LanguagePlugin for NomPlugin {
         }
     }
 
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata::new(self.id(), self.name())
+            .with_language_tag("vi-Hani")
+            .with_script("Hani")
+            .with_version(env!("CARGO_PKG_VERSION"))
+            .with_display_name("vi", "Chữ Nôm")
+            .with_display_name("en", "Chữ Nôm (Han-Nom)")
+            .with_icon("nom")
+            .with_order(10)
+            .with_method(
+                MethodMetadata::new("telex-nom", "Telex-Nôm")
+                    .with_description("Gõ âm đọc bằng Telex rồi chọn chữ Nôm")
+                    .with_display_name("vi", "Ký âm (Telex)")
+                    .with_capabilities(Capabilities::CANDIDATES)
+                    .with_priority(10),
+            )
+            .with_method(
+                MethodMetadata::new("pinyin-nom", "Pinyin-Nôm")
+                    .with_description("Gõ âm Hán Việt bằng Pinyin rồi chọn chữ Nôm")
+                    .with_capabilities(Capabilities::CANDIDATES)
+                    .experimental(),
+            )
+    }
+
     fn lookup(&self) -> &dyn LookupProvider {
         &self.lookup
     }
@@ -114,4 +139,17 @@ mod tests {
         let methods = plugin.input_methods();
         assert!(methods.contains(&"telex-nom"));
     }
+
+    #[test]
+    fn test_metadata() {
+        let plugin = NomPlugin::new();
+        let metadata = plugin.metadata();
+        assert_eq!(metadata.script, "Hani");
+        assert_eq!(metadata.display_name("vi"), "Chữ Nôm");
+        assert_eq!(
+            metadata.default_method().map(|m| m.id.as_str()),
+            Some("telex-nom")
+        );
+        assert!(metadata.method("pinyin-nom").unwrap().experimental);
+    }
 }
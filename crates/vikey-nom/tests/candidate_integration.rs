@@ -0,0 +1,126 @@
+// Integration tests for the candidate-list API driven through Engine
+
+use vikey_core::{Action, CandidateConfig, Engine, KeyCode, KeyEvent};
+use vikey_nom::NomPlugin;
+
+fn setup_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(NomPlugin::new())).unwrap();
+    engine.set_language("nom").unwrap();
+    engine.set_input_method("telex-nom").unwrap();
+    engine
+}
+
+fn type_string(engine: &mut Engine, input: &str) -> Action {
+    let mut last = Action::DoNothing;
+    for c in input.chars() {
+        last = engine.process(c);
+    }
+    last
+}
+
+#[test]
+fn test_typing_shows_candidates() {
+    let mut engine = setup_engine();
+
+    match type_string(&mut engine, "nguoi") {
+        Action::ShowCandidates(list) => {
+            assert_eq!(list.aux_text, "nguoi");
+            assert_eq!(list.get(0).unwrap().text, "𡦂");
+            assert_eq!(list.get(0).unwrap().annotation.as_deref(), Some("người"));
+        }
+        other => panic!("expected ShowCandidates, got {:?}", other),
+    }
+    assert!(engine.candidates().is_some());
+}
+
+#[test]
+fn test_selection_key_commits() {
+    let mut engine = setup_engine();
+    type_string(&mut engine, "viet");
+
+    assert_eq!(engine.process('1'), Action::Commit("越".to_string()));
+    assert!(engine.candidates().is_none());
+    assert!(engine.buffer_content().is_empty());
+}
+
+#[test]
+fn test_space_commits_selected() {
+    let mut engine = setup_engine();
+    type_string(&mut engine, "nom");
+
+    assert_eq!(engine.process(' '), Action::Commit("喃".to_string()));
+}
+
+#[test]
+fn test_custom_selection_keys() {
+    let mut engine = setup_engine();
+    engine.set_candidate_config(CandidateConfig {
+        selection_keys: vec!['a', 's', 'd'],
+        page_size: 3,
+    });
+
+    match type_string(&mut engine, "nom") {
+        Action::ShowCandidates(list) => {
+            assert_eq!(list.page_size(), 3);
+            assert_eq!(list.selection_keys(), &['a', 's', 'd']);
+        }
+        other => panic!("expected ShowCandidates, got {:?}", other),
+    }
+
+    // '1' không còn là phím chọn
+    assert_eq!(engine.process('1'), Action::PassThrough);
+    assert_eq!(engine.process('a'), Action::Commit("喃".to_string()));
+}
+
+#[test]
+fn test_navigation_keys_stay_in_window() {
+    let mut engine = setup_engine();
+    type_string(&mut engine, "nguoi");
+
+    // Chỉ có một candidate: điều hướng không đổi gì nhưng vẫn bị nuốt
+    assert_eq!(
+        engine.process(KeyEvent::new(KeyCode::Down, None)),
+        Action::DoNothing
+    );
+    assert_eq!(
+        engine.process(KeyEvent::new(KeyCode::PageDown, None)),
+        Action::DoNothing
+    );
+    assert!(engine.candidates().is_some());
+}
+
+#[test]
+fn test_escape_hides_candidates() {
+    let mut engine = setup_engine();
+    type_string(&mut engine, "viet");
+
+    assert_eq!(
+        engine.process(KeyEvent::new(KeyCode::Escape, None)),
+        Action::HideCandidates
+    );
+    assert!(engine.candidates().is_none());
+    assert!(engine.buffer_content().is_empty());
+}
+
+#[test]
+fn test_backspace_to_empty_hides_candidates() {
+    let mut engine = setup_engine();
+    type_string(&mut engine, "vi");
+
+    assert!(matches!(
+        engine.process_backspace(),
+        Action::ShowCandidates(_)
+    ));
+    assert_eq!(engine.process_backspace(), Action::HideCandidates);
+    assert_eq!(engine.process_backspace(), Action::PassThrough);
+}
+
+#[test]
+fn test_select_candidate_out_of_range() {
+    let mut engine = setup_engine();
+    type_string(&mut engine, "viet");
+
+    assert_eq!(engine.select_candidate(5), Action::DoNothing);
+    assert_eq!(engine.select_candidate(0), Action::Commit("越".to_string()));
+}
@@ -0,0 +1,121 @@
+// auto_restore.rs - Restore non-Vietnamese words to the keys typed
+
+use crate::validation::is_valid_word;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+/// What to do with words that are not valid Vietnamese
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoRestoreMode {
+    /// Never restore: keep whatever the transformations produced
+    Off,
+
+    /// Keep composing while typing; when a separator arrives and the word
+    /// is not a valid Vietnamese syllable, replace it with the raw keys
+    #[default]
+    RestoreOnBoundary,
+
+    /// Like `RestoreOnBoundary`, but as soon as a key makes the word
+    /// impossible in Vietnamese, revert it to the raw keys immediately and
+    /// stop transforming the rest of the word
+    StrictReject,
+}
+
+/// Auto-restore policy shared by the Vietnamese input methods
+///
+/// Clones share one kept-word set, so a word kept through any clone (e.g.
+/// the one given to `VietnamesePlugin::with_auto_restore`) is honoured by
+/// every input method already built from it.
+#[derive(Debug, Clone, Default)]
+pub struct AutoRestore {
+    /// Restore mode
+    pub mode: AutoRestoreMode,
+
+    /// Words the user meant as typed (lowercase), never restored
+    kept_words: Arc<RwLock<HashSet<String>>>,
+}
+
+impl AutoRestore {
+    /// Create a policy with the given mode
+    pub fn new(mode: AutoRestoreMode) -> Self {
+        Self {
+            mode,
+            kept_words: Arc::default(),
+        }
+    }
+
+    /// Mark a composed word as intended ("I meant it"), e.g. "đc"
+    pub fn keep_word(&self, word: &str) {
+        self.kept_words.write().unwrap().insert(word.to_lowercase());
+    }
+
+    /// Forget a word previously marked with `keep_word`
+    pub fn forget_word(&self, word: &str) {
+        self.kept_words
+            .write()
+            .unwrap()
+            .remove(&word.to_lowercase());
+    }
+
+    /// Is this word marked as intended?
+    pub fn is_kept(&self, word: &str) -> bool {
+        self.kept_words
+            .read()
+            .unwrap()
+            .contains(&word.to_lowercase())
+    }
+
+    /// Should `composed` be replaced by `raw` when the word ends?
+    pub fn should_restore(&self, composed: &str, raw: &str) -> bool {
+        self.mode != AutoRestoreMode::Off
+            && composed != raw
+            && !self.is_kept(composed)
+            && !is_valid_word(composed)
+    }
+
+    /// Should the whole word revert to raw keys as soon as it becomes impossible?
+    pub fn is_strict(&self) -> bool {
+        self.mode == AutoRestoreMode::StrictReject
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_restore() {
+        let policy = AutoRestore::default();
+        assert!(policy.should_restore("wíndơ", "windows"));
+        assert!(!policy.should_restore("tiếng", "tieesng"));
+        // Nothing was transformed
+        assert!(!policy.should_restore("hello", "hello"));
+    }
+
+    #[test]
+    fn test_off() {
+        let policy = AutoRestore::new(AutoRestoreMode::Off);
+        assert!(!policy.should_restore("đc", "ddc"));
+    }
+
+    #[test]
+    fn test_kept_words() {
+        let policy = AutoRestore::default();
+        assert!(policy.should_restore("đc", "ddc"));
+
+        policy.keep_word("Đc");
+        assert!(!policy.should_restore("đc", "ddc"));
+
+        policy.forget_word("đc");
+        assert!(policy.should_restore("đc", "ddc"));
+    }
+
+    #[test]
+    fn test_clones_share_kept_words() {
+        let policy = AutoRestore::default();
+        let clone = policy.clone();
+
+        policy.keep_word("đc");
+        assert!(!clone.should_restore("đc", "ddc"));
+    }
+}
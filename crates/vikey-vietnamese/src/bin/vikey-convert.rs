@@ -0,0 +1,176 @@
+//! Vikey Convert - convert Vietnamese text between charsets
+//!
+//! ```text
+//! vikey-convert --from <charset|auto> --to <charset> [--format <format>] [-o <output>] [<file>...]
+//! vikey-convert --detect [<file>...]
+//! vikey-convert --list
+//! ```
+//!
+//! Reads the files in order (stdin if there are none, or for "-") and
+//! writes the converted text to the output file (stdout by default). With
+//! `--from auto` the source charset is detected. HTML, RTF, CSV and TSV
+//! documents (`--format`, or the extension of the first file) keep their
+//! markup; only their text is converted.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use vikey_vietnamese::detect::detect;
+use vikey_vietnamese::document::{convert_document, text_bytes, Format};
+use vikey_vietnamese::Charset;
+
+const USAGE: &str =
+    "usage: vikey-convert --from <charset|auto> --to <charset> [--format <format>] [-o <output>] [<file>...]
+       vikey-convert --detect [<file>...]
+       vikey-convert --list
+
+formats: text, html, rtf, csv, tsv";
+
+/// Parsed command line
+struct Options {
+    /// Source charset, `None` to detect it
+    from: Option<Charset>,
+    /// Target charset, `None` to only list the detected charsets
+    to: Option<Charset>,
+    /// Document format, `None` to use the file extension
+    format: Option<Format>,
+    output: Option<String>,
+    inputs: Vec<String>,
+}
+
+fn parse_charset(id: Option<&String>) -> Result<Charset, String> {
+    let id = id.ok_or(USAGE)?;
+    Charset::from_id(&id.to_ascii_lowercase()).ok_or_else(|| {
+        format!(
+            "unknown charset '{}' (see vikey-convert --list)\n{}",
+            id, USAGE
+        )
+    })
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let (mut from, mut to, mut output) = (None, None, None);
+    let mut detect_only = false;
+    let mut format = None;
+    let mut inputs = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-f" | "--from" => match args.next() {
+                Some(id) if id.eq_ignore_ascii_case("auto") => from = Some(None),
+                id => from = Some(Some(parse_charset(id)?)),
+            },
+            "-t" | "--to" => to = Some(parse_charset(args.next())?),
+            "-o" | "--output" => output = Some(args.next().ok_or(USAGE)?.clone()),
+            "--format" => {
+                let id = args.next().ok_or(USAGE)?;
+                let parsed = Format::from_id(id);
+                format = Some(parsed.ok_or_else(|| format!("unknown format '{}'\n{}", id, USAGE))?);
+            }
+            "--detect" => detect_only = true,
+            "-" => inputs.push(arg.clone()),
+            flag if flag.starts_with('-') => {
+                return Err(format!("unknown option '{}'\n{}", flag, USAGE))
+            }
+            _ => inputs.push(arg.clone()),
+        }
+    }
+    match (from, to) {
+        (Some(from), Some(to)) if !detect_only => Ok(Options {
+            from,
+            to: Some(to),
+            format,
+            output,
+            inputs,
+        }),
+        (None, None) if detect_only && output.is_none() => Ok(Options {
+            from: None,
+            to: None,
+            format,
+            output,
+            inputs,
+        }),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn read_input(path: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    if path == "-" {
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("stdin: {}", e))?;
+    } else {
+        bytes = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    Ok(bytes)
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    if let [flag] = args {
+        if flag == "--list" {
+            for charset in Charset::ALL {
+                println!("{:<12} {}", charset.id(), charset.name());
+            }
+            return Ok(());
+        }
+    }
+    let mut options = parse_args(args)?;
+    if options.inputs.is_empty() {
+        options.inputs.push("-".to_string());
+    }
+
+    let mut input = Vec::new();
+    for path in &options.inputs {
+        input.extend(read_input(path)?);
+    }
+    let format = options.format.unwrap_or_else(|| {
+        let path = Path::new(&options.inputs[0]);
+        Format::from_path(path).unwrap_or_default()
+    });
+    let Some(to) = options.to else {
+        for guess in detect(&text_bytes(format, &input)) {
+            println!("{}", guess);
+        }
+        return Ok(());
+    };
+    let encoded = match options.from {
+        Some(from) => convert_document(format, &input, from, to),
+        None => {
+            let guess = detect(&text_bytes(format, &input))[0];
+            eprintln!("detected: {}", guess);
+            convert_document(format, &guess.source_bytes(&input), guess.charset, to)
+        }
+    };
+
+    if !encoded.unmapped.is_empty() {
+        let mut chars = encoded.unmapped.clone();
+        chars.sort_unstable();
+        chars.dedup();
+        eprintln!(
+            "warning: {} characters cannot be written in {}, replaced with '?': {}",
+            encoded.unmapped.len(),
+            to,
+            chars.into_iter().collect::<String>()
+        );
+    }
+
+    match &options.output {
+        Some(path) => fs::write(path, &encoded.bytes).map_err(|e| format!("{}: {}", path, e)),
+        None => io::stdout()
+            .write_all(&encoded.bytes)
+            .map_err(|e| format!("stdout: {}", e)),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,513 @@
+//! Vietnamese charsets (bảng mã)
+//!
+//! Besides Unicode, Vietnamese text is still written in the legacy
+//! charsets used by old fonts (.VnTime for TCVN3, VNI-Times for VNI
+//! Windows...), in the Windows code page 1258, in VIQR (plain ASCII) and as
+//! HTML character references. `Charset` encodes Unicode text into any of
+//! them; `convert` reads them back.
+//!
+//! Legacy charsets are byte encodings. `Charset::bytes` gives the bytes of
+//! a letter; `Charset::encode` returns them as a `String`, one char per
+//! byte, decoded the way a Windows ANSI application sees them
+//! (Windows-1252, or 1258 for CP1258): that is the text a legacy font
+//! expects.
+
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use unicode_normalization::char::decompose_canonical;
+use unicode_normalization::UnicodeNormalization;
+
+/// A charset Vietnamese text can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Charset {
+    /// Unicode, precomposed (NFC)
+    #[default]
+    Unicode,
+    /// Unicode, decomposed (NFD, "Unicode tổ hợp")
+    UnicodeNfd,
+    /// TCVN 5712:1993 (TCVN3, "ABC"), one byte per letter
+    Tcvn3,
+    /// VNI Windows: base letter plus a mark byte
+    VniWindows,
+    /// VISCII (RFC 1456), one byte per letter
+    Viscii,
+    /// Windows code page 1258: some precomposed letters, tone marks as
+    /// combining bytes
+    Cp1258,
+    /// VIQR (RFC 1456): ASCII letters followed by mark characters ("Vie^.t")
+    Viqr,
+    /// HTML numeric character references, decimal ("&#7879;")
+    NcrDecimal,
+    /// HTML numeric character references, hexadecimal ("&#x1EC7;")
+    NcrHex,
+}
+
+impl Charset {
+    /// All charsets, in menu order
+    pub const ALL: [Charset; 9] = [
+        Charset::Unicode,
+        Charset::UnicodeNfd,
+        Charset::Tcvn3,
+        Charset::VniWindows,
+        Charset::Viscii,
+        Charset::Cp1258,
+        Charset::Viqr,
+        Charset::NcrDecimal,
+        Charset::NcrHex,
+    ];
+
+    /// Stable ID, used in config files and on the command line (e.g. "tcvn3")
+    pub fn id(self) -> &'static str {
+        match self {
+            Charset::Unicode => "unicode",
+            Charset::UnicodeNfd => "unicode-nfd",
+            Charset::Tcvn3 => "tcvn3",
+            Charset::VniWindows => "vni-windows",
+            Charset::Viscii => "viscii",
+            Charset::Cp1258 => "cp1258",
+            Charset::Viqr => "viqr",
+            Charset::NcrDecimal => "ncr-decimal",
+            Charset::NcrHex => "ncr-hex",
+        }
+    }
+
+    /// Display name (e.g. "TCVN3 (ABC)")
+    pub fn name(self) -> &'static str {
+        match self {
+            Charset::Unicode => "Unicode",
+            Charset::UnicodeNfd => "Unicode NFD",
+            Charset::Tcvn3 => "TCVN3 (ABC)",
+            Charset::VniWindows => "VNI Windows",
+            Charset::Viscii => "VISCII",
+            Charset::Cp1258 => "Windows CP 1258",
+            Charset::Viqr => "VIQR",
+            Charset::NcrDecimal => "NCR Decimal",
+            Charset::NcrHex => "NCR Hex",
+        }
+    }
+
+    /// Charset with the given ID
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|charset| charset.id() == id)
+    }
+
+    /// Is this a byte encoding (files hold raw bytes rather than UTF-8)?
+    pub fn is_byte_encoding(self) -> bool {
+        matches!(
+            self,
+            Charset::Tcvn3 | Charset::VniWindows | Charset::Viscii | Charset::Cp1258
+        )
+    }
+
+    /// Bytes of `c` in a byte encoding (`None` for other charsets, or if
+    /// the charset cannot represent `c`)
+    pub fn bytes(self, c: char) -> Option<Cow<'static, [u8]>> {
+        if !self.is_byte_encoding() {
+            return None;
+        }
+        if c.is_ascii() {
+            return Some(Cow::Borrowed(std::slice::from_ref(&ASCII[c as usize])));
+        }
+        let table = match self {
+            Charset::Tcvn3 => &*TCVN3_MAP,
+            Charset::VniWindows => &*VNI_WINDOWS_MAP,
+            Charset::Viscii => &*VISCII_MAP,
+            _ => return cp1258_bytes(c).map(Cow::Owned),
+        };
+        table.get(&c).map(|bytes| Cow::Borrowed(*bytes))
+    }
+
+    /// Character a Windows ANSI application sees for a byte of this
+    /// charset
+    pub fn byte_char(self, byte: u8) -> char {
+        match (self, byte) {
+            (Charset::Cp1258, 0x80..=0xFF) => CP1258_HIGH[usize::from(byte - 0x80)],
+            (_, 0x80..=0x9F) => WINDOWS_1252_HIGH[usize::from(byte - 0x80)],
+            _ => char::from(byte),
+        }
+    }
+
+    /// Byte shown as `c` by a Windows ANSI application (reverse of
+    /// `byte_char`)
+    pub fn char_byte(self, c: char) -> Option<u8> {
+        let high: &[char] = match self {
+            Charset::Cp1258 => &CP1258_HIGH,
+            _ if ('\u{A0}'..='\u{FF}').contains(&c) => return u8::try_from(c).ok(),
+            _ => &WINDOWS_1252_HIGH,
+        };
+        match u8::try_from(c) {
+            Ok(byte) if byte < 0x80 => Some(byte),
+            _ => high
+                .iter()
+                .position(|&h| h == c)
+                .and_then(|i| u8::try_from(0x80 + i).ok()),
+        }
+    }
+
+    /// Append `c` encoded in this charset to `out`
+    ///
+    /// Characters the charset cannot represent (and ASCII) are kept as is.
+    pub fn encode_char(self, c: char, out: &mut String) {
+        if c.is_ascii() {
+            out.push(c);
+            return;
+        }
+        match self {
+            Charset::Unicode => out.push(c),
+            Charset::UnicodeNfd => decompose_canonical(c, |part| out.push(part)),
+            Charset::Tcvn3 | Charset::VniWindows | Charset::Viscii | Charset::Cp1258 => {
+                match self.bytes(c) {
+                    Some(bytes) => out.extend(bytes.iter().map(|&b| self.byte_char(b))),
+                    None => out.push(c),
+                }
+            }
+            Charset::Viqr => {
+                if !push_viqr(c, out) {
+                    out.push(c);
+                }
+            }
+            Charset::NcrDecimal => out.push_str(&format!("&#{};", c as u32)),
+            Charset::NcrHex => out.push_str(&format!("&#x{:X};", c as u32)),
+        }
+    }
+
+    /// Encode `text` (in any normalization form) in this charset
+    pub fn encode(self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut previous = None;
+        for c in text.nfc() {
+            // In VIQR, a mark right after a vowel would be read as its tone
+            if self == Charset::Viqr && VIQR_MARKS.contains(&c) && previous.is_some_and(is_vowel) {
+                out.push('\\');
+            }
+            self.encode_char(c, &mut out);
+            previous = Some(c);
+        }
+        out
+    }
+
+    /// Length in chars of `text` once encoded, i.e. the number of
+    /// backspaces that delete it
+    pub fn encoded_len(self, text: &str) -> usize {
+        match self {
+            Charset::Unicode => text.chars().count(),
+            _ => self.encode(text).chars().count(),
+        }
+    }
+}
+
+impl fmt::Display for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Combining tone marks: huyền, sắc, ngã, hỏi, nặng
+const TONE_MARKS: [char; 5] = ['\u{0300}', '\u{0301}', '\u{0303}', '\u{0309}', '\u{0323}'];
+
+/// Mark characters of VIQR, which a backslash writes literally ("\\.")
+pub(crate) const VIQR_MARKS: &[char] = &['^', '(', '+', '\'', '`', '?', '~', '.'];
+
+/// Is `c` a vowel, with or without marks?
+fn is_vowel(c: char) -> bool {
+    let mut vowel = false;
+    decompose_canonical(c, |part| vowel |= "aeiouyAEIOUY".contains(part));
+    vowel
+}
+
+/// Bytes 0x00..=0x7F, so ASCII can be borrowed like table entries
+static ASCII: [u8; 128] = {
+    let mut bytes = [0; 128];
+    let mut i = 0;
+    while i < 128 {
+        bytes[i] = i as u8;
+        i += 1;
+    }
+    bytes
+};
+
+/// Windows-1252 characters for bytes 0x80..=0x9F (unassigned bytes map to
+/// the C1 control of the same value)
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Windows-1258 characters for bytes 0x80..=0xFF
+#[rustfmt::skip]
+const CP1258_HIGH: [char; 128] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{008A}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{008E}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{009A}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{009E}', '\u{0178}',
+    '\u{00A0}', '\u{00A1}', '\u{00A2}', '\u{00A3}', '\u{00A4}', '\u{00A5}', '\u{00A6}', '\u{00A7}',
+    '\u{00A8}', '\u{00A9}', '\u{00AA}', '\u{00AB}', '\u{00AC}', '\u{00AD}', '\u{00AE}', '\u{00AF}',
+    '\u{00B0}', '\u{00B1}', '\u{00B2}', '\u{00B3}', '\u{00B4}', '\u{00B5}', '\u{00B6}', '\u{00B7}',
+    '\u{00B8}', '\u{00B9}', '\u{00BA}', '\u{00BB}', '\u{00BC}', '\u{00BD}', '\u{00BE}', '\u{00BF}',
+    '\u{00C0}', '\u{00C1}', '\u{00C2}', '\u{0102}', '\u{00C4}', '\u{00C5}', '\u{00C6}', '\u{00C7}',
+    '\u{00C8}', '\u{00C9}', '\u{00CA}', '\u{00CB}', '\u{0300}', '\u{00CD}', '\u{00CE}', '\u{00CF}',
+    '\u{0110}', '\u{00D1}', '\u{0309}', '\u{00D3}', '\u{00D4}', '\u{01A0}', '\u{00D6}', '\u{00D7}',
+    '\u{00D8}', '\u{00D9}', '\u{00DA}', '\u{00DB}', '\u{00DC}', '\u{01AF}', '\u{0303}', '\u{00DF}',
+    '\u{00E0}', '\u{00E1}', '\u{00E2}', '\u{0103}', '\u{00E4}', '\u{00E5}', '\u{00E6}', '\u{00E7}',
+    '\u{00E8}', '\u{00E9}', '\u{00EA}', '\u{00EB}', '\u{0301}', '\u{00ED}', '\u{00EE}', '\u{00EF}',
+    '\u{0111}', '\u{00F1}', '\u{0323}', '\u{00F3}', '\u{00F4}', '\u{01A1}', '\u{00F6}', '\u{00F7}',
+    '\u{00F8}', '\u{00F9}', '\u{00FA}', '\u{00FB}', '\u{00FC}', '\u{01B0}', '\u{20AB}', '\u{00FF}',
+];
+
+/// CP1258 bytes of `c`: its own byte if the code page has one, otherwise
+/// the letter without its tone followed by the combining tone ("ệ" → "ê"
+/// + dot below)
+fn cp1258_bytes(c: char) -> Option<Vec<u8>> {
+    if let Some(byte) = Charset::Cp1258.char_byte(c) {
+        return Some(vec![byte]);
+    }
+    let mut tone = None;
+    let mut rest = String::new();
+    decompose_canonical(c, |part| match part {
+        part if TONE_MARKS.contains(&part) => tone = Some(part),
+        part => rest.push(part),
+    });
+    let mut base = rest.nfc();
+    let (Some(letter), None) = (base.next(), base.next()) else {
+        return None;
+    };
+    Some(vec![
+        Charset::Cp1258.char_byte(letter)?,
+        Charset::Cp1258.char_byte(tone?)?,
+    ])
+}
+
+/// Append the VIQR spelling of a Vietnamese letter ("ệ" → "e^."), or
+/// return `false` if `c` is not one
+fn push_viqr(c: char, out: &mut String) -> bool {
+    match c {
+        'đ' => out.push_str("dd"),
+        'Đ' => out.push_str("DD"),
+        _ => {
+            let mut base = None;
+            let (mut modifier, mut tone) = (None, None);
+            let mut valid = true;
+            decompose_canonical(c, |part| match part {
+                '\u{0302}' => modifier = Some('^'),
+                '\u{0306}' => modifier = Some('('),
+                '\u{031B}' => modifier = Some('+'),
+                '\u{0301}' => tone = Some('\''),
+                '\u{0300}' => tone = Some('`'),
+                '\u{0309}' => tone = Some('?'),
+                '\u{0303}' => tone = Some('~'),
+                '\u{0323}' => tone = Some('.'),
+                part if base.is_none() && part.is_ascii_alphabetic() => base = Some(part),
+                _ => valid = false,
+            });
+            let Some(base) = base.filter(|_| valid && (modifier, tone) != (None, None)) else {
+                return false;
+            };
+            out.push(base);
+            out.extend(modifier);
+            out.extend(tone);
+        }
+    }
+    true
+}
+
+fn build(table: &'static [(char, u8)]) -> HashMap<char, &'static [u8]> {
+    table
+        .iter()
+        .map(|(c, byte)| (*c, std::slice::from_ref(byte)))
+        .collect()
+}
+
+static TCVN3_MAP: Lazy<HashMap<char, &'static [u8]>> = Lazy::new(|| build(TCVN3));
+static VISCII_MAP: Lazy<HashMap<char, &'static [u8]>> = Lazy::new(|| build(VISCII));
+static VNI_WINDOWS_MAP: Lazy<HashMap<char, &'static [u8]>> =
+    Lazy::new(|| VNI_WINDOWS.iter().copied().collect());
+
+/// TCVN 5712:1993. Capitals with tone marks use the VN1 positions (some of
+/// them C0 control bytes), as UniKey does.
+#[rustfmt::skip]
+pub(crate) const TCVN3: &[(char, u8)] = &[
+    ('á', 0xB8), ('à', 0xB5), ('ả', 0xB6), ('ã', 0xB7), ('ạ', 0xB9),
+    ('ă', 0xA8), ('ắ', 0xBE), ('ằ', 0xBB), ('ẳ', 0xBC), ('ẵ', 0xBD), ('ặ', 0xC6),
+    ('â', 0xA9), ('ấ', 0xCA), ('ầ', 0xC7), ('ẩ', 0xC8), ('ẫ', 0xC9), ('ậ', 0xCB),
+    ('é', 0xD0), ('è', 0xCC), ('ẻ', 0xCE), ('ẽ', 0xCF), ('ẹ', 0xD1),
+    ('ê', 0xAA), ('ế', 0xD5), ('ề', 0xD2), ('ể', 0xD3), ('ễ', 0xD4), ('ệ', 0xD6),
+    ('í', 0xDD), ('ì', 0xD7), ('ỉ', 0xD8), ('ĩ', 0xDC), ('ị', 0xDE),
+    ('ó', 0xE3), ('ò', 0xDF), ('ỏ', 0xE1), ('õ', 0xE2), ('ọ', 0xE4),
+    ('ô', 0xAB), ('ố', 0xE8), ('ồ', 0xE5), ('ổ', 0xE6), ('ỗ', 0xE7), ('ộ', 0xE9),
+    ('ơ', 0xAC), ('ớ', 0xED), ('ờ', 0xEA), ('ở', 0xEB), ('ỡ', 0xEC), ('ợ', 0xEE),
+    ('ú', 0xF3), ('ù', 0xEF), ('ủ', 0xF1), ('ũ', 0xF2), ('ụ', 0xF4),
+    ('ư', 0xAD), ('ứ', 0xF8), ('ừ', 0xF5), ('ử', 0xF6), ('ữ', 0xF7), ('ự', 0xF9),
+    ('ý', 0xFD), ('ỳ', 0xFA), ('ỷ', 0xFB), ('ỹ', 0xFC), ('ỵ', 0xFE),
+    ('đ', 0xAE),
+    ('Á', 0x83), ('À', 0x80), ('Ả', 0x81), ('Ã', 0x82), ('Ạ', 0x84),
+    ('Ă', 0xA1), ('Ắ', 0xC0), ('Ằ', 0xAF), ('Ẳ', 0xBA), ('Ẵ', 0xBF), ('Ặ', 0x85),
+    ('Â', 0xA2), ('Ấ', 0xC4), ('Ầ', 0xC1), ('Ẩ', 0xC2), ('Ẫ', 0xC3), ('Ậ', 0x86),
+    ('É', 0x8A), ('È', 0x87), ('Ẻ', 0x88), ('Ẽ', 0x89), ('Ẹ', 0x8B),
+    ('Ê', 0xA3), ('Ế', 0xDA), ('Ề', 0xC5), ('Ể', 0xCD), ('Ễ', 0xD9), ('Ệ', 0x8C),
+    ('Í', 0x90), ('Ì', 0x8D), ('Ỉ', 0x8E), ('Ĩ', 0x8F), ('Ị', 0x91),
+    ('Ó', 0x95), ('Ò', 0x92), ('Ỏ', 0x93), ('Õ', 0x94), ('Ọ', 0x96),
+    ('Ô', 0xA4), ('Ố', 0xFF), ('Ồ', 0xDB), ('Ổ', 0xE0), ('Ỗ', 0xF0), ('Ộ', 0x97),
+    ('Ơ', 0xA5), ('Ớ', 0x9B), ('Ờ', 0x98), ('Ở', 0x99), ('Ỡ', 0x9A), ('Ợ', 0x9C),
+    ('Ú', 0x01), ('Ù', 0x9D), ('Ủ', 0x9E), ('Ũ', 0x9F), ('Ụ', 0x02),
+    ('Ư', 0xA6), ('Ứ', 0x11), ('Ừ', 0x04), ('Ử', 0x05), ('Ữ', 0x06), ('Ự', 0x12),
+    ('Ý', 0x16), ('Ỳ', 0x13), ('Ỷ', 0x14), ('Ỹ', 0x15), ('Ỵ', 0x17),
+    ('Đ', 0xA7),
+];
+
+/// VISCII (RFC 1456)
+#[rustfmt::skip]
+pub(crate) const VISCII: &[(char, u8)] = &[
+    ('á', 0xE1), ('à', 0xE0), ('ả', 0xE4), ('ã', 0xE3), ('ạ', 0xD5),
+    ('ă', 0xE5), ('ắ', 0xA1), ('ằ', 0xA2), ('ẳ', 0xC6), ('ẵ', 0xC7), ('ặ', 0xA3),
+    ('â', 0xE2), ('ấ', 0xA4), ('ầ', 0xA5), ('ẩ', 0xA6), ('ẫ', 0xE7), ('ậ', 0xA7),
+    ('é', 0xE9), ('è', 0xE8), ('ẻ', 0xEB), ('ẽ', 0xA8), ('ẹ', 0xA9),
+    ('ê', 0xEA), ('ế', 0xAA), ('ề', 0xAB), ('ể', 0xAC), ('ễ', 0xAD), ('ệ', 0xAE),
+    ('í', 0xED), ('ì', 0xEC), ('ỉ', 0xEF), ('ĩ', 0xEE), ('ị', 0xB8),
+    ('ó', 0xF3), ('ò', 0xF2), ('ỏ', 0xF6), ('õ', 0xF5), ('ọ', 0xF7),
+    ('ô', 0xF4), ('ố', 0xAF), ('ồ', 0xB0), ('ổ', 0xB1), ('ỗ', 0xB2), ('ộ', 0xB5),
+    ('ơ', 0xBD), ('ớ', 0xBE), ('ờ', 0xB6), ('ở', 0xB7), ('ỡ', 0xDE), ('ợ', 0xFE),
+    ('ú', 0xFA), ('ù', 0xF9), ('ủ', 0xFC), ('ũ', 0xFB), ('ụ', 0xF8),
+    ('ư', 0xDF), ('ứ', 0xD1), ('ừ', 0xD7), ('ử', 0xD8), ('ữ', 0xE6), ('ự', 0xF1),
+    ('ý', 0xFD), ('ỳ', 0xCF), ('ỷ', 0xD6), ('ỹ', 0xDB), ('ỵ', 0xDC),
+    ('đ', 0xF0),
+    ('Á', 0xC1), ('À', 0xC0), ('Ả', 0xC4), ('Ã', 0xC3), ('Ạ', 0x80),
+    ('Ă', 0xC5), ('Ắ', 0x81), ('Ằ', 0x82), ('Ẳ', 0x02), ('Ẵ', 0x05), ('Ặ', 0x83),
+    ('Â', 0xC2), ('Ấ', 0x84), ('Ầ', 0x85), ('Ẩ', 0x86), ('Ẫ', 0x06), ('Ậ', 0x87),
+    ('É', 0xC9), ('È', 0xC8), ('Ẻ', 0xCB), ('Ẽ', 0x88), ('Ẹ', 0x89),
+    ('Ê', 0xCA), ('Ế', 0x8A), ('Ề', 0x8B), ('Ể', 0x8C), ('Ễ', 0x8D), ('Ệ', 0x8E),
+    ('Í', 0xCD), ('Ì', 0xCC), ('Ỉ', 0x9B), ('Ĩ', 0xCE), ('Ị', 0x98),
+    ('Ó', 0xD3), ('Ò', 0xD2), ('Ỏ', 0x99), ('Õ', 0xA0), ('Ọ', 0x9A),
+    ('Ô', 0xD4), ('Ố', 0x8F), ('Ồ', 0x90), ('Ổ', 0x91), ('Ỗ', 0x92), ('Ộ', 0x93),
+    ('Ơ', 0xB4), ('Ớ', 0x95), ('Ờ', 0x96), ('Ở', 0x97), ('Ỡ', 0xB3), ('Ợ', 0x94),
+    ('Ú', 0xDA), ('Ù', 0xD9), ('Ủ', 0x9C), ('Ũ', 0x9D), ('Ụ', 0x9E),
+    ('Ư', 0xBF), ('Ứ', 0xBA), ('Ừ', 0xBB), ('Ử', 0xBC), ('Ữ', 0xFF), ('Ự', 0xB9),
+    ('Ý', 0xDD), ('Ỳ', 0x9F), ('Ỷ', 0x14), ('Ỹ', 0x19), ('Ỵ', 0x1E),
+    ('Đ', 0xD0),
+];
+
+/// VNI Windows: the base letter (or ơ/ư/đ and some i/y forms on their own)
+/// followed by a byte for the vowel mark and tone
+#[rustfmt::skip]
+pub(crate) const VNI_WINDOWS: &[(char, &[u8])] = &[
+    ('á', b"a\xF9"), ('à', b"a\xF8"), ('ả', b"a\xFB"), ('ã', b"a\xF5"), ('ạ', b"a\xEF"),
+    ('ă', b"a\xEA"), ('ắ', b"a\xE9"), ('ằ', b"a\xE8"), ('ẳ', b"a\xFA"), ('ẵ', b"a\xFC"), ('ặ', b"a\xEB"),
+    ('â', b"a\xE2"), ('ấ', b"a\xE1"), ('ầ', b"a\xE0"), ('ẩ', b"a\xE5"), ('ẫ', b"a\xE3"), ('ậ', b"a\xE4"),
+    ('é', b"e\xF9"), ('è', b"e\xF8"), ('ẻ', b"e\xFB"), ('ẽ', b"e\xF5"), ('ẹ', b"e\xEF"),
+    ('ê', b"e\xE2"), ('ế', b"e\xE1"), ('ề', b"e\xE0"), ('ể', b"e\xE5"), ('ễ', b"e\xE3"), ('ệ', b"e\xE4"),
+    ('í', b"\xED"), ('ì', b"\xEC"), ('ỉ', b"\xE6"), ('ĩ', b"\xF3"), ('ị', b"\xF2"),
+    ('ó', b"o\xF9"), ('ò', b"o\xF8"), ('ỏ', b"o\xFB"), ('õ', b"o\xF5"), ('ọ', b"o\xEF"),
+    ('ô', b"o\xE2"), ('ố', b"o\xE1"), ('ồ', b"o\xE0"), ('ổ', b"o\xE5"), ('ỗ', b"o\xE3"), ('ộ', b"o\xE4"),
+    ('ơ', b"\xF4"), ('ớ', b"\xF4\xF9"), ('ờ', b"\xF4\xF8"), ('ở', b"\xF4\xFB"), ('ỡ', b"\xF4\xF5"), ('ợ', b"\xF4\xEF"),
+    ('ú', b"u\xF9"), ('ù', b"u\xF8"), ('ủ', b"u\xFB"), ('ũ', b"u\xF5"), ('ụ', b"u\xEF"),
+    ('ư', b"\xF6"), ('ứ', b"\xF6\xF9"), ('ừ', b"\xF6\xF8"), ('ử', b"\xF6\xFB"), ('ữ', b"\xF6\xF5"), ('ự', b"\xF6\xEF"),
+    ('ý', b"y\xF9"), ('ỳ', b"y\xF8"), ('ỷ', b"y\xFB"), ('ỹ', b"y\xF5"), ('ỵ', b"\xEE"),
+    ('đ', b"\xF1"),
+    ('Á', b"A\xD9"), ('À', b"A\xD8"), ('Ả', b"A\xDB"), ('Ã', b"A\xD5"), ('Ạ', b"A\xCF"),
+    ('Ă', b"A\xCA"), ('Ắ', b"A\xC9"), ('Ằ', b"A\xC8"), ('Ẳ', b"A\xDA"), ('Ẵ', b"A\xDC"), ('Ặ', b"A\xCB"),
+    ('Â', b"A\xC2"), ('Ấ', b"A\xC1"), ('Ầ', b"A\xC0"), ('Ẩ', b"A\xC5"), ('Ẫ', b"A\xC3"), ('Ậ', b"A\xC4"),
+    ('É', b"E\xD9"), ('È', b"E\xD8"), ('Ẻ', b"E\xDB"), ('Ẽ', b"E\xD5"), ('Ẹ', b"E\xCF"),
+    ('Ê', b"E\xC2"), ('Ế', b"E\xC1"), ('Ề', b"E\xC0"), ('Ể', b"E\xC5"), ('Ễ', b"E\xC3"), ('Ệ', b"E\xC4"),
+    ('Í', b"\xCD"), ('Ì', b"\xCC"), ('Ỉ', b"\xC6"), ('Ĩ', b"\xD3"), ('Ị', b"\xD2"),
+    ('Ó', b"O\xD9"), ('Ò', b"O\xD8"), ('Ỏ', b"O\xDB"), ('Õ', b"O\xD5"), ('Ọ', b"O\xCF"),
+    ('Ô', b"O\xC2"), ('Ố', b"O\xC1"), ('Ồ', b"O\xC0"), ('Ổ', b"O\xC5"), ('Ỗ', b"O\xC3"), ('Ộ', b"O\xC4"),
+    ('Ơ', b"\xD4"), ('Ớ', b"\xD4\xD9"), ('Ờ', b"\xD4\xD8"), ('Ở', b"\xD4\xDB"), ('Ỡ', b"\xD4\xD5"), ('Ợ', b"\xD4\xCF"),
+    ('Ú', b"U\xD9"), ('Ù', b"U\xD8"), ('Ủ', b"U\xDB"), ('Ũ', b"U\xD5"), ('Ụ', b"U\xCF"),
+    ('Ư', b"\xD6"), ('Ứ', b"\xD6\xD9"), ('Ừ', b"\xD6\xD8"), ('Ử', b"\xD6\xDB"), ('Ữ', b"\xD6\xD5"), ('Ự', b"\xD6\xCF"),
+    ('Ý', b"Y\xD9"), ('Ỳ', b"Y\xD8"), ('Ỷ', b"Y\xDB"), ('Ỹ', b"Y\xD5"), ('Ỵ', b"\xCE"),
+    ('Đ', b"\xD1"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_are_complete() {
+        for table in [TCVN3, VISCII] {
+            assert_eq!(table.len(), 134);
+            let bytes: std::collections::HashSet<u8> = table.iter().map(|(_, b)| *b).collect();
+            assert_eq!(bytes.len(), 134);
+        }
+        assert_eq!(VNI_WINDOWS.len(), 134);
+        let chars: std::collections::HashSet<char> = TCVN3.iter().map(|(c, _)| *c).collect();
+        assert!(VISCII.iter().all(|(c, _)| chars.contains(c)));
+        assert!(VNI_WINDOWS.iter().all(|(c, _)| chars.contains(c)));
+    }
+
+    #[test]
+    fn test_encode() {
+        let text = "Tiếng Việt được";
+        assert_eq!(Charset::Unicode.encode(text), text);
+        assert_eq!(
+            Charset::Tcvn3.encode(text),
+            "Ti\u{D5}ng Vi\u{D6}t \u{AE}\u{AD}\u{EE}c"
+        );
+        assert_eq!(Charset::VniWindows.encode(text), "Tieáng Vieät ñöôïc");
+        assert_eq!(
+            Charset::Viscii.encode(text),
+            "Ti\u{AA}ng Vi\u{AE}t \u{F0}\u{DF}\u{FE}c"
+        );
+        assert_eq!(Charset::Viqr.encode(text), "Tie^'ng Vie^.t ddu+o+.c");
+        assert_eq!(Charset::NcrDecimal.encode("Việt"), "Vi&#7879;t");
+        assert_eq!(Charset::NcrHex.encode("Việt"), "Vi&#x1EC7;t");
+        assert_eq!(Charset::UnicodeNfd.encode("Việt"), "Vie\u{0323}\u{0302}t");
+        assert_eq!(
+            Charset::Cp1258.encode(text),
+            "Ti\u{EA}\u{301}ng Vi\u{EA}\u{323}t \u{111}\u{1B0}\u{1A1}\u{323}c"
+        );
+
+        // Bytes 0x80..=0x9F come out as Windows-1252 characters
+        assert_eq!(Charset::Tcvn3.encode("Á"), "\u{0192}");
+        assert_eq!(Charset::Viqr.encode("Ặ ặ"), "A(. a(.");
+        assert_eq!(Charset::Tcvn3.encode("中"), "中");
+
+        // Marks after a vowel are escaped so VIQR reads them back literally
+        assert_eq!(Charset::Viqr.encode("Ai? Anh."), "Ai\\? Anh.");
+        // Input in any normalization form
+        assert_eq!(Charset::Tcvn3.encode("Vie\u{0302}\u{0323}t"), "Vi\u{D6}t");
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(
+            Charset::VniWindows.bytes('ệ').as_deref(),
+            Some(&b"e\xE4"[..])
+        );
+        assert_eq!(
+            Charset::Cp1258.bytes('ệ').as_deref(),
+            Some(&b"\xEA\xF2"[..])
+        );
+        assert_eq!(Charset::Cp1258.bytes('Ơ').as_deref(), Some(&b"\xD5"[..]));
+        assert_eq!(Charset::Cp1258.bytes('x').as_deref(), Some(&b"x"[..]));
+        assert_eq!(Charset::Cp1258.bytes('中'), None);
+        assert_eq!(Charset::Viqr.bytes('a'), None);
+
+        for charset in Charset::ALL.into_iter().filter(|c| c.is_byte_encoding()) {
+            for byte in 0..=255 {
+                assert_eq!(charset.char_byte(charset.byte_char(byte)), Some(byte));
+            }
+        }
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        assert_eq!(Charset::Unicode.encoded_len("việt"), 4);
+        assert_eq!(Charset::Tcvn3.encoded_len("việt"), 4);
+        assert_eq!(Charset::VniWindows.encoded_len("việt"), 5);
+        assert_eq!(Charset::VniWindows.encoded_len("đi"), 2);
+        assert_eq!(Charset::Viqr.encoded_len("việt"), 6);
+        assert_eq!(Charset::NcrDecimal.encoded_len("việt"), 10);
+    }
+
+    #[test]
+    fn test_ids() {
+        for charset in Charset::ALL {
+            assert_eq!(Charset::from_id(charset.id()), Some(charset));
+        }
+        assert_eq!(Charset::from_id("vps"), None);
+    }
+}
@@ -0,0 +1,1139 @@
+// compose.rs - Composition core shared by the Vietnamese input methods
+
+use crate::auto_restore::AutoRestore;
+use crate::rules::ToneStyle;
+use crate::syllable::{Modification, Syllable, Tone};
+use crate::validation::{is_valid_syllable, FINAL_CONSONANTS};
+use vikey_core::traits::InputMethodTrait;
+use vikey_core::traits::LookupProvider;
+use vikey_core::types::{Action, CompositionMode};
+use vikey_core::{InputBuffer, KeyEvent};
+
+/// What a key does besides typing itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRole<'a> {
+    /// Puts this tone on the syllable (Telex "s", VNI "1", VIQR "'")
+    Tone(Tone),
+    /// Takes the tone off (Telex "z", VNI "0")
+    RemoveTone,
+    /// Puts one of these marks on a letter: the first that gives a possible
+    /// syllable (Telex "w" is a horn on "u" and "o", a breve on "a" in "oa")
+    ///
+    /// A key that is itself a letter taking the mark only marks that
+    /// letter: Telex "a" is a circumflex on "a", not on "e" or "o".
+    Mark(&'a [Modification]),
+    /// Types the next key as-is (VIQR "\")
+    Escape,
+}
+
+/// Where tone and mark keys may be typed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Placement {
+    /// Anywhere after the letter, even after the final consonant:
+    /// "vieetj", "vie65t" and "viet65" all give "việt"
+    #[default]
+    Free,
+    /// Right after the letter, as written in VIQR text ("vie^.t"); elsewhere
+    /// the key is a plain character, so VIQR tone keys stay punctuation
+    AfterLetter,
+}
+
+/// What typing a mark the letter already carries does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Repeat {
+    /// Takes the mark off and types the key ("a11" → "a1")
+    #[default]
+    TakesOff,
+    /// Takes the mark off when the key is repeated right away ("ass" → "as");
+    /// later the key is a plain character
+    TakesOffRightAway,
+    /// Keeps the mark and types the key as a plain character ("aaa" → "âa")
+    KeepsMark,
+}
+
+/// How the horn key marks "uo"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UoHorn {
+    /// Both letters take the horn at once ("tuo7" → "tươ")
+    #[default]
+    Together,
+    /// The "o" takes it first ("tuow" → "tuơ", as in "thuở"); the "u" takes
+    /// it too once letters follow ("tuowng" → "tương") or the word ends on
+    /// a final consonant
+    OnceTheWordGoesOn,
+}
+
+/// Key-role table of an input method
+///
+/// This is all that sets Telex, VNI and VIQR apart: [`Composer`] reads the
+/// keys of each method through its keymap.
+pub trait Keymap: Send + Sync {
+    /// Unique input method ID ("vni")
+    fn id(&self) -> &str;
+
+    /// Input method name (display)
+    fn name(&self) -> &str;
+
+    /// Role of `key`, `None` for keys that only type themselves
+    fn role(&self, key: char) -> Option<KeyRole<'_>>;
+
+    /// Where tone and mark keys may be typed
+    fn placement(&self) -> Placement {
+        Placement::Free
+    }
+
+    /// What typing a mark twice does
+    fn repeat(&self) -> Repeat {
+        Repeat::TakesOff
+    }
+
+    /// How the horn key marks "uo"
+    fn uo_horn(&self) -> UoHorn {
+        UoHorn::Together
+    }
+
+    /// Letter typed by `key` when it has nothing to mark ("]" → "ư"), if any
+    fn shortcut(&self, _key: char) -> Option<char> {
+        None
+    }
+}
+
+/// What a key did to the syllable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marked {
+    /// The mark was added (or replaced another one)
+    Applied,
+    /// The mark was already there and has been taken off: the key is typed
+    Escaped,
+    /// Nothing to put the mark on: the key is a plain character
+    NotApplicable,
+    /// The key typed the letter of its shortcut
+    Shortcut,
+}
+
+/// Check if character is a separator (space, enter, etc.)
+pub(crate) fn is_separator(ch: char) -> bool {
+    matches!(
+        ch,
+        ' ' | '\n'
+            | '\t'
+            | '.'
+            | ','
+            | '!'
+            | '?'
+            | ';'
+            | ':'
+            | '('
+            | ')'
+            | '['
+            | ']'
+            | '{'
+            | '}'
+            | '"'
+            | '\''
+    )
+}
+
+/// Letter without its modification (â → a, Ư → U, đ → d)
+pub(crate) fn base_letter(ch: char) -> char {
+    match ch {
+        'â' | 'ă' => 'a',
+        'Â' | 'Ă' => 'A',
+        'ê' => 'e',
+        'Ê' => 'E',
+        'ô' | 'ơ' => 'o',
+        'Ô' | 'Ơ' => 'O',
+        'ư' => 'u',
+        'Ư' => 'U',
+        'đ' => 'd',
+        'Đ' => 'D',
+        _ => ch,
+    }
+}
+
+/// Letter with `modification` instead of its own one (a → â, ô → ơ)
+fn modified(ch: char, modification: Modification) -> Option<char> {
+    let letter = match (modification, base_letter(ch).to_ascii_lowercase()) {
+        (Modification::Circumflex, 'a') => 'â',
+        (Modification::Circumflex, 'e') => 'ê',
+        (Modification::Circumflex, 'o') => 'ô',
+        (Modification::Breve, 'a') => 'ă',
+        (Modification::Horn, 'o') => 'ơ',
+        (Modification::Horn, 'u') => 'ư',
+        (Modification::DStroke, 'd') => 'đ',
+        _ => return None,
+    };
+    if ch.is_uppercase() {
+        letter.to_uppercase().next()
+    } else {
+        Some(letter)
+    }
+}
+
+/// Modification a written letter carries (ơ → horn)
+fn modification_of(ch: char) -> Option<Modification> {
+    [
+        Modification::Circumflex,
+        Modification::Breve,
+        Modification::Horn,
+        Modification::DStroke,
+    ]
+    .into_iter()
+    .find(|&modification| base_letter(ch) != ch && modified(ch, modification) == Some(ch))
+}
+
+/// Put `modification` on the letter of `word` at `target`, or take it off
+/// if the letter carries it already
+fn mark_letter(word: &mut String, target: Option<usize>, modification: Modification) -> Marked {
+    let Some(target) = target else {
+        return Marked::NotApplicable;
+    };
+    let mut chars: Vec<char> = word.chars().collect();
+    let letter = chars[target];
+    let escaped = modified(letter, modification) == Some(letter);
+    chars[target] = if escaped {
+        base_letter(letter)
+    } else {
+        modified(letter, modification).unwrap_or(letter)
+    };
+    *word = chars.into_iter().collect();
+    if escaped {
+        Marked::Escaped
+    } else {
+        Marked::Applied
+    }
+}
+
+/// Can the syllable still become Vietnamese? Besides permissible
+/// syllables, this allows a vowel waiting for the mark of its last
+/// letter: "uye" for "uyê", "ưo" for "ươ".
+fn is_possible(syllable: &Syllable) -> bool {
+    if syllable.is_permissible() {
+        return true;
+    }
+    let Some(last) = syllable.vowel.chars().last() else {
+        return false;
+    };
+    if !syllable.final_consonant.is_empty() {
+        return false;
+    }
+    [
+        Modification::Circumflex,
+        Modification::Breve,
+        Modification::Horn,
+    ]
+    .into_iter()
+    .filter_map(|modification| modified(last, modification))
+    .any(|marked| {
+        let mut marked_syllable = syllable.clone();
+        marked_syllable.vowel.pop();
+        marked_syllable.vowel.push(marked);
+        marked_syllable.is_permissible()
+    })
+}
+
+/// Is `vowel[i..]` "uo", whatever marks the letters carry?
+fn is_uo(vowel: &[char], i: usize) -> bool {
+    let is = |i: usize, letter: char| {
+        vowel
+            .get(i)
+            .is_some_and(|&c| base_letter(c).eq_ignore_ascii_case(&letter))
+    };
+    is(i, 'u') && is(i + 1, 'o')
+}
+
+/// "uơ" only ends a syllable (thuở, huơ): once letters follow the "ơ", the
+/// "u" takes its horn too ("tuơng" → "tương"). A final consonant that may
+/// still grow into a longer one ("n" → "ng") waits for the next key, or
+/// for the end of the word.
+fn settle_horn(syllable: &mut Syllable, word_end: bool) {
+    // After "q" the "u" belongs to the initial ("quơ")
+    let first = usize::from(syllable.initial.eq_ignore_ascii_case("q"));
+    let vowel: Vec<char> = syllable.vowel.chars().collect();
+    let Some(u) = (first..vowel.len().saturating_sub(1))
+        .find(|&i| matches!(vowel[i], 'u' | 'U') && matches!(vowel[i + 1], 'ơ' | 'Ơ'))
+    else {
+        return;
+    };
+
+    let final_consonant = syllable.final_consonant.to_lowercase();
+    let final_may_grow = FINAL_CONSONANTS.iter().any(|longer| {
+        longer.len() > final_consonant.len()
+            && longer.starts_with(&final_consonant)
+            && is_valid_syllable("", "ươ", longer)
+    });
+    let followed =
+        u + 2 < vowel.len() || (!final_consonant.is_empty() && (word_end || !final_may_grow));
+    if followed {
+        mark_letter(&mut syllable.vowel, Some(u), Modification::Horn);
+    }
+}
+
+/// History-based composition shared by the Vietnamese input methods
+///
+/// The composer keeps the keys typed for the current word and reads them
+/// again into a [`Syllable`] after every key, through the [`Keymap`] of the
+/// method. So marks may come in any order the keymap allows, backspace takes
+/// back a key rather than a letter, and a word that can no longer be
+/// Vietnamese is kept as typed (or restored, following [`AutoRestore`]).
+pub struct Composer<K> {
+    /// Key roles of the input method
+    keymap: K,
+
+    /// History of typed keys for the current word
+    typed_chars: Vec<char>,
+
+    /// Current calculated syllable state
+    syllable: Syllable,
+
+    /// Length of last output (for backspace_count)
+    last_output_len: usize,
+
+    /// Direct (backspace-and-retype) or preedit output
+    mode: CompositionMode,
+
+    /// Index in `typed_chars` from which keys are taken literally, once the
+    /// word can no longer be Vietnamese or a key was escaped
+    literal_from: Option<usize>,
+
+    /// A key was escaped (escape key or a mark typed twice): the word is
+    /// meant as shown
+    escaped: bool,
+
+    /// What to do with non-Vietnamese words
+    auto_restore: AutoRestore,
+
+    /// Where the tone mark goes in "oa", "oe", "uy"
+    tone_style: ToneStyle,
+
+    /// The user took back an auto-restore: keep this word as composed
+    keep_word: bool,
+
+    /// The last key made the word impossible and was kept as typed
+    rejected: bool,
+
+    /// Last word auto-restored on a boundary (composed keys), so that an
+    /// immediate backspace can take the restore back
+    last_restore: Option<Vec<char>>,
+}
+
+impl<K: Keymap + Default> Composer<K> {
+    /// Compose with the default key roles of `K`
+    pub fn new() -> Self {
+        Self::from_keymap(K::default())
+    }
+}
+
+impl<K: Keymap + Default> Default for Composer<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Keymap> Composer<K> {
+    /// Compose with the given key roles
+    pub fn from_keymap(keymap: K) -> Self {
+        Self {
+            keymap,
+            typed_chars: Vec::new(),
+            syllable: Syllable::new(),
+            last_output_len: 0,
+            mode: CompositionMode::Direct,
+            literal_from: None,
+            escaped: false,
+            auto_restore: AutoRestore::default(),
+            tone_style: ToneStyle::default(),
+            keep_word: false,
+            rejected: false,
+            last_restore: None,
+        }
+    }
+
+    /// Use the given auto-restore policy
+    pub fn with_auto_restore(mut self, auto_restore: AutoRestore) -> Self {
+        self.auto_restore = auto_restore;
+        self
+    }
+
+    /// Use the given tone placement style
+    pub fn with_tone_style(mut self, tone_style: ToneStyle) -> Self {
+        self.tone_style = tone_style;
+        self
+    }
+
+    /// Key roles of this composer
+    pub fn keymap(&self) -> &K {
+        &self.keymap
+    }
+
+    /// Apply `key` to the syllable, typed after `previous`
+    fn apply_key(&self, syllable: &mut Syllable, key: char, previous: Option<char>) -> Marked {
+        let mut marked = syllable.clone();
+        match self.mark(&mut marked, key) {
+            Marked::NotApplicable => match self.keymap.shortcut(key) {
+                Some(letter) => {
+                    marked.push(letter);
+                    if !is_possible(&marked) {
+                        return Marked::NotApplicable;
+                    }
+                    *syllable = marked;
+                    Marked::Shortcut
+                }
+                None => Marked::NotApplicable,
+            },
+            Marked::Escaped
+                if self.keymap.repeat() == Repeat::KeepsMark
+                    || (self.keymap.repeat() == Repeat::TakesOffRightAway
+                        && previous != Some(key)) =>
+            {
+                Marked::NotApplicable
+            }
+            result => {
+                *syllable = marked;
+                result
+            }
+        }
+    }
+
+    /// Do what the role of `key` says to the syllable
+    fn mark(&self, syllable: &mut Syllable, key: char) -> Marked {
+        let free = self.keymap.placement() == Placement::Free;
+
+        match self.keymap.role(key) {
+            Some(KeyRole::Tone(tone)) => {
+                if syllable.vowel.is_empty() || !(free || syllable.final_consonant.is_empty()) {
+                    return Marked::NotApplicable;
+                }
+                if syllable.tone == Some(tone) {
+                    syllable.tone = None;
+                    return Marked::Escaped;
+                }
+                // Punctuation does not replace a tone: it ends the word ("chào.")
+                if syllable.tone.is_some() && is_separator(key) {
+                    return Marked::NotApplicable;
+                }
+                syllable.tone = Some(tone);
+                Marked::Applied
+            }
+            Some(KeyRole::RemoveTone) => match syllable.tone.take() {
+                Some(_) => Marked::Applied,
+                None => Marked::NotApplicable,
+            },
+            Some(KeyRole::Mark(modifications)) => {
+                let mut fallback = None;
+                for &modification in modifications {
+                    let mut candidate = syllable.clone();
+                    let result = self.put_mark(&mut candidate, modification, key);
+                    if result == Marked::NotApplicable {
+                        continue;
+                    }
+                    if is_possible(&candidate) {
+                        *syllable = candidate;
+                        return result;
+                    }
+                    fallback.get_or_insert((candidate, result));
+                }
+                match fallback {
+                    Some((candidate, result)) => {
+                        *syllable = candidate;
+                        result
+                    }
+                    None => Marked::NotApplicable,
+                }
+            }
+            Some(KeyRole::Escape) | None => Marked::NotApplicable,
+        }
+    }
+
+    /// Put `modification` on the letter it belongs to
+    fn put_mark(&self, syllable: &mut Syllable, modification: Modification, key: char) -> Marked {
+        let free = self.keymap.placement() == Placement::Free;
+
+        // A mark key that is a letter itself only marks that letter ("aa", "dd")
+        let own = modified(key, modification).map(|_| base_letter(key).to_ascii_lowercase());
+        let fits = |c: char| {
+            modified(c, modification).is_some()
+                && own.is_none_or(|own| base_letter(c).to_ascii_lowercase() == own)
+        };
+
+        // đ is the whole initial
+        if modification == Modification::DStroke {
+            let mut initial = syllable.initial.chars();
+            let target = match (initial.next(), initial.next()) {
+                (Some(d), None) if fits(d) && (free || syllable.vowel.is_empty()) => Some(0),
+                _ => None,
+            };
+            return mark_letter(&mut syllable.initial, target, modification);
+        }
+
+        let vowel: Vec<char> = syllable.vowel.chars().collect();
+        // After "q" the "u" belongs to the initial ("quơ")
+        let first = usize::from(syllable.initial.eq_ignore_ascii_case("q")).min(vowel.len());
+
+        let target = if !free {
+            // The letter just typed
+            let last = vowel.len().checked_sub(1);
+            last.filter(|&i| i >= first && syllable.final_consonant.is_empty() && fits(vowel[i]))
+        } else if modification == Modification::Horn {
+            // The "o" of "uo", otherwise the first letter that takes it
+            (first..vowel.len().saturating_sub(1))
+                .find(|&i| is_uo(&vowel, i))
+                .map(|i| i + 1)
+                .or_else(|| (first..vowel.len()).find(|&i| fits(vowel[i])))
+        } else {
+            // The last letter that takes it ("uo6" → "uô")
+            (first..vowel.len()).rev().find(|&i| fits(vowel[i]))
+        };
+        let result = mark_letter(&mut syllable.vowel, target, modification);
+
+        // The "u" of "uo" takes the horn with its "o" ("ươ")
+        let together = self.keymap.uo_horn() == UoHorn::Together;
+        let u = target
+            .filter(|&o| modification == Modification::Horn && o > first && is_uo(&vowel, o - 1))
+            .filter(|_| together)
+            .map(|o| o - 1);
+        if let Some(u) = u {
+            let mut chars: Vec<char> = syllable.vowel.chars().collect();
+            chars[u] = match result {
+                Marked::Escaped => base_letter(vowel[u]),
+                _ => modified(vowel[u], modification).unwrap_or(vowel[u]),
+            };
+            syllable.vowel = chars.into_iter().collect();
+        }
+        result
+    }
+
+    /// Rebuild syllable from typed history
+    ///
+    /// Also returns the index of the first escaped key, if any; keys from
+    /// there on are not part of the syllable.
+    fn parse(&self, keys: &[char]) -> (Syllable, Option<usize>) {
+        let mut syllable = Syllable::new();
+        let mut escape = None;
+        // Syllable before the previous key, if that key was a shortcut
+        let mut before_shortcut = None;
+
+        for (i, &key) in keys.iter().enumerate() {
+            if self.keymap.role(key) == Some(KeyRole::Escape) {
+                escape = Some(i);
+                break;
+            }
+            let previous = i.checked_sub(1).map(|i| keys[i]);
+            let before = syllable.clone();
+            match self.apply_key(&mut syllable, key, previous) {
+                Marked::Applied => {}
+                Marked::Shortcut => {
+                    before_shortcut = Some(before);
+                    continue;
+                }
+                Marked::Escaped => {
+                    // A shortcut typed twice gives its key ("ww" → "w")
+                    if let Some(before) = before_shortcut.filter(|_| previous == Some(key)) {
+                        syllable = before;
+                    }
+                    escape = Some(i);
+                    break;
+                }
+                Marked::NotApplicable => syllable.push(key),
+            }
+            before_shortcut = None;
+        }
+
+        if self.keymap.uo_horn() == UoHorn::OnceTheWordGoesOn {
+            settle_horn(&mut syllable, false);
+        }
+        (syllable, escape)
+    }
+
+    /// Keys typed literally: escape keys dropped, each escaped key kept;
+    /// a trailing escape key is still shown
+    fn unescape(&self, keys: &[char]) -> String {
+        let mut text = String::new();
+        let mut escape = None;
+        for &key in keys {
+            if escape.is_none() && self.keymap.role(key) == Some(KeyRole::Escape) {
+                escape = Some(key);
+            } else {
+                text.push(key);
+                escape = None;
+            }
+        }
+        text.extend(escape);
+        text
+    }
+
+    /// Is the next key escaped by an escape key?
+    pub(crate) fn escape_pending(&self) -> bool {
+        let escapes = self
+            .typed_chars
+            .iter()
+            .rev()
+            .take_while(|&&c| self.keymap.role(c) == Some(KeyRole::Escape));
+        escapes.count() % 2 == 1
+    }
+
+    /// Does `key` mark the current word rather than end it? Only asked for
+    /// keys that are also punctuation.
+    pub(crate) fn marks_word(&self, key: char) -> bool {
+        if self.literal_from.is_some() {
+            return false;
+        }
+        let mut syllable = self.syllable.clone();
+        match self.apply_key(&mut syllable, key, self.typed_chars.last().copied()) {
+            Marked::Applied => is_possible(&syllable),
+            Marked::Escaped | Marked::Shortcut => true,
+            Marked::NotApplicable => false,
+        }
+    }
+
+    /// Add a key to the history and recompute the syllable
+    fn push_key(&mut self, key: char) {
+        self.typed_chars.push(key);
+        if self.literal_from.is_some() {
+            return;
+        }
+
+        let (new_syllable, escape) = self.parse(&self.typed_chars);
+        if let Some(index) = escape {
+            self.syllable = new_syllable;
+            self.literal_from = Some(index);
+            self.escaped = true;
+            return;
+        }
+        if is_possible(&new_syllable) {
+            self.syllable = new_syllable;
+            return;
+        }
+
+        // The word cannot be Vietnamese any more: keep the keys as typed
+        self.rejected = true;
+        if self.auto_restore.is_strict() && !self.keep_word {
+            self.syllable.clear();
+            self.literal_from = Some(0);
+        } else {
+            self.literal_from = Some(self.typed_chars.len() - 1);
+        }
+    }
+
+    /// Recompute the state from `typed_chars`
+    fn rebuild(&mut self) {
+        let keys = std::mem::take(&mut self.typed_chars);
+        self.syllable.clear();
+        self.literal_from = None;
+        self.escaped = false;
+        for key in keys {
+            self.push_key(key);
+        }
+        self.rejected = false;
+    }
+
+    /// Text of the current word as shown to the user
+    pub(crate) fn output(&self) -> String {
+        let mut output = self.syllable.render(self.tone_style);
+        if let Some(from) = self.literal_from {
+            output.push_str(&self.unescape(&self.typed_chars[from..]));
+        }
+        output
+    }
+
+    /// Text of the current word once it ends
+    fn final_output(&self) -> String {
+        let mut syllable = self.syllable.clone();
+        if self.literal_from.is_none() && self.keymap.uo_horn() == UoHorn::OnceTheWordGoesOn {
+            settle_horn(&mut syllable, true);
+        }
+        let mut output = syllable.render(self.tone_style);
+        if let Some(from) = self.literal_from {
+            output.push_str(&self.unescape(&self.typed_chars[from..]));
+        }
+        output
+    }
+
+    /// Finish the current word on a separator
+    pub(crate) fn commit(&mut self, separator: char, buffer: &mut InputBuffer) -> Action {
+        if self.typed_chars.is_empty() {
+            return Action::PassThrough;
+        }
+
+        let shown = self.output();
+        let composed = self.final_output();
+        let raw = self.unescape(&self.typed_chars);
+        let restore =
+            !self.keep_word && !self.escaped && self.auto_restore.should_restore(&composed, &raw);
+        let backspace = self.last_output_len;
+        let typed = std::mem::take(&mut self.typed_chars);
+
+        self.reset();
+        buffer.clear();
+
+        let text = if restore {
+            raw
+        } else if composed != shown {
+            composed
+        } else {
+            return match self.mode {
+                // Text on screen is already final; the host types the separator
+                CompositionMode::Direct => Action::PassThrough,
+                CompositionMode::Preedit => Action::CommitPreedit,
+            };
+        };
+
+        match self.mode {
+            CompositionMode::Direct => {
+                if restore {
+                    self.last_restore = Some(typed);
+                }
+                Action::Replace {
+                    backspace_count: backspace,
+                    text: format!("{}{}", text, separator),
+                }
+            }
+            CompositionMode::Preedit => Action::Commit(format!("{}{}", text, separator)),
+        }
+    }
+
+    /// First key with a role matching `matches`, lowercase keys first
+    fn key_for(&self, matches: impl Fn(KeyRole) -> bool) -> Option<char> {
+        (' '..='~')
+            .filter(|key| !key.is_ascii_uppercase())
+            .find(|&key| self.keymap.role(key).is_some_and(&matches))
+    }
+
+    /// Key that puts the modification of `letter` on it, if it needs one
+    fn mark_key(&self, letter: char) -> Option<Option<char>> {
+        let Some(modification) = modification_of(letter) else {
+            return Some(None);
+        };
+        let base = base_letter(letter);
+        let fits =
+            |role: KeyRole| matches!(role, KeyRole::Mark(marks) if marks.contains(&modification));
+
+        // The letter itself if it is its own mark key ("aa"), or another key
+        let own = base.to_ascii_lowercase();
+        if self.keymap.role(own).is_some_and(fits) && modified(own, modification).is_some() {
+            return Some(Some(base));
+        }
+        self.key_for(fits)
+            .filter(|&key| modified(key, modification).is_none())
+            .map(Some)
+    }
+
+    /// Keys that compose a written syllable ("việt" → "vieetj" in Telex)
+    fn keys_for(&self, syllable: &Syllable) -> Option<Vec<char>> {
+        let tone = match syllable.tone {
+            Some(tone) => Some(self.key_for(|role| role == KeyRole::Tone(tone))?),
+            None => None,
+        };
+
+        let mut keys = Vec::new();
+        for ch in syllable.initial.chars().chain(syllable.vowel.chars()) {
+            keys.push(base_letter(ch));
+            keys.extend(self.mark_key(ch)?);
+        }
+        if self.keymap.placement() == Placement::AfterLetter {
+            keys.extend(tone);
+        }
+        keys.extend(syllable.final_consonant.chars());
+        if self.keymap.placement() == Placement::Free {
+            keys.extend(tone);
+        }
+        Some(keys)
+    }
+
+    /// Take over `word` as the word being composed
+    ///
+    /// Returns `false` if typing its keys would not give back exactly the
+    /// same word; the state is then left unchanged.
+    pub(crate) fn load_word(&mut self, word: &str) -> bool {
+        let Some(keys) = Syllable::parse(word).and_then(|syllable| self.keys_for(&syllable)) else {
+            return false;
+        };
+        let (parsed, escape) = self.parse(&keys);
+        if escape.is_some() || parsed.render(self.tone_style) != word {
+            return false;
+        }
+
+        self.reset();
+        self.typed_chars = keys;
+        self.rebuild();
+        self.last_output_len = word.chars().count();
+        true
+    }
+
+    /// Take over `text` as the word being composed: composed again from its
+    /// keys if it is a syllable, kept as typed otherwise
+    pub(crate) fn load_text(&mut self, text: &str) {
+        if self.load_word(text) {
+            return;
+        }
+        self.reset();
+        self.typed_chars = text.chars().collect();
+        self.literal_from = Some(0);
+        self.last_output_len = self.typed_chars.len();
+    }
+
+    /// Type a key of the word (not a separator)
+    pub(crate) fn type_key(&mut self, key: char, buffer: &mut InputBuffer) -> Action {
+        self.last_restore = None;
+        self.rejected = false;
+        self.push_key(key);
+        self.render(buffer)
+    }
+
+    /// Render the current syllable, sync the buffer and build the output action
+    fn render(&mut self, buffer: &mut InputBuffer) -> Action {
+        let output = self.output();
+        let backspace = self.last_output_len;
+        self.last_output_len = output.chars().count();
+
+        // Update buffer
+        buffer.clear();
+        for ch in output.chars() {
+            buffer.push(ch, ch.is_lowercase());
+        }
+        buffer.set_last_is_escape(self.escape_pending());
+
+        match self.mode {
+            CompositionMode::Direct => Action::Replace {
+                backspace_count: backspace,
+                text: output,
+            },
+            CompositionMode::Preedit => Action::preedit(output),
+        }
+    }
+}
+
+impl<K: Keymap> InputMethodTrait for Composer<K> {
+    fn name(&self) -> &str {
+        self.keymap.name()
+    }
+
+    fn id(&self) -> &str {
+        self.keymap.id()
+    }
+
+    fn process(
+        &mut self,
+        key: &KeyEvent,
+        buffer: &mut InputBuffer,
+        _lookup: &dyn LookupProvider,
+    ) -> Action {
+        let Some(key) = key.char() else {
+            return Action::PassThrough;
+        };
+
+        // Check for separator - commit current syllable, unless the key is
+        // escaped or marks the word
+        if is_separator(key) && !self.escape_pending() && !self.marks_word(key) {
+            return self.commit(key, buffer);
+        }
+
+        self.type_key(key, buffer)
+    }
+
+    fn process_backspace(&mut self, buffer: &mut InputBuffer) -> Action {
+        self.rejected = false;
+        if self.typed_chars.is_empty() {
+            // Backspace right after an auto-restore: "I meant it", bring the
+            // composed word back (without the separator) and keep it
+            return match self.last_restore.take() {
+                Some(keys) => {
+                    let restored_len = self.unescape(&keys).chars().count() + 1;
+                    self.typed_chars = keys;
+                    self.keep_word = true;
+                    self.rebuild();
+                    self.last_output_len = restored_len;
+                    self.render(buffer)
+                }
+                None => Action::PassThrough,
+            };
+        }
+
+        // Pop last key from history
+        self.typed_chars.pop();
+
+        // Rebuild
+        self.rebuild();
+        self.render(buffer)
+    }
+
+    fn reset(&mut self) {
+        self.typed_chars.clear();
+        self.syllable.clear();
+        self.last_output_len = 0;
+        self.literal_from = None;
+        self.escaped = false;
+        self.keep_word = false;
+        self.rejected = false;
+        self.last_restore = None;
+    }
+
+    fn can_undo(&self, _buffer: &InputBuffer) -> bool {
+        !self.typed_chars.is_empty()
+    }
+
+    fn undo(&mut self, buffer: &mut InputBuffer) -> Action {
+        self.process_backspace(buffer)
+    }
+
+    fn set_composition_mode(&mut self, mode: CompositionMode) -> bool {
+        self.mode = mode;
+        true
+    }
+
+    fn raw_input(&self) -> Option<String> {
+        Some(self.typed_chars.iter().collect())
+    }
+
+    fn rejected_last_key(&self) -> bool {
+        self.rejected
+    }
+
+    fn resume(&mut self, text_before: &str, buffer: &mut InputBuffer) -> usize {
+        let mut word: Vec<char> = text_before
+            .chars()
+            .rev()
+            .take_while(|c| c.is_alphabetic())
+            .collect();
+        word.reverse();
+        let word: String = word.into_iter().collect();
+
+        // Only resume if typing the keys gives back exactly the same word
+        if !self.load_word(&word) {
+            return 0;
+        }
+
+        buffer.clear();
+        for ch in word.chars() {
+            buffer.push(ch, ch.is_lowercase());
+        }
+        self.last_output_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup::VietnameseLookup;
+
+    /// Keymap with one key per role, to test the composer on its own
+    #[derive(Default)]
+    struct TestKeymap {
+        placement: Placement,
+        repeat: Repeat,
+        uo_horn: UoHorn,
+    }
+
+    impl Keymap for TestKeymap {
+        fn id(&self) -> &str {
+            "test"
+        }
+
+        fn name(&self) -> &str {
+            "Test"
+        }
+
+        fn role(&self, key: char) -> Option<KeyRole<'_>> {
+            match key {
+                '1' => Some(KeyRole::Tone(Tone::Acute)),
+                '2' => Some(KeyRole::Tone(Tone::Grave)),
+                '.' => Some(KeyRole::Tone(Tone::Underdot)),
+                '0' => Some(KeyRole::RemoveTone),
+                '^' => Some(KeyRole::Mark(&[Modification::Circumflex])),
+                '+' => Some(KeyRole::Mark(&[Modification::Horn, Modification::Breve])),
+                'd' => Some(KeyRole::Mark(&[Modification::DStroke])),
+                '\\' => Some(KeyRole::Escape),
+                _ => None,
+            }
+        }
+
+        fn placement(&self) -> Placement {
+            self.placement
+        }
+
+        fn repeat(&self) -> Repeat {
+            self.repeat
+        }
+
+        fn uo_horn(&self) -> UoHorn {
+            self.uo_horn
+        }
+
+        fn shortcut(&self, key: char) -> Option<char> {
+            match key {
+                '+' => Some('ơ'),
+                ']' => Some('ư'),
+                _ => None,
+            }
+        }
+    }
+
+    fn type_word(method: &mut Composer<TestKeymap>, keys: &str) -> String {
+        let mut buffer = InputBuffer::new();
+        let lookup = VietnameseLookup::new_telex();
+        for c in keys.chars() {
+            method.process(&KeyEvent::from_char(c), &mut buffer, &lookup);
+        }
+        method.output()
+    }
+
+    fn composer(placement: Placement, repeat: Repeat) -> Composer<TestKeymap> {
+        Composer::from_keymap(TestKeymap {
+            placement,
+            repeat,
+            ..TestKeymap::default()
+        })
+    }
+
+    #[test]
+    fn test_roles() {
+        for (keys, expected) in [
+            ("a1", "á"),
+            ("a12", "à"),
+            ("a10", "a"),
+            ("a^", "â"),
+            ("u+", "ư"),
+            ("a+", "ă"),
+            ("hoa+", "hoă"),
+            ("dd", "đ"),
+            ("nguye^n2", "nguyền"),
+            ("qua1", "quá"),
+            ("gia2", "già"),
+        ] {
+            let mut method = Composer::<TestKeymap>::new();
+            assert_eq!(type_word(&mut method, keys), expected, "{}", keys);
+        }
+    }
+
+    #[test]
+    fn test_horn_on_uo() {
+        let mut method = Composer::<TestKeymap>::new();
+        assert_eq!(type_word(&mut method, "tuo+"), "tươ");
+        assert_eq!(type_word(&mut method, "ng"), "tương");
+
+        let mut method = Composer::<TestKeymap>::new();
+        assert_eq!(type_word(&mut method, "thuo+2"), "thườ");
+        assert_eq!(type_word(&mut Composer::new(), "tuo++"), "tuo+");
+        assert_eq!(type_word(&mut Composer::new(), "quo+"), "quơ");
+        assert_eq!(type_word(&mut Composer::new(), "muo+i"), "mươi");
+        assert_eq!(type_word(&mut Composer::new(), "duo+c."), "dược");
+    }
+
+    #[test]
+    fn test_horn_on_uo_once_the_word_goes_on() {
+        let keymap = || TestKeymap {
+            uo_horn: UoHorn::OnceTheWordGoesOn,
+            ..TestKeymap::default()
+        };
+        let mut method = Composer::from_keymap(keymap());
+        assert_eq!(type_word(&mut method, "tuo+"), "tuơ");
+        assert_eq!(type_word(&mut method, "n"), "tuơn");
+        assert_eq!(type_word(&mut method, "g"), "tương");
+
+        let mut method = Composer::from_keymap(keymap());
+        assert_eq!(type_word(&mut method, "thuo+2"), "thuờ");
+        let mut method = Composer::from_keymap(keymap());
+        assert_eq!(type_word(&mut method, "muo+i"), "mươi");
+    }
+
+    #[test]
+    fn test_final_settles_on_word_end() {
+        let mut method = Composer::from_keymap(TestKeymap {
+            uo_horn: UoHorn::OnceTheWordGoesOn,
+            ..TestKeymap::default()
+        });
+        let mut buffer = InputBuffer::new();
+        assert_eq!(type_word(&mut method, "luo+n"), "luơn");
+        assert_eq!(
+            method.commit(' ', &mut buffer),
+            Action::Replace {
+                backspace_count: 4,
+                text: "lươn ".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_placement() {
+        let mut free = composer(Placement::Free, Repeat::TakesOff);
+        assert_eq!(type_word(&mut free, "viet^."), "việt");
+
+        let mut after_letter = composer(Placement::AfterLetter, Repeat::TakesOff);
+        assert_eq!(type_word(&mut after_letter, "vie^.t"), "việt");
+        after_letter.reset();
+        assert_eq!(type_word(&mut after_letter, "viet^"), "viet^");
+    }
+
+    #[test]
+    fn test_repeat() {
+        let mut takes_off = composer(Placement::Free, Repeat::TakesOff);
+        assert_eq!(type_word(&mut takes_off, "a11"), "a1");
+        takes_off.reset();
+        assert_eq!(type_word(&mut takes_off, "a1n1"), "an1");
+
+        let mut right_away = composer(Placement::Free, Repeat::TakesOffRightAway);
+        assert_eq!(type_word(&mut right_away, "a11"), "a1");
+        right_away.reset();
+        assert_eq!(type_word(&mut right_away, "a1n1"), "án1");
+
+        let mut keeps_mark = composer(Placement::Free, Repeat::KeepsMark);
+        assert_eq!(type_word(&mut keeps_mark, "a11"), "á1");
+        keeps_mark.reset();
+        assert_eq!(type_word(&mut keeps_mark, "a^^"), "â^");
+    }
+
+    #[test]
+    fn test_escape_key() {
+        let mut method = Composer::<TestKeymap>::new();
+        assert_eq!(type_word(&mut method, "a\\"), "a\\");
+        assert!(method.escape_pending());
+        assert_eq!(type_word(&mut method, "1"), "a1");
+        assert_eq!(method.raw_input().unwrap(), "a\\1");
+        assert_eq!(
+            type_word(&mut Composer::<TestKeymap>::new(), "a\\\\"),
+            "a\\"
+        );
+    }
+
+    #[test]
+    fn test_shortcuts() {
+        for (keys, expected) in [
+            ("]", "ư"),
+            ("t]", "tư"),
+            ("t]ng", "tưng"),
+            ("+", "ơ"),
+            ("++", "+"),
+            ("u+", "ư"),
+            ("a+", "ă"),
+            ("an+", "ăn"),
+        ] {
+            assert_eq!(
+                type_word(&mut Composer::<TestKeymap>::new(), keys),
+                expected,
+                "{}",
+                keys
+            );
+        }
+
+        // A separator that types a letter does not end the word
+        let mut method = Composer::<TestKeymap>::new();
+        let mut buffer = InputBuffer::new();
+        let lookup = VietnameseLookup::new_telex();
+        assert_eq!(
+            method.process(&KeyEvent::from_char(']'), &mut buffer, &lookup),
+            Action::Replace {
+                backspace_count: 0,
+                text: "ư".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_word() {
+        let mut method = Composer::<TestKeymap>::new();
+        assert!(method.load_word("đường"));
+        assert_eq!(method.raw_input().unwrap(), "ddu+o+ng2");
+        assert!(method.load_word("Quyền"));
+        assert!(!method.load_word("hello"));
+    }
+}
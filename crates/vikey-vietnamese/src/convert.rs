@@ -0,0 +1,336 @@
+//! Text conversion between charsets (chuyển mã)
+//!
+//! Reads text written in any `Charset` back into Unicode and writes it out
+//! in another one. Byte encodings come either as raw bytes (a file) or as
+//! text a Windows ANSI application produced from them (a clipboard copied
+//! from a .VnTime document); both are accepted.
+//!
+//! ```
+//! use vikey_vietnamese::convert::convert;
+//! use vikey_vietnamese::Charset;
+//!
+//! assert_eq!(convert("Vie^.t Nam", Charset::Viqr, Charset::Unicode), "Việt Nam");
+//! assert_eq!(convert("Việt", Charset::Unicode, Charset::VniWindows), "Vieät");
+//! ```
+
+use crate::charset::{Charset, TCVN3, VIQR_MARKS, VISCII, VNI_WINDOWS};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use unicode_normalization::UnicodeNormalization;
+
+/// Text encoded in a byte charset
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Encoded {
+    /// Encoded bytes
+    pub bytes: Vec<u8>,
+
+    /// Characters the charset cannot represent, written as '?'
+    pub unmapped: Vec<char>,
+}
+
+/// Decode text written in `charset` into Unicode (NFC)
+///
+/// For byte encodings, `text` holds one char per byte, as shown by a
+/// Windows ANSI application (see `Charset::byte_char`); chars that are not
+/// such a byte are kept as is.
+pub fn decode(charset: Charset, text: &str) -> String {
+    match charset {
+        Charset::Unicode | Charset::UnicodeNfd => text.nfc().collect(),
+        Charset::Viqr => parse_viqr(text),
+        Charset::NcrDecimal | Charset::NcrHex => parse_ncr(text),
+        Charset::Tcvn3 | Charset::VniWindows | Charset::Viscii | Charset::Cp1258 => {
+            let mut out = String::with_capacity(text.len());
+            let mut bytes = Vec::new();
+            for c in text.chars() {
+                match charset.char_byte(c) {
+                    Some(byte) => bytes.push(byte),
+                    None => {
+                        out.push_str(&decode_bytes(charset, &bytes));
+                        bytes.clear();
+                        out.push(c);
+                    }
+                }
+            }
+            out.push_str(&decode_bytes(charset, &bytes));
+            out.nfc().collect()
+        }
+    }
+}
+
+/// Decode the content of a file written in `charset` into Unicode (NFC)
+///
+/// Charsets that are not byte encodings are read as UTF-8 (invalid
+/// sequences become U+FFFD, a byte order mark is dropped).
+pub fn decode_bytes(charset: Charset, bytes: &[u8]) -> String {
+    let text: String = match charset {
+        Charset::Tcvn3 => bytes
+            .iter()
+            .map(|&b| TCVN3_DECODE[usize::from(b)])
+            .collect(),
+        Charset::Viscii => bytes
+            .iter()
+            .map(|&b| VISCII_DECODE[usize::from(b)])
+            .collect(),
+        Charset::Cp1258 => bytes.iter().map(|&b| charset.byte_char(b)).collect(),
+        Charset::VniWindows => decode_vni(bytes),
+        _ => {
+            let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+            return decode(charset, &String::from_utf8_lossy(bytes));
+        }
+    };
+    text.nfc().collect()
+}
+
+/// Encode `text` as the content of a file written in `charset`
+///
+/// Charsets that are not byte encodings are written as UTF-8.
+pub fn encode_bytes(charset: Charset, text: &str) -> Encoded {
+    if !charset.is_byte_encoding() {
+        return Encoded {
+            bytes: charset.encode(text).into_bytes(),
+            unmapped: Vec::new(),
+        };
+    }
+    let mut encoded = Encoded::default();
+    for c in text.nfc() {
+        match charset.bytes(c) {
+            Some(bytes) => encoded.bytes.extend_from_slice(&bytes),
+            None => {
+                encoded.bytes.push(b'?');
+                encoded.unmapped.push(c);
+            }
+        }
+    }
+    encoded
+}
+
+/// Convert text from one charset to another
+///
+/// Byte encodings are read and written one char per byte, see `decode`.
+pub fn convert(text: &str, from: Charset, to: Charset) -> String {
+    to.encode(&decode(from, text))
+}
+
+/// Convert file content from one charset to another
+pub fn convert_bytes(bytes: &[u8], from: Charset, to: Charset) -> Encoded {
+    encode_bytes(to, &decode_bytes(from, bytes))
+}
+
+/// Byte → char tables of the one-byte charsets; bytes that are not
+/// Vietnamese letters keep their Latin-1 meaning
+fn decode_table(table: &[(char, u8)], extra: &[(char, u8)]) -> [char; 256] {
+    let mut chars: [char; 256] = std::array::from_fn(|b| char::from(b as u8));
+    for &(c, byte) in table.iter().chain(extra) {
+        chars[usize::from(byte)] = c;
+    }
+    chars
+}
+
+/// TCVN3 also has the five tone marks as combining bytes
+static TCVN3_DECODE: Lazy<[char; 256]> = Lazy::new(|| {
+    let marks = [
+        ('\u{0300}', 0xB0),
+        ('\u{0309}', 0xB1),
+        ('\u{0303}', 0xB2),
+        ('\u{0301}', 0xB3),
+        ('\u{0323}', 0xB4),
+    ];
+    decode_table(TCVN3, &marks)
+});
+
+static VISCII_DECODE: Lazy<[char; 256]> = Lazy::new(|| decode_table(VISCII, &[]));
+
+static VNI_WINDOWS_DECODE: Lazy<HashMap<&'static [u8], char>> =
+    Lazy::new(|| VNI_WINDOWS.iter().map(|&(c, bytes)| (bytes, c)).collect());
+
+/// VNI Windows letters take one or two bytes: match the longest
+fn decode_vni(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    let mut rest = bytes;
+    while let Some(&first) = rest.first() {
+        let pair = rest.get(..2).and_then(|pair| VNI_WINDOWS_DECODE.get(pair));
+        match pair {
+            Some(&c) => {
+                out.push(c);
+                rest = &rest[2..];
+            }
+            None => {
+                let single = VNI_WINDOWS_DECODE.get(&rest[..1]).copied();
+                out.push(single.unwrap_or_else(|| Charset::VniWindows.byte_char(first)));
+                rest = &rest[1..];
+            }
+        }
+    }
+    out
+}
+
+/// Combining mark for a VIQR modifier after `base`
+fn viqr_modifier(base: char, mark: char) -> Option<char> {
+    match (base.to_ascii_lowercase(), mark) {
+        ('a' | 'e' | 'o', '^') => Some('\u{0302}'),
+        ('a', '(') => Some('\u{0306}'),
+        ('o' | 'u', '+') => Some('\u{031B}'),
+        _ => None,
+    }
+}
+
+/// Combining mark for a VIQR tone
+fn viqr_tone(mark: char) -> Option<char> {
+    match mark {
+        '\'' => Some('\u{0301}'),
+        '`' => Some('\u{0300}'),
+        '?' => Some('\u{0309}'),
+        '~' => Some('\u{0303}'),
+        '.' => Some('\u{0323}'),
+        _ => None,
+    }
+}
+
+/// Read VIQR (RFC 1456): a vowel takes a modifier then a tone ("e^."),
+/// "dd" starting a word is "đ"
+fn parse_viqr(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        i += 1;
+
+        if c == '\\' && next.is_some_and(|next| VIQR_MARKS.contains(&next)) {
+            out.extend(next);
+            i += 1;
+            continue;
+        }
+        let starts_word = !out.chars().next_back().is_some_and(char::is_alphabetic);
+        if matches!(c, 'd' | 'D') && matches!(next, Some('d' | 'D')) && starts_word {
+            out.push(if c == 'd' { 'đ' } else { 'Đ' });
+            i += 1;
+            continue;
+        }
+        if !matches!(c.to_ascii_lowercase(), 'a' | 'e' | 'i' | 'o' | 'u' | 'y') {
+            out.push(c);
+            continue;
+        }
+
+        let mut letter = String::from(c);
+        if let Some(modifier) = next.and_then(|mark| viqr_modifier(c, mark)) {
+            letter.push(modifier);
+            i += 1;
+        }
+        if let Some(tone) = chars.get(i).and_then(|&mark| viqr_tone(mark)) {
+            letter.push(tone);
+            i += 1;
+        }
+        out.extend(letter.nfc());
+    }
+    out
+}
+
+/// Read HTML numeric character references ("&#7879;", "&#x1EC7;"); other
+/// text, and references that are not valid chars, are kept as is
+fn parse_ncr(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("&#") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let parsed = rest[2..].split_once(';').and_then(|(number, _)| {
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => number.parse(),
+            };
+            Some((char::from_u32(code.ok()?)?, number.len() + 3))
+        });
+        match parsed {
+            Some((c, len)) if len > 3 => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            _ => {
+                out.push_str("&#");
+                rest = &rest[2..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.nfc().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Tiếng Việt được viết bằng chữ Quốc ngữ. ĐƯỢC, Ỹ ỵ";
+
+    #[test]
+    fn test_round_trip() {
+        for from in Charset::ALL {
+            let encoded = from.encode(TEXT);
+            assert_eq!(decode(from, &encoded), TEXT, "{}", from);
+
+            let file = encode_bytes(from, TEXT);
+            assert!(file.unmapped.is_empty(), "{}", from);
+            assert_eq!(decode_bytes(from, &file.bytes), TEXT, "{}", from);
+
+            for to in Charset::ALL {
+                assert_eq!(convert(&encoded, from, to), to.encode(TEXT));
+                assert_eq!(convert_bytes(&file.bytes, from, to), encode_bytes(to, TEXT));
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_bytes() {
+        assert_eq!(decode_bytes(Charset::Tcvn3, b"Vi\xD6t"), "Việt");
+        // Decomposed TCVN3: base letter followed by a combining tone byte
+        assert_eq!(decode_bytes(Charset::Tcvn3, b"Vi\xAA\xB4t"), "Việt");
+        assert_eq!(decode_bytes(Charset::VniWindows, b"Vie\xE4t"), "Việt");
+        assert_eq!(decode_bytes(Charset::Viscii, b"Vi\xAEt"), "Việt");
+        assert_eq!(decode_bytes(Charset::Cp1258, b"Vi\xEA\xF2t"), "Việt");
+        assert_eq!(
+            decode_bytes(
+                Charset::UnicodeNfd,
+                "\u{FEFF}Vie\u{0302}\u{0323}t".as_bytes()
+            ),
+            "Việt"
+        );
+        assert_eq!(decode_bytes(Charset::Unicode, b"\xFF"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn test_encode_bytes() {
+        assert_eq!(encode_bytes(Charset::Cp1258, "Việt").bytes, b"Vi\xEA\xF2t");
+        assert_eq!(encode_bytes(Charset::Cp1258, "Đà").bytes, b"\xD0\xE0");
+        assert_eq!(
+            encode_bytes(Charset::Tcvn3, "Việt 中文"),
+            Encoded {
+                bytes: b"Vi\xD6t ??".to_vec(),
+                unmapped: vec!['中', '文'],
+            }
+        );
+        assert_eq!(
+            encode_bytes(Charset::UnicodeNfd, "ệ").bytes,
+            "e\u{0323}\u{0302}".as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_viqr() {
+        assert_eq!(parse_viqr("DDu+o+`ng ddi"), "Đường đi");
+        assert_eq!(parse_viqr("A(n co+m chu+a?"), "Ăn cơm chưả");
+        assert_eq!(parse_viqr("A(n co+m chu+a\\?"), "Ăn cơm chưa?");
+        assert_eq!(parse_viqr("add Nam."), "add Nam.");
+        assert_eq!(parse_viqr("ki^"), "ki^");
+    }
+
+    #[test]
+    fn test_ncr() {
+        assert_eq!(parse_ncr("Vi&#7879;t &#x1ec7; &#X1EC7;"), "Việt ệ ệ");
+        assert_eq!(
+            parse_ncr("&#; &#xZZ; &#1114112; &#12"),
+            "&#; &#xZZ; &#1114112; &#12"
+        );
+        assert_eq!(parse_ncr("a &amp; b"), "a &amp; b");
+    }
+}
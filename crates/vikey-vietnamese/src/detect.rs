@@ -0,0 +1,304 @@
+//! Charset detection (nhận dạng bảng mã)
+//!
+//! Reads text in every charset it could be written in and keeps the
+//! readings whose words are Vietnamese syllables
+//! (`validation::is_valid_word`). Besides raw files, text often arrives
+//! misread: bytes shown as Windows-1252 by a program that did not know the
+//! charset, then saved again as UTF-8 ("Viá»‡t" for "Việt", or TCVN3 text
+//! pasted from a .VnTime document). Those readings are tried as well.
+//!
+//! ```
+//! use vikey_vietnamese::detect::detect;
+//! use vikey_vietnamese::Charset;
+//!
+//! let guesses = detect("Tiếng Việt".as_bytes());
+//! assert_eq!(guesses[0].charset, Charset::Unicode);
+//! assert_eq!(guesses[0].confidence, 1.0);
+//! ```
+
+use crate::charset::Charset;
+use crate::convert::{decode, decode_bytes};
+use crate::validation::is_valid_word;
+use std::borrow::Cow;
+use std::fmt;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// Bytes read to detect the charset of longer input
+const SAMPLE_LEN: usize = 64 * 1024;
+
+/// A possible charset of some input, as returned by `detect`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guess {
+    /// Charset the text was written in
+    pub charset: Charset,
+
+    /// The bytes were shown as Windows-1252 (CP 1258 for `Cp1258`) text and
+    /// saved again as UTF-8
+    pub misread: bool,
+
+    /// Share of the Vietnamese-looking words this reading spells
+    /// correctly, from 0.0 to 1.0
+    pub confidence: f32,
+}
+
+impl Guess {
+    fn new(charset: Charset, misread: bool) -> Self {
+        Self {
+            charset,
+            misread,
+            confidence: 0.0,
+        }
+    }
+
+    /// Decode `bytes` into Unicode (NFC) the way this guess reads them
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        if !self.misread {
+            return decode_bytes(self.charset, bytes);
+        }
+        let text = String::from_utf8_lossy(bytes);
+        if self.charset.is_byte_encoding() {
+            return decode(self.charset, &text);
+        }
+        // Misread UTF-8: get the bytes back, then read them as UTF-8
+        let original: Option<Vec<u8>> = text.chars().map(|c| self.charset.char_byte(c)).collect();
+        match original {
+            Some(original) => String::from_utf8_lossy(&original).nfc().collect(),
+            None => text.nfc().collect(),
+        }
+    }
+
+    /// `bytes` as written in `charset`, undoing the misreading
+    ///
+    /// Chars that no byte is shown as are kept in UTF-8.
+    pub fn source_bytes<'a>(&self, bytes: &'a [u8]) -> Cow<'a, [u8]> {
+        if !self.misread {
+            return Cow::Borrowed(bytes);
+        }
+        let mut original = Vec::with_capacity(bytes.len());
+        for c in String::from_utf8_lossy(bytes).chars() {
+            match self.charset.char_byte(c) {
+                Some(byte) => original.push(byte),
+                None => original.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
+        }
+        Cow::Owned(original)
+    }
+}
+
+impl fmt::Display for Guess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.charset)?;
+        if self.misread {
+            f.write_str(" (read as Windows-1252)")?;
+        }
+        write!(f, ", {:.0}%", self.confidence * 100.0)
+    }
+}
+
+/// Possible charsets of `bytes`, most likely first
+///
+/// Readings that give the same text are listed once. Input without any
+/// Vietnamese-looking word (e.g. plain ASCII) gives a single Unicode guess
+/// with full confidence, since every charset would read it the same.
+pub fn detect(bytes: &[u8]) -> Vec<Guess> {
+    let sample = sample(bytes);
+    let utf8 = std::str::from_utf8(sample).ok();
+
+    let mut candidates = vec![Guess::new(Charset::Unicode, false)];
+    if utf8.is_some_and(|text| !is_nfc(text)) {
+        candidates[0].charset = Charset::UnicodeNfd;
+    }
+    for charset in [
+        Charset::Tcvn3,
+        Charset::VniWindows,
+        Charset::Viscii,
+        Charset::Cp1258,
+    ] {
+        candidates.push(Guess::new(charset, false));
+    }
+    candidates.push(Guess::new(Charset::Viqr, false));
+    let hex = sample.windows(3).any(|w| w.eq_ignore_ascii_case(b"&#x"));
+    candidates.push(Guess::new(
+        if hex {
+            Charset::NcrHex
+        } else {
+            Charset::NcrDecimal
+        },
+        false,
+    ));
+    // Misread text holds only chars a byte can be shown as
+    if let Some(text) = utf8.filter(|text| !text.is_ascii()) {
+        for charset in [
+            Charset::Unicode,
+            Charset::Tcvn3,
+            Charset::VniWindows,
+            Charset::Viscii,
+            Charset::Cp1258,
+        ] {
+            if text.chars().all(|c| charset.char_byte(c).is_some()) {
+                candidates.push(Guess::new(charset, true));
+            }
+        }
+    }
+
+    let mut readings: Vec<String> = Vec::new();
+    let mut scored = Vec::new();
+    for candidate in candidates {
+        let text = candidate.decode(sample);
+        if readings.contains(&text) {
+            continue;
+        }
+        scored.push((candidate, score(candidate.charset, sample, &text)));
+        readings.push(text);
+    }
+
+    let evidence = scored.iter().map(|(_, s)| s.valid + s.invalid).max();
+    let Some(evidence) = evidence.filter(|&evidence| evidence > 0) else {
+        let mut guess = scored[0].0;
+        guess.confidence = 1.0;
+        return vec![guess];
+    };
+    let mut guesses: Vec<Guess> = scored
+        .into_iter()
+        .map(|(mut guess, score)| {
+            let net = score.valid.saturating_sub(score.invalid);
+            guess.confidence = net as f32 / evidence as f32;
+            guess
+        })
+        .collect();
+    // Stable: equal guesses keep the candidate order (Unicode first)
+    guesses.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    guesses
+}
+
+/// Decode `bytes` with the most likely charset
+pub fn decode_auto(bytes: &[u8]) -> (String, Guess) {
+    let guess = detect(bytes)[0];
+    (guess.decode(bytes), guess)
+}
+
+/// Words of a reading that look Vietnamese (have a non-ASCII letter)
+#[derive(Debug, Default)]
+struct Score {
+    valid: usize,
+    invalid: usize,
+}
+
+/// Score the reading `text` of `source`
+fn score(charset: Charset, source: &[u8], text: &str) -> Score {
+    let mut score = Score::default();
+    let source = source
+        .split(u8::is_ascii_whitespace)
+        .filter(|t| !t.is_empty());
+    for (source, word) in source.zip(text.split_ascii_whitespace()) {
+        if word.is_ascii() || (charset == Charset::Viqr && !viqr_evidence(source)) {
+            continue;
+        }
+        let letters = word.trim_matches(|c: char| !c.is_alphabetic());
+        if is_valid_word(letters) {
+            score.valid += 1;
+        } else {
+            score.invalid += 1;
+        }
+    }
+    score
+}
+
+/// Does a VIQR word show its marks beyond a trailing one? English text has
+/// words ending in "." or "?" that VIQR would read as tones.
+fn viqr_evidence(word: &[u8]) -> bool {
+    let mark_before_letter = word
+        .windows(2)
+        .any(|pair| b"'`?~.".contains(&pair[0]) && pair[1].is_ascii_alphabetic());
+    mark_before_letter
+        || word.iter().any(|b| b"^(+".contains(b))
+        || word
+            .get(..2)
+            .is_some_and(|start| start.eq_ignore_ascii_case(b"dd"))
+}
+
+/// The start of `bytes`, cut after a whitespace byte
+fn sample(bytes: &[u8]) -> &[u8] {
+    if bytes.len() <= SAMPLE_LEN {
+        return bytes;
+    }
+    let end = bytes[..SAMPLE_LEN]
+        .iter()
+        .rposition(u8::is_ascii_whitespace)
+        .unwrap_or(SAMPLE_LEN);
+    &bytes[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::encode_bytes;
+
+    const TEXT: &str = "Tiếng Việt là ngôn ngữ của người Việt, được viết bằng chữ Quốc ngữ. \
+        Đây là một đoạn văn bản để thử nhận dạng bảng mã.";
+
+    #[test]
+    fn test_detect_raw() {
+        for charset in Charset::ALL {
+            let bytes = encode_bytes(charset, TEXT).bytes;
+            let guesses = detect(&bytes);
+            let best = guesses[0];
+            assert_eq!(
+                (best.charset, best.misread),
+                (charset, false),
+                "{:?}",
+                guesses
+            );
+            assert!(best.confidence > 0.9, "{:?}", guesses);
+            assert!(
+                guesses[1..].iter().all(|g| g.confidence < 0.5),
+                "{:?}",
+                guesses
+            );
+            assert_eq!(decode_auto(&bytes).0, TEXT);
+        }
+    }
+
+    #[test]
+    fn test_detect_misread() {
+        // UTF-8 shown as Windows-1252 and saved again as UTF-8
+        let utf8 = TEXT
+            .as_bytes()
+            .iter()
+            .map(|&b| Charset::Unicode.byte_char(b));
+        let mojibake: String = utf8.collect();
+        assert!(mojibake.starts_with("Tiáº¿ng"));
+        let (text, guess) = decode_auto(mojibake.as_bytes());
+        assert_eq!((guess.charset, guess.misread), (Charset::Unicode, true));
+        assert_eq!(text, TEXT);
+
+        // TCVN3 text copied from a legacy font document
+        let pasted = Charset::Tcvn3.encode(TEXT);
+        let (text, guess) = decode_auto(pasted.as_bytes());
+        assert_eq!((guess.charset, guess.misread), (Charset::Tcvn3, true));
+        assert_eq!(text, TEXT);
+        assert_eq!(
+            guess.source_bytes(pasted.as_bytes()),
+            encode_bytes(Charset::Tcvn3, TEXT).bytes
+        );
+    }
+
+    #[test]
+    fn test_detect_without_evidence() {
+        let guesses = detect(b"Hello world. Is it ok? Yes.");
+        assert_eq!(guesses.len(), 1);
+        assert_eq!(guesses[0].charset, Charset::Unicode);
+        assert_eq!(guesses[0].confidence, 1.0);
+        assert_eq!(detect(b"")[0].charset, Charset::Unicode);
+    }
+
+    #[test]
+    fn test_display() {
+        let guess = Guess {
+            charset: Charset::Tcvn3,
+            misread: true,
+            confidence: 0.875,
+        };
+        assert_eq!(guess.to_string(), "TCVN3 (ABC) (read as Windows-1252), 88%");
+    }
+}
@@ -0,0 +1,713 @@
+//! Charset conversion of structured documents
+//!
+//! Legacy Vietnamese text mostly lives in documents: HTML pages with
+//! `<font face=".VnTime">` tags, RTF files with `\'xx` escapes, CSV
+//! exports. `convert_document` converts only their text runs and copies the
+//! markup (tags, attributes, RTF control words and tables, CSV quoting) as
+//! is, so the structure survives whatever bytes the target charset uses.
+
+use crate::charset::Charset;
+use crate::convert::{convert_bytes, decode, Encoded};
+use std::borrow::Cow;
+use unicode_normalization::UnicodeNormalization;
+
+/// Document format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Plain text, converted as a whole
+    #[default]
+    Text,
+    /// HTML or XML: text between tags, outside `<script>` and `<style>`
+    Html,
+    /// Rich Text Format: text runs, outside font tables and other
+    /// destinations
+    Rtf,
+    /// Delimiter-separated values: each field
+    Csv {
+        /// Field delimiter (`,` for CSV, tab for TSV)
+        delimiter: u8,
+    },
+}
+
+impl Format {
+    /// Format with the given ID ("text", "html", "rtf", "csv" or "tsv")
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id.to_ascii_lowercase().as_str() {
+            "text" | "txt" => Some(Format::Text),
+            "html" | "htm" | "xhtml" | "xml" => Some(Format::Html),
+            "rtf" => Some(Format::Rtf),
+            "csv" => Some(Format::Csv { delimiter: b',' }),
+            "tsv" => Some(Format::Csv { delimiter: b'\t' }),
+            _ => None,
+        }
+    }
+
+    /// Format of a file, from its extension
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        Self::from_id(path.extension()?.to_str()?)
+    }
+}
+
+/// Convert the text runs of a document from one charset to another
+pub fn convert_document(format: Format, bytes: &[u8], from: Charset, to: Charset) -> Encoded {
+    match format {
+        Format::Text => convert_bytes(bytes, from, to),
+        Format::Html => convert_html(bytes, from, to),
+        Format::Rtf => convert_rtf(bytes, from, to),
+        Format::Csv { delimiter } => convert_csv(bytes, delimiter, from, to),
+    }
+}
+
+/// Bytes of the text of a document, to detect its charset
+///
+/// RTF text is unescaped (`\'d6` → byte 0xD6); other formats keep their
+/// markup, which is ASCII and does not change the detection.
+pub fn text_bytes(format: Format, bytes: &[u8]) -> Cow<'_, [u8]> {
+    if format != Format::Rtf {
+        return Cow::Borrowed(bytes);
+    }
+    let run = RtfRun {
+        from: Charset::Unicode,
+        to: Charset::Unicode,
+        text: String::new(),
+        breaks: Vec::new(),
+        fallback: 1,
+        after_word: false,
+        raw: true,
+    };
+    Cow::Owned(parse_rtf(bytes, run).bytes)
+}
+
+fn append(out: &mut Encoded, run: &[u8], from: Charset, to: Charset) {
+    if run.is_empty() {
+        return;
+    }
+    let mut encoded = convert_bytes(run, from, to);
+    out.bytes.append(&mut encoded.bytes);
+    out.unmapped.append(&mut encoded.unmapped);
+}
+
+/// Elements whose content is not text
+const RAW_ELEMENTS: [&[u8]; 2] = [b"script", b"style"];
+
+fn starts_with_ignore_case(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes
+        .get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// End (exclusive) of the markup starting at `bytes[0] == b'<'`, or `None`
+/// if this `<` is text ("a < b")
+fn markup_end(bytes: &[u8]) -> Option<usize> {
+    if bytes.starts_with(b"<!--") {
+        let end = bytes[4..].windows(3).position(|w| w == b"-->");
+        return Some(end.map_or(bytes.len(), |end| end + 7));
+    }
+    if !bytes
+        .get(1)
+        .is_some_and(|&b| b.is_ascii_alphabetic() || b"/!?".contains(&b))
+    {
+        return None;
+    }
+    let mut quote = None;
+    for (i, &b) in bytes.iter().enumerate().skip(1) {
+        match (quote, b) {
+            (Some(q), _) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(b),
+            (None, b'>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    Some(bytes.len())
+}
+
+fn convert_html(bytes: &[u8], from: Charset, to: Charset) -> Encoded {
+    let mut out = Encoded::default();
+    let mut text_start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let Some(len) = (bytes[i] == b'<')
+            .then(|| markup_end(&bytes[i..]))
+            .flatten()
+        else {
+            i += 1;
+            continue;
+        };
+        append(&mut out, &bytes[text_start..i], from, to);
+        let tag = &bytes[i..i + len];
+        let mut end = i + len;
+
+        // The content of <script> and <style> is copied up to the end tag
+        let raw = RAW_ELEMENTS.iter().find(|name| {
+            starts_with_ignore_case(&tag[1..], name)
+                && !tag
+                    .get(name.len() + 1)
+                    .is_some_and(|b| b.is_ascii_alphanumeric())
+        });
+        if let Some(name) = raw {
+            let close = [b"</", *name].concat();
+            end += bytes[end..]
+                .windows(close.len())
+                .position(|w| w.eq_ignore_ascii_case(&close))
+                .unwrap_or(bytes.len() - end);
+        }
+        out.bytes.extend_from_slice(&bytes[i..end]);
+        i = end;
+        text_start = end;
+    }
+    append(&mut out, &bytes[text_start..], from, to);
+    out
+}
+
+/// RTF destinations that hold no document text
+const RTF_SKIPPED: &[&str] = &[
+    "colortbl",
+    "colorschememapping",
+    "datastore",
+    "fldinst",
+    "fonttbl",
+    "generator",
+    "info",
+    "latentstyles",
+    "listoverridetable",
+    "listtable",
+    "object",
+    "pict",
+    "rsidtbl",
+    "stylesheet",
+    "themedata",
+    "xmlnstbl",
+];
+
+/// Writer of RTF text runs
+struct RtfRun {
+    from: Charset,
+    to: Charset,
+
+    /// Text of the run, one char per byte for byte encodings (see
+    /// `convert::decode`)
+    text: String,
+
+    /// Line breaks met inside the run, which RTF ignores
+    breaks: Vec<u8>,
+
+    /// Fallback chars written after `\uN` (`\ucN`)
+    fallback: usize,
+
+    /// The output ends with a control word without its delimiter
+    after_word: bool,
+
+    /// Write only the bytes of the text runs (see `text_bytes`)
+    raw: bool,
+}
+
+impl RtfRun {
+    /// Add a text byte
+    fn push_byte(&mut self, byte: u8) {
+        if self.raw {
+            self.text.push(char::from(byte));
+            return;
+        }
+        let charset = if self.from.is_byte_encoding() {
+            self.from
+        } else {
+            // The ANSI code page of Unicode documents
+            Charset::Unicode
+        };
+        self.text.push(charset.byte_char(byte));
+    }
+
+    /// Add a line break, kept after the run
+    fn push_break(&mut self, out: &mut Encoded, byte: u8) {
+        if self.text.is_empty() {
+            self.copy(out, &[byte], false);
+        } else {
+            self.breaks.push(byte);
+        }
+    }
+
+    /// Copy markup to the output
+    fn copy(&mut self, out: &mut Encoded, markup: &[u8], word: bool) {
+        if self.raw {
+            return;
+        }
+        out.bytes.extend_from_slice(markup);
+        self.after_word = word && !markup.ends_with(b" ");
+    }
+
+    /// Write the run converted, then the line breaks
+    fn flush(&mut self, out: &mut Encoded) {
+        if self.raw {
+            for c in std::mem::take(&mut self.text).chars() {
+                match u8::try_from(c) {
+                    Ok(byte) => out.bytes.push(byte),
+                    Err(_) => out
+                        .bytes
+                        .extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                }
+            }
+            if out.bytes.last().is_some_and(|&b| b != b' ') {
+                out.bytes.push(b' ');
+            }
+            return;
+        }
+        let text = decode(self.from, &std::mem::take(&mut self.text));
+        if self.to.is_byte_encoding() {
+            for c in text.nfc() {
+                match self.to.bytes(c) {
+                    Some(bytes) => bytes.iter().for_each(|&b| self.push_rtf_byte(out, b)),
+                    None => self.push_rtf_char(out, c),
+                }
+            }
+        } else {
+            for c in self.to.encode(&text).chars() {
+                self.push_rtf_char(out, c);
+            }
+        }
+        let breaks = std::mem::take(&mut self.breaks);
+        if !breaks.is_empty() {
+            self.copy(out, &breaks, false);
+        }
+    }
+
+    fn push_text(&mut self, out: &mut Encoded, text: &[u8]) {
+        // "\b0" followed by "x" would read as "\b0x"
+        let starts_word = text
+            .first()
+            .is_some_and(|b| b.is_ascii_alphanumeric() || *b == b' ');
+        if self.after_word && starts_word {
+            out.bytes.push(b' ');
+        }
+        self.copy(out, text, false);
+    }
+
+    fn push_rtf_byte(&mut self, out: &mut Encoded, byte: u8) {
+        match byte {
+            0x20..=0x7E => self.push_rtf_char(out, char::from(byte)),
+            _ => self.push_text(out, format!("\\'{:02x}", byte).as_bytes()),
+        }
+    }
+
+    fn push_rtf_char(&mut self, out: &mut Encoded, c: char) {
+        match c {
+            '\\' | '{' | '}' => self.push_text(out, &[b'\\', c as u8]),
+            ' '..='~' => self.push_text(out, &[c as u8]),
+            _ => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    // \uN takes a signed 16-bit number
+                    let mut escape = format!("\\u{}", *unit as i16);
+                    escape.extend(std::iter::repeat_n('?', self.fallback));
+                    if self.fallback == 0 {
+                        escape.push(' ');
+                    }
+                    self.push_text(out, escape.as_bytes());
+                }
+            }
+        }
+    }
+}
+
+/// Length of a `\'xx` escape at the start of `bytes` and its byte
+fn rtf_hex(bytes: &[u8]) -> Option<(usize, u8)> {
+    let hex = std::str::from_utf8(bytes.get(2..4)?).ok()?;
+    let byte = u8::from_str_radix(hex, 16).ok()?;
+    bytes.starts_with(b"\\'").then_some((4, byte))
+}
+
+/// Control word at the start of `bytes` (after the backslash): name,
+/// parameter and length, including the space that ends it
+fn rtf_control_word(bytes: &[u8]) -> (&str, Option<i32>, usize) {
+    let name_len = bytes.iter().take_while(|b| b.is_ascii_alphabetic()).count();
+    let mut len = name_len;
+    if bytes.get(len) == Some(&b'-') {
+        len += 1;
+    }
+    let digits = bytes[len..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    let parameter = if digits > 0 {
+        len += digits;
+        std::str::from_utf8(&bytes[name_len..len])
+            .ok()
+            .and_then(|n| n.parse().ok())
+    } else {
+        len = name_len;
+        None
+    };
+    if bytes.get(len) == Some(&b' ') {
+        len += 1;
+    }
+    let name = std::str::from_utf8(&bytes[..name_len]).unwrap_or_default();
+    (name, parameter, len)
+}
+
+fn convert_rtf(bytes: &[u8], from: Charset, to: Charset) -> Encoded {
+    let run = RtfRun {
+        from,
+        to,
+        text: String::new(),
+        breaks: Vec::new(),
+        fallback: 1,
+        after_word: false,
+        raw: false,
+    };
+    parse_rtf(bytes, run)
+}
+
+fn parse_rtf(bytes: &[u8], mut run: RtfRun) -> Encoded {
+    let mut out = Encoded::default();
+    // Skipped destination flags of the enclosing groups
+    let mut groups = Vec::new();
+    let mut skipped = false;
+    // Fallback chars still to drop after a \uN
+    let mut drop = 0;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        match rest[0] {
+            b'{' | b'}' => {
+                run.flush(&mut out);
+                if rest[0] == b'{' {
+                    groups.push(skipped);
+                } else {
+                    skipped = groups.pop().unwrap_or(false);
+                }
+                run.copy(&mut out, &rest[..1], false);
+                drop = 0;
+                i += 1;
+            }
+            b'\r' | b'\n' => {
+                run.push_break(&mut out, rest[0]);
+                i += 1;
+            }
+            b'\\' if rest.get(1).is_some_and(u8::is_ascii_alphabetic) => {
+                let (name, parameter, len) = rtf_control_word(&rest[1..]);
+                let len = len + 1;
+                match (name, parameter) {
+                    ("u", Some(code)) if !skipped => {
+                        let unit = code as u16;
+                        let c = char::decode_utf16([unit]).next().and_then(Result::ok);
+                        run.text.push(c.unwrap_or(char::REPLACEMENT_CHARACTER));
+                        drop = run.fallback;
+                    }
+                    ("bin", Some(count)) => {
+                        run.flush(&mut out);
+                        let end = (i + len + count.max(0) as usize).min(bytes.len());
+                        run.copy(&mut out, &bytes[i..end], false);
+                        i = end;
+                        continue;
+                    }
+                    _ => {
+                        run.flush(&mut out);
+                        if name == "uc" {
+                            run.fallback = parameter.unwrap_or(1).max(0) as usize;
+                        }
+                        skipped |= RTF_SKIPPED.contains(&name);
+                        run.copy(&mut out, &rest[..len], true);
+                    }
+                }
+                i += len;
+            }
+            b'\\' if !skipped && rest.get(1).is_some_and(|b| b"\\{}".contains(b)) => {
+                if drop > 0 {
+                    drop -= 1;
+                } else {
+                    run.text.push(char::from(rest[1]));
+                }
+                i += 2;
+            }
+            b'\\' => match rtf_hex(rest) {
+                Some((len, byte)) if !skipped => {
+                    if drop > 0 {
+                        drop -= 1;
+                    } else {
+                        run.push_byte(byte);
+                    }
+                    i += len;
+                }
+                _ => {
+                    // Control symbol (\~, \-, \*...), or an escape in a
+                    // skipped destination
+                    run.flush(&mut out);
+                    skipped |= rest.get(1) == Some(&b'*');
+                    let len = rtf_hex(rest).map_or(rest.len().min(2), |(len, _)| len);
+                    run.copy(&mut out, &rest[..len], false);
+                    i += len;
+                }
+            },
+            _ if skipped => {
+                run.copy(&mut out, &rest[..1], false);
+                i += 1;
+            }
+            byte => {
+                if drop > 0 {
+                    drop -= 1;
+                } else {
+                    run.push_byte(byte);
+                }
+                i += 1;
+            }
+        }
+    }
+    run.flush(&mut out);
+    out
+}
+
+fn convert_csv(bytes: &[u8], delimiter: u8, from: Charset, to: Charset) -> Encoded {
+    let mut out = Encoded::default();
+    let mut i = 0;
+    while i <= bytes.len() {
+        // One field: quoted ("a ""b"""), or up to the next separator
+        let quoted = bytes.get(i) == Some(&b'"');
+        let mut field = Vec::new();
+        if quoted {
+            i += 1;
+            while i < bytes.len() {
+                match (bytes[i], bytes.get(i + 1)) {
+                    (b'"', Some(b'"')) => {
+                        field.push(b'"');
+                        i += 2;
+                    }
+                    (b'"', _) => {
+                        i += 1;
+                        break;
+                    }
+                    (byte, _) => {
+                        field.push(byte);
+                        i += 1;
+                    }
+                }
+            }
+        }
+        let end = bytes[i..]
+            .iter()
+            .position(|&b| b == delimiter || b == b'\n' || b == b'\r')
+            .map_or(bytes.len(), |end| i + end);
+        field.extend_from_slice(&bytes[i..end]);
+        i = end;
+
+        let mut value = Encoded::default();
+        append(&mut value, &field, from, to);
+        out.unmapped.append(&mut value.unmapped);
+        let special = |b: &u8| *b == delimiter || b"\"\r\n".contains(b);
+        if quoted || value.bytes.iter().any(special) {
+            out.bytes.push(b'"');
+            for byte in value.bytes {
+                if byte == b'"' {
+                    out.bytes.push(b'"');
+                }
+                out.bytes.push(byte);
+            }
+            out.bytes.push(b'"');
+        } else {
+            out.bytes.append(&mut value.bytes);
+        }
+
+        // The separator, if any
+        match bytes.get(i) {
+            Some(b'\r') if bytes.get(i + 1) == Some(&b'\n') => {
+                out.bytes.extend_from_slice(b"\r\n");
+                i += 2;
+                if i == bytes.len() {
+                    break;
+                }
+            }
+            Some(&separator) => {
+                out.bytes.push(separator);
+                i += 1;
+                if i == bytes.len() && separator != delimiter {
+                    break;
+                }
+            }
+            None => break,
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::encode_bytes;
+
+    fn tcvn3(text: &str) -> Vec<u8> {
+        encode_bytes(Charset::Tcvn3, text).bytes
+    }
+
+    #[test]
+    fn test_html() {
+        let page = [
+            &b"<html><head><title>"[..],
+            &tcvn3("Trang chủ"),
+            b"</title><style>p { font-family: \".VnTime\" }</style>\n",
+            b"<script>if (a < b) alert('\xD6');</script></head>\n",
+            b"<body><!-- \xD6 --><p class=\"vn\" title='a > b'><font face=\".VnTime\">",
+            &tcvn3("Tiếng Việt"),
+            b"</font> &amp; ",
+            &tcvn3("chữ"),
+            b" < 3<br/></p></body></html>",
+        ]
+        .concat();
+        let converted = convert_document(Format::Html, &page, Charset::Tcvn3, Charset::Unicode);
+        let expected = [
+            "<html><head><title>Trang chủ</title><style>p { font-family: \".VnTime\" }</style>\n"
+                .as_bytes(),
+            b"<script>if (a < b) alert('\xD6');</script></head>\n",
+            b"<body><!-- \xD6 --><p class=\"vn\" title='a > b'><font face=\".VnTime\">",
+            "Tiếng Việt</font> &amp; chữ < 3<br/></p></body></html>".as_bytes(),
+        ]
+        .concat();
+        assert_eq!(converted.bytes, expected);
+    }
+
+    #[test]
+    fn test_html_keeps_markup_bytes() {
+        let page = "<p title=\"Việt\">Việt</p>";
+        let converted = convert_document(
+            Format::Html,
+            page.as_bytes(),
+            Charset::Unicode,
+            Charset::VniWindows,
+        );
+        assert_eq!(
+            converted.bytes,
+            ["<p title=\"Việt\">".as_bytes(), b"Vie\xE4t</p>"].concat()
+        );
+        assert!(converted.unmapped.is_empty());
+    }
+
+    #[test]
+    fn test_rtf() {
+        let document = b"{\\rtf1\\ansi\\ansicpg1252\\deff0{\\fonttbl{\\f0\\fnil VNI-Times;}{\\f1 Ti\\'e1m;}}\n\
+            {\\*\\generator Ri\\'e1ched20;}\\f0\\fs24 Tie\\'e1ng Vie\\'e4t\\par\n\
+            ch\\'f6\\'f5 \\{\\b ca\\'f9i\\b0\\} \\u7879?\\par\n}";
+        let converted =
+            convert_document(Format::Rtf, document, Charset::VniWindows, Charset::Unicode);
+        assert_eq!(
+            String::from_utf8(converted.bytes).unwrap(),
+            "{\\rtf1\\ansi\\ansicpg1252\\deff0{\\fonttbl{\\f0\\fnil VNI-Times;}{\\f1 Ti\\'e1m;}}\n\
+            {\\*\\generator Ri\\'e1ched20;}\\f0\\fs24 Ti\\u7871?ng Vi\\u7879?t\\par\n\
+            ch\\u7919? \\{\\b c\\u225?i\\b0\\} \\u7879?\\par\n}"
+        );
+
+        // Back to VNI Windows, with the same line breaks
+        let back = convert_document(
+            Format::Rtf,
+            document,
+            Charset::VniWindows,
+            Charset::VniWindows,
+        );
+        assert_eq!(
+            String::from_utf8(back.bytes).unwrap(),
+            String::from_utf8(document.to_vec())
+                .unwrap()
+                .replace("\\u7879?", "e\\'e4")
+        );
+    }
+
+    #[test]
+    fn test_rtf_line_break_inside_word() {
+        let document = b"{\\f0 Vie\r\n\\'e4t}";
+        let converted =
+            convert_document(Format::Rtf, document, Charset::VniWindows, Charset::Tcvn3);
+        assert_eq!(converted.bytes, b"{\\f0 Vi\\'d6t\r\n}".to_vec());
+
+        let converted = convert_document(
+            Format::Rtf,
+            b"{\\uc0\\u7879 x}",
+            Charset::Unicode,
+            Charset::Viqr,
+        );
+        assert_eq!(converted.bytes, b"{\\uc0 e^.x}".to_vec());
+    }
+
+    #[test]
+    fn test_csv() {
+        let table = [
+            &b"id,name,note\r\n1,"[..],
+            &tcvn3("Hà Nội"),
+            b",\"",
+            &tcvn3("thủ đô, \"\"lớn\"\""),
+            b"\"\r\n2,,\n",
+        ]
+        .concat();
+        let converted = convert_document(
+            Format::Csv { delimiter: b',' },
+            &table,
+            Charset::Tcvn3,
+            Charset::Unicode,
+        );
+        assert_eq!(
+            String::from_utf8(converted.bytes).unwrap(),
+            "id,name,note\r\n1,Hà Nội,\"thủ đô, \"\"lớn\"\"\"\r\n2,,\n"
+        );
+
+        // NCR text holds the delimiter: the field gets quoted
+        let converted = convert_document(
+            Format::Csv { delimiter: b';' },
+            "a;Việt\n".as_bytes(),
+            Charset::Unicode,
+            Charset::NcrDecimal,
+        );
+        assert_eq!(converted.bytes, b"a;\"Vi&#7879;t\"\n".to_vec());
+        let converted = convert_document(
+            Format::Csv { delimiter: b';' },
+            b"a;;\"\"",
+            Charset::Unicode,
+            Charset::Unicode,
+        );
+        assert_eq!(converted.bytes, b"a;;\"\"".to_vec());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let documents = [
+            (
+                Format::Html,
+                "<p title=\"x\">Tiếng <b>Việt</b> &amp; chữ</p>\n",
+            ),
+            (
+                Format::Rtf,
+                "{\\rtf1\\ansi{\\fonttbl{\\f0 Arial;}}\\f0 Ti\\u7871?ng \\b Vi\\u7879?t\\b0\\par}",
+            ),
+            (
+                Format::Csv { delimiter: b',' },
+                "id,name\r\n1,\"Đà Nẵng, Việt Nam\"\r\n",
+            ),
+        ];
+        for (format, document) in documents {
+            for charset in Charset::ALL {
+                let encoded =
+                    convert_document(format, document.as_bytes(), Charset::Unicode, charset);
+                assert!(encoded.unmapped.is_empty());
+                let back = convert_document(format, &encoded.bytes, charset, Charset::Unicode);
+                assert_eq!(
+                    String::from_utf8(back.bytes).unwrap(),
+                    document,
+                    "{}",
+                    charset
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_text_bytes() {
+        let document = b"{\\rtf1{\\fonttbl{\\f0 .VnTime;}}\\f0 Vi\\'d6t\\par Nam}";
+        assert_eq!(text_bytes(Format::Rtf, document), &b"Vi\xD6t Nam "[..]);
+        assert_eq!(text_bytes(Format::Html, b"<b>x</b>"), &b"<b>x</b>"[..]);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Format::from_id("HTM"), Some(Format::Html));
+        assert_eq!(
+            Format::from_path(std::path::Path::new("data/table.tsv")),
+            Some(Format::Csv { delimiter: b'\t' })
+        );
+        assert_eq!(Format::from_path(std::path::Path::new("README")), None);
+    }
+}
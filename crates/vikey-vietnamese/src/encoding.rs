@@ -0,0 +1,241 @@
+//! Output encoding stage (bảng mã)
+//!
+//! Input methods always produce Unicode. `OutputEncoding` runs last in the
+//! engine pipeline and rewrites the text of every `Action` into the
+//! selected `Charset`. Backspace counts are converted as well: "ệ" is one
+//! char in Unicode but two in VNI Windows ("eä"), so replacing it takes two
+//! backspaces.
+
+use crate::charset::Charset;
+use vikey_core::config::{OptionSpec, OptionValue};
+use vikey_core::transform::{order, Middleware, StageContext, StageSession};
+use vikey_core::{Action, PreeditSegment};
+
+/// ID of the output encoding stage
+pub const ID: &str = "encoding";
+
+/// Name of the charset option
+const CHARSET: &str = "charset";
+
+/// Pipeline stage writing the engine's output in a Vietnamese charset
+///
+/// The charset comes from the `charset` option (`[engine.stages.encoding]`
+/// in the config file) and can be overridden for the focused context only
+/// with `Engine::set_session_option("encoding", "charset", ...)`, like
+/// picking a different "bảng mã" for an application that uses legacy fonts.
+#[derive(Debug, Clone, Default)]
+pub struct OutputEncoding {
+    /// Charset from the configuration
+    charset: Charset,
+
+    /// Charset chosen for the focused context only
+    session: Option<Charset>,
+}
+
+impl OutputEncoding {
+    /// Create the stage writing `charset`
+    pub fn new(charset: Charset) -> Self {
+        Self {
+            charset,
+            session: None,
+        }
+    }
+
+    /// Charset used for the focused context
+    pub fn charset(&self) -> Charset {
+        self.session.unwrap_or(self.charset)
+    }
+}
+
+fn parse_charset(value: &OptionValue) -> Result<Charset, String> {
+    value
+        .as_str()
+        .and_then(Charset::from_id)
+        .ok_or_else(|| format!("unknown charset {}", value))
+}
+
+/// Backspaces needed to delete the last `count` chars of `before` once
+/// encoded; anything beyond the composed word counts one per char
+fn backspaces(charset: Charset, before: &str, count: usize) -> usize {
+    let len = before.chars().count();
+    let deleted: String = before.chars().skip(len.saturating_sub(count)).collect();
+    charset.encoded_len(&deleted) + count.saturating_sub(len)
+}
+
+/// Encode preedit text, moving the cursor and segment offsets with it
+fn encode_preedit(
+    charset: Charset,
+    text: &str,
+    cursor: usize,
+    segments: Vec<PreeditSegment>,
+) -> Action {
+    // offsets[i]: encoded length of the first i chars
+    let mut encoded = String::new();
+    let mut offsets = vec![0];
+    for c in text.chars() {
+        charset.encode_char(c, &mut encoded);
+        offsets.push(encoded.chars().count());
+    }
+    let offset = |i: usize| offsets[i.min(offsets.len() - 1)];
+    Action::UpdatePreedit {
+        cursor: offset(cursor),
+        segments: segments
+            .into_iter()
+            .map(|segment| PreeditSegment {
+                start: offset(segment.start),
+                end: offset(segment.end),
+                style: segment.style,
+            })
+            .collect(),
+        text: encoded,
+    }
+}
+
+impl Middleware for OutputEncoding {
+    fn id(&self) -> &str {
+        ID
+    }
+
+    fn order(&self) -> i32 {
+        order::ENCODING
+    }
+
+    fn process(&mut self, action: Action, context: &StageContext) -> Action {
+        let charset = self.charset();
+        if charset == Charset::Unicode {
+            return action;
+        }
+        match action {
+            Action::Commit(text) => Action::Commit(charset.encode(&text)),
+            Action::Replace {
+                backspace_count,
+                text,
+            } => Action::Replace {
+                backspace_count: backspaces(charset, context.before, backspace_count),
+                text: charset.encode(&text),
+            },
+            Action::UpdatePreedit {
+                text,
+                cursor,
+                segments,
+            } => encode_preedit(charset, &text, cursor, segments),
+            action => action,
+        }
+    }
+
+    fn options(&self) -> Vec<OptionSpec> {
+        let ids: Vec<&str> = Charset::ALL.iter().map(|charset| charset.id()).collect();
+        vec![OptionSpec::choice(
+            CHARSET,
+            &ids,
+            Charset::Unicode.id(),
+            "Charset of the text written to applications (bảng mã)",
+        )]
+    }
+
+    fn option(&self, key: &str) -> Option<OptionValue> {
+        (key == CHARSET).then(|| self.charset.id().into())
+    }
+
+    fn set_option(&mut self, key: &str, value: &OptionValue) -> Result<(), String> {
+        match key {
+            CHARSET => self.charset = parse_charset(value)?,
+            _ => return Err(format!("unknown option '{}'", key)),
+        }
+        Ok(())
+    }
+
+    fn set_session_option(&mut self, key: &str, value: Option<&OptionValue>) -> Result<(), String> {
+        match key {
+            CHARSET => self.session = value.map(parse_charset).transpose()?,
+            _ => return Err(format!("unknown option '{}'", key)),
+        }
+        Ok(())
+    }
+
+    fn take_session(&mut self) -> Option<StageSession> {
+        let charset = self.session.take()?;
+        Some(Box::new(charset))
+    }
+
+    fn restore_session(&mut self, session: StageSession) {
+        if let Ok(charset) = session.downcast::<Charset>() {
+            self.session = Some(*charset);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vikey_core::CompositionMode;
+
+    fn context(before: &str) -> StageContext<'_> {
+        StageContext {
+            key: None,
+            before,
+            composing: "",
+            mode: CompositionMode::Direct,
+        }
+    }
+
+    #[test]
+    fn test_replace_counts_encoded_chars() {
+        let mut stage = OutputEncoding::new(Charset::VniWindows);
+        let action = Action::Replace {
+            backspace_count: 2,
+            text: "ệt".to_string(),
+        };
+        assert_eq!(
+            stage.process(action, &context("viêt")),
+            Action::Replace {
+                backspace_count: 3,
+                text: "eät".to_string(),
+            }
+        );
+
+        // Text before the composed word counts one backspace per char
+        assert_eq!(backspaces(Charset::VniWindows, "ê", 3), 4);
+        assert_eq!(backspaces(Charset::Tcvn3, "viêt", 2), 2);
+    }
+
+    #[test]
+    fn test_preedit_offsets() {
+        let mut stage = OutputEncoding::new(Charset::Viqr);
+        let action = stage.process(Action::preedit("việt".to_string()), &context(""));
+        assert_eq!(
+            action,
+            Action::UpdatePreedit {
+                text: "vie^.t".to_string(),
+                cursor: 6,
+                segments: vec![PreeditSegment::underline(0, 6)],
+            }
+        );
+    }
+
+    #[test]
+    fn test_session_charset() {
+        let mut stage = OutputEncoding::default();
+        assert_eq!(
+            stage.process(Action::Commit("ố".to_string()), &context("")),
+            Action::Commit("ố".to_string())
+        );
+
+        stage
+            .set_session_option(CHARSET, Some(&"tcvn3".into()))
+            .unwrap();
+        assert_eq!(stage.charset(), Charset::Tcvn3);
+        assert_eq!(stage.option(CHARSET), Some("unicode".into()));
+        assert!(stage
+            .set_session_option(CHARSET, Some(&"vps".into()))
+            .is_err());
+
+        let session = stage.take_session().unwrap();
+        assert_eq!(stage.charset(), Charset::Unicode);
+        stage.restore_session(session);
+        assert_eq!(stage.charset(), Charset::Tcvn3);
+
+        stage.set_session_option(CHARSET, None).unwrap();
+        assert!(stage.take_session().is_none());
+    }
+}
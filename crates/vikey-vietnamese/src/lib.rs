@@ -1,15 +1,29 @@
 // lib.rs - Vietnamese Language Support for Vikey
 
+pub mod auto_restore;
+pub mod charset;
+pub mod compose;
+pub mod convert;
+pub mod detect;
+pub mod document;
+pub mod encoding;
 pub mod lookup;
 pub mod methods;
 pub mod plugin;
 pub mod rules;
+pub mod schema;
 pub mod syllable;
 pub mod types;
 pub mod validation;
 
 // Re-exports
+pub use auto_restore::{AutoRestore, AutoRestoreMode};
+pub use charset::Charset;
+pub use compose::{Composer, KeyRole, Keymap, Placement, Repeat, UoHorn};
+pub use encoding::OutputEncoding;
 pub use lookup::VietnameseLookup;
 pub use plugin::VietnamesePlugin;
+pub use rules::ToneStyle;
+pub use schema::{Schema, SchemaError, SchemaFormat, SchemaMethod};
 pub use syllable::{Modification, Syllable, Tone};
 pub use types::{MarkType, ToneType, TransformEffect, Transformation, WordForm};
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use vikey_core::traits::LookupProvider;
 
 /// Vietnamese character information
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct VietCharInfo {
     pub is_vowel: bool,
     pub is_consonant: bool,
@@ -16,21 +16,6 @@ pub struct VietCharInfo {
     pub vni_double_index: u8, // VNI: 6,7,8,9
 }
 
-impl Default for VietCharInfo {
-    fn default() -> Self {
-        Self {
-            is_vowel: false,
-            is_consonant: false,
-            is_separator: false,
-            is_soft_separator: false,
-            vowel_index: 0,
-            tone_index: 0,
-            is_breve: false,
-            vni_double_index: 0,
-        }
-    }
-}
-
 /// Vietnamese lookup provider for Telex input method
 pub struct VietnameseLookup {
     /// Fast lookup table for ASCII characters
@@ -98,9 +83,9 @@ impl VietnameseLookup {
         lookup.ascii_table[b'5' as usize].tone_index = 5; // Nặng
 
         // VNI double marks
-        lookup.ascii_table[b'6' as usize].vni_double_index = 1; // ă
-        lookup.ascii_table[b'7' as usize].vni_double_index = 2; // â
-        lookup.ascii_table[b'8' as usize].vni_double_index = 3; // ơ
+        lookup.ascii_table[b'6' as usize].vni_double_index = 1; // â, ê, ô
+        lookup.ascii_table[b'7' as usize].vni_double_index = 2; // ơ, ư
+        lookup.ascii_table[b'8' as usize].vni_double_index = 3; // ă
         lookup.ascii_table[b'9' as usize].vni_double_index = 4; // đ
 
         // Clear Telex tone marks
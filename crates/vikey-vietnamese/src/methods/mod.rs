@@ -6,6 +6,6 @@ pub mod viqr;
 pub mod vni;
 
 pub use telex::TelexMethod;
-pub use telex_v2::TelexMethodV2;
-pub use viqr::VIQRMethod;
-pub use vni::VNIMethod;
+pub use telex_v2::{TelexKeymap, TelexMethodV2};
+pub use viqr::{VIQRMethod, ViqrKeymap};
+pub use vni::{VNIMethod, VniKeymap};
@@ -1,61 +1,162 @@
-// methods/viqr.rs - VIQR input method
+// methods/viqr.rs - VIQR input method with history-based processing
 
-use vikey_core::traits::InputMethodTrait;
-use vikey_core::traits::LookupProvider;
-use vikey_core::types::Action;
-use vikey_core::InputBuffer;
+use crate::compose::{Composer, KeyRole, Keymap, Placement, Repeat};
+use crate::syllable::{Modification, Tone};
 
-/// VIQR Input Method
-pub struct VIQRMethod {}
+/// VIQR keys: the marks of VIQR text
+///
+/// Marks follow the letter they belong to, as in VIQR text: "a^" → "â",
+/// "a(" → "ă", "o+" → "ơ", "dd" → "đ", then the tone right after the vowel
+/// ("vie^.t" → "việt"). Since tones are also punctuation, a tone key only
+/// marks a vowel it directly follows:
+///
+/// - after a consonant, a digit or a word that is not Vietnamese it is
+///   punctuation ("Nam." and "ok?" end the word);
+/// - on a vowel that has a tone already, `'`, `?` and `.` end the word
+///   ("chào." stays "chào."), `` ` `` and `~` replace the tone;
+/// - the same mark typed twice in a row takes it off and types the key
+///   ("a''" → "a'");
+/// - a backslash types the next key as-is: "ddi\." gives "đi." and not "đị".
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ViqrKeymap;
 
-impl VIQRMethod {
-    pub fn new() -> Self {
-        Self {}
+impl Keymap for ViqrKeymap {
+    fn id(&self) -> &str {
+        "viqr"
+    }
+
+    fn name(&self) -> &str {
+        "VIQR"
     }
-}
 
-impl Default for VIQRMethod {
-    fn default() -> Self {
-        Self::new()
+    fn role(&self, key: char) -> Option<KeyRole<'_>> {
+        let role = match key {
+            '\'' => KeyRole::Tone(Tone::Acute),    // sắc
+            '`' => KeyRole::Tone(Tone::Grave),     // huyền
+            '?' => KeyRole::Tone(Tone::HookAbove), // hỏi
+            '~' => KeyRole::Tone(Tone::Tilde),     // ngã
+            '.' => KeyRole::Tone(Tone::Underdot),  // nặng
+            '^' => KeyRole::Mark(&[Modification::Circumflex]),
+            '(' => KeyRole::Mark(&[Modification::Breve]),
+            '+' => KeyRole::Mark(&[Modification::Horn]),
+            'd' | 'D' => KeyRole::Mark(&[Modification::DStroke]),
+            '\\' => KeyRole::Escape,
+            _ => return None,
+        };
+        Some(role)
+    }
+
+    fn placement(&self) -> Placement {
+        Placement::AfterLetter
+    }
+
+    fn repeat(&self) -> Repeat {
+        Repeat::TakesOffRightAway
     }
 }
 
-impl InputMethodTrait for VIQRMethod {
-    fn name(&self) -> &str {
-        "VIQR"
+/// VIQR Input Method with history-based processing
+pub type VIQRMethod = Composer<ViqrKeymap>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup::VietnameseLookup;
+    use vikey_core::traits::InputMethodTrait;
+    use vikey_core::types::Action;
+    use vikey_core::{InputBuffer, KeyEvent};
+
+    fn type_word(method: &mut VIQRMethod, keys: &str) -> String {
+        let mut buffer = InputBuffer::new();
+        let lookup = VietnameseLookup::new_telex();
+        for c in keys.chars() {
+            method.process(&KeyEvent::from_char(c), &mut buffer, &lookup);
+        }
+        method.output()
     }
 
-    fn id(&self) -> &str {
-        "viqr"
+    #[test]
+    fn test_viqr_marks() {
+        for (keys, expected) in [
+            ("a^", "â"),
+            ("e^", "ê"),
+            ("o^", "ô"),
+            ("a(", "ă"),
+            ("o+", "ơ"),
+            ("u+", "ư"),
+            ("dd", "đ"),
+            ("DD", "Đ"),
+            ("Dd", "Đ"),
+            ("tuo+ng", "tương"),
+            ("tu+o+ng", "tương"),
+            ("quo+", "quơ"),
+            ("vie^.t", "việt"),
+            ("ddu+o+`ng", "đường"),
+        ] {
+            assert_eq!(
+                type_word(&mut VIQRMethod::new(), keys),
+                expected,
+                "{}",
+                keys
+            );
+        }
     }
 
-    fn process(
-        &mut self,
-        key: char,
-        buffer: &mut InputBuffer,
-        _lookup: &dyn LookupProvider,
-    ) -> Action {
-        // TODO: Implement VIQR logic
-        buffer.push(key, key.is_lowercase());
-        Action::Commit(key.to_string())
+    #[test]
+    fn test_viqr_escape() {
+        let mut method = VIQRMethod::new();
+        let mut buffer = InputBuffer::new();
+        assert_eq!(type_word(&mut method, "ddi\\"), "đi\\");
+        assert!(method.escape_pending());
+        assert_eq!(type_word(&mut method, "."), "đi.");
+        assert!(!method.escape_pending());
+        assert_eq!(method.raw_input().unwrap(), "ddi\\.");
+
+        method.process_backspace(&mut buffer);
+        assert!(buffer.last_is_escape());
+        assert_eq!(method.output(), "đi\\");
+
+        assert_eq!(type_word(&mut VIQRMethod::new(), "a\\\\"), "a\\");
+        assert_eq!(type_word(&mut VIQRMethod::new(), "a''"), "a'");
+        assert_eq!(type_word(&mut VIQRMethod::new(), "a^^"), "a^");
     }
 
-    fn process_backspace(&mut self, buffer: &mut InputBuffer) -> Action {
-        if buffer.pop().is_some() {
-            Action::Replace {
-                backspace_count: 1,
-                text: String::new(),
+    #[test]
+    fn test_viqr_punctuation_ends_word() {
+        let lookup = VietnameseLookup::new_telex();
+        for keys in ["Nam", "ok", "cha`o", "2024"] {
+            let mut method = VIQRMethod::new();
+            let mut buffer = InputBuffer::new();
+            type_word(&mut method, keys);
+            for key in ['.', '?', '\''] {
+                assert!(!method.marks_word(key), "{} {}", keys, key);
             }
-        } else {
-            Action::DoNothing
+            let action = method.process(&KeyEvent::from_char('.'), &mut buffer, &lookup);
+            assert_eq!(action, Action::PassThrough, "{}", keys);
         }
     }
 
-    fn reset(&mut self) {}
-    fn can_undo(&self, _buffer: &InputBuffer) -> bool {
-        false
-    }
-    fn undo(&mut self, _buffer: &mut InputBuffer) -> Action {
-        Action::DoNothing
+    #[test]
+    fn test_viqr_resume() {
+        let mut method = VIQRMethod::new();
+        let mut buffer = InputBuffer::new();
+        let lookup = VietnameseLookup::new_telex();
+
+        assert_eq!(method.resume("Tiếng Việt", &mut buffer), 4);
+        assert_eq!(method.raw_input().unwrap(), "Vie^.t");
+
+        let action = method.process(&KeyEvent::from_char('a'), &mut buffer, &lookup);
+        assert!(matches!(
+            action,
+            Action::Replace {
+                backspace_count: 4,
+                ..
+            }
+        ));
+
+        method.reset();
+        assert_eq!(method.resume("đường", &mut buffer), 5);
+        assert_eq!(method.raw_input().unwrap(), "ddu+o+`ng");
+        assert_eq!(method.resume("hello ", &mut buffer), 0);
     }
 }
@@ -1,61 +1,157 @@
-// methods/vni.rs - VNI input method
+// methods/vni.rs - VNI input method with history-based processing
 
-use vikey_core::traits::InputMethodTrait;
-use vikey_core::traits::LookupProvider;
-use vikey_core::types::Action;
-use vikey_core::InputBuffer;
+use crate::compose::{Composer, KeyRole, Keymap};
+use crate::syllable::{Modification, Tone};
 
-/// VNI Input Method
-pub struct VNIMethod {}
+/// VNI keys: digits after the letters
+///
+/// 1-5 add the tones and 0 takes the tone off; 6 → â, ê, ô; 7 → ư, ơ;
+/// 8 → ă; 9 → đ. The digits may come in any order and anywhere in the word:
+/// "vie65t", "viet65" and "vie6t5" all give "việt". Typing a mark a second
+/// time takes it off and types the digit ("a11" → "a1").
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VniKeymap;
 
-impl VNIMethod {
-    pub fn new() -> Self {
-        Self {}
+impl Keymap for VniKeymap {
+    fn id(&self) -> &str {
+        "vni"
+    }
+
+    fn name(&self) -> &str {
+        "VNI"
     }
-}
 
-impl Default for VNIMethod {
-    fn default() -> Self {
-        Self::new()
+    fn role(&self, key: char) -> Option<KeyRole<'_>> {
+        let role = match key {
+            '1' => KeyRole::Tone(Tone::Acute),     // sắc
+            '2' => KeyRole::Tone(Tone::Grave),     // huyền
+            '3' => KeyRole::Tone(Tone::HookAbove), // hỏi
+            '4' => KeyRole::Tone(Tone::Tilde),     // ngã
+            '5' => KeyRole::Tone(Tone::Underdot),  // nặng
+            '0' => KeyRole::RemoveTone,
+            '6' => KeyRole::Mark(&[Modification::Circumflex]),
+            '7' => KeyRole::Mark(&[Modification::Horn]),
+            '8' => KeyRole::Mark(&[Modification::Breve]),
+            '9' => KeyRole::Mark(&[Modification::DStroke]),
+            _ => return None,
+        };
+        Some(role)
     }
 }
 
-impl InputMethodTrait for VNIMethod {
-    fn name(&self) -> &str {
-        "VNI"
+/// VNI Input Method with history-based processing
+pub type VNIMethod = Composer<VniKeymap>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup::VietnameseLookup;
+    use vikey_core::traits::InputMethodTrait;
+    use vikey_core::types::Action;
+    use vikey_core::{InputBuffer, KeyEvent};
+
+    fn type_word(method: &mut VNIMethod, keys: &str) -> String {
+        let mut buffer = InputBuffer::new();
+        let lookup = VietnameseLookup::new_vni();
+        for c in keys.chars() {
+            method.process(&KeyEvent::from_char(c), &mut buffer, &lookup);
+        }
+        method.output()
     }
 
-    fn id(&self) -> &str {
-        "vni"
+    #[test]
+    fn test_vni_marks() {
+        for (keys, expected) in [
+            ("a6", "â"),
+            ("e6", "ê"),
+            ("o6", "ô"),
+            ("u7", "ư"),
+            ("o7", "ơ"),
+            ("a8", "ă"),
+            ("d9", "đ"),
+            ("D9", "Đ"),
+            ("tuong7", "tương"),
+            ("quo7", "quơ"),
+            ("thuy1", "thuý"),
+        ] {
+            assert_eq!(type_word(&mut VNIMethod::new(), keys), expected, "{}", keys);
+        }
     }
 
-    fn process(
-        &mut self,
-        key: char,
-        buffer: &mut InputBuffer,
-        _lookup: &dyn LookupProvider,
-    ) -> Action {
-        // TODO: Implement VNI logic
-        buffer.push(key, key.is_lowercase());
-        Action::Commit(key.to_string())
+    #[test]
+    fn test_vni_free_order() {
+        for keys in ["vie65t", "vie56t", "viet65", "vie6t5", "vi5e6t"] {
+            assert_eq!(type_word(&mut VNIMethod::new(), keys), "việt", "{}", keys);
+        }
+        assert_eq!(type_word(&mut VNIMethod::new(), "duong972"), "đường");
+        assert_eq!(type_word(&mut VNIMethod::new(), "d9uo7ng2"), "đường");
     }
 
-    fn process_backspace(&mut self, buffer: &mut InputBuffer) -> Action {
-        if buffer.pop().is_some() {
+    #[test]
+    fn test_vni_replace_and_remove_marks() {
+        assert_eq!(type_word(&mut VNIMethod::new(), "a12"), "à");
+        assert_eq!(type_word(&mut VNIMethod::new(), "a10"), "a");
+        assert_eq!(type_word(&mut VNIMethod::new(), "a68"), "ă");
+        assert_eq!(type_word(&mut VNIMethod::new(), "o67"), "ơ");
+    }
+
+    #[test]
+    fn test_vni_escape() {
+        let mut method = VNIMethod::new();
+        assert_eq!(type_word(&mut method, "a11"), "a1");
+        assert_eq!(method.raw_input().unwrap(), "a11");
+        assert_eq!(type_word(&mut method, "b"), "a1b");
+
+        assert_eq!(type_word(&mut VNIMethod::new(), "a66"), "a6");
+        assert_eq!(type_word(&mut VNIMethod::new(), "uo77"), "uo7");
+        assert_eq!(type_word(&mut VNIMethod::new(), "d99"), "d9");
+    }
+
+    #[test]
+    fn test_vni_digits_without_target() {
+        assert_eq!(type_word(&mut VNIMethod::new(), "2024"), "2024");
+        assert_eq!(type_word(&mut VNIMethod::new(), "b6"), "b6");
+        assert_eq!(type_word(&mut VNIMethod::new(), "u8"), "u8");
+    }
+
+    #[test]
+    fn test_vni_backspace() {
+        let mut method = VNIMethod::new();
+        let mut buffer = InputBuffer::new();
+        type_word(&mut method, "a11");
+
+        // The escape is taken back with the second key
+        let action = method.process_backspace(&mut buffer);
+        assert_eq!(
+            action,
             Action::Replace {
-                backspace_count: 1,
-                text: String::new(),
+                backspace_count: 2,
+                text: "á".to_string(),
             }
-        } else {
-            Action::DoNothing
-        }
+        );
     }
 
-    fn reset(&mut self) {}
-    fn can_undo(&self, _buffer: &InputBuffer) -> bool {
-        false
-    }
-    fn undo(&mut self, _buffer: &mut InputBuffer) -> Action {
-        Action::DoNothing
+    #[test]
+    fn test_vni_resume() {
+        let mut method = VNIMethod::new();
+        let mut buffer = InputBuffer::new();
+        let lookup = VietnameseLookup::new_vni();
+
+        assert_eq!(method.resume("Tiếng Việt", &mut buffer), 4);
+        assert_eq!(method.raw_input().unwrap(), "Vie6t5");
+
+        let action = method.process(&KeyEvent::from_char('1'), &mut buffer, &lookup);
+        assert_eq!(
+            action,
+            Action::Replace {
+                backspace_count: 4,
+                text: "Viết".to_string(),
+            }
+        );
+
+        method.reset();
+        assert_eq!(method.resume("đường", &mut buffer), 5);
+        assert_eq!(method.raw_input().unwrap(), "d9u7o7ng2");
+        assert_eq!(method.resume("hello ", &mut buffer), 0);
     }
 }
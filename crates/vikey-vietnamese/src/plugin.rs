@@ -1,17 +1,40 @@
 // plugin.rs - Vietnamese Language Plugin
 
+use crate::auto_restore::{AutoRestore, AutoRestoreMode};
 use crate::lookup::VietnameseLookup;
 use crate::methods::telex::TelexMethod;
 use crate::methods::telex_v2::TelexMethodV2;
 use crate::methods::viqr::VIQRMethod;
 use crate::methods::vni::VNIMethod;
+use crate::rules::ToneStyle;
+use crate::schema::{self, Schema, SchemaError, SchemaMethod};
+use std::path::Path;
+use vikey_core::config::{OptionSpec, OptionValue};
+use vikey_core::metadata::{Capabilities, MethodMetadata, PluginMetadata};
 use vikey_core::traits::{InputMethodTrait, LanguagePlugin, LanguageRules, LookupProvider};
 
+/// Option names and values of the auto-restore modes
+const AUTO_RESTORE_MODES: &[(&str, AutoRestoreMode)] = &[
+    ("off", AutoRestoreMode::Off),
+    ("restore_on_boundary", AutoRestoreMode::RestoreOnBoundary),
+    ("strict", AutoRestoreMode::StrictReject),
+];
+
+/// Option names and values of the tone placement styles
+const TONE_STYLES: &[(&str, ToneStyle)] = &[("new", ToneStyle::New), ("old", ToneStyle::Old)];
+
+/// Built-in input methods, in display order
+const BUILTIN_METHODS: &[&str] = &["telex", "telex_v2", "vni", "viqr"];
+
 /// Vietnamese Language Plugin
 pub struct VietnamesePlugin {
     lookup_telex: VietnameseLookup,
     #[allow(dead_code)]
     lookup_vni: VietnameseLookup,
+    auto_restore: AutoRestore,
+    tone_style: ToneStyle,
+    /// User-defined input methods, listed after the built-in ones
+    schemas: Vec<Schema>,
 }
 
 impl VietnamesePlugin {
@@ -19,8 +42,84 @@ impl VietnamesePlugin {
         Self {
             lookup_telex: VietnameseLookup::new_telex(),
             lookup_vni: VietnameseLookup::new_vni(),
+            auto_restore: AutoRestore::default(),
+            tone_style: ToneStyle::default(),
+            schemas: Vec::new(),
         }
     }
+
+    /// Use the given auto-restore policy for the input methods created;
+    /// words kept later through a clone of it reach those methods too
+    pub fn with_auto_restore(mut self, auto_restore: AutoRestore) -> Self {
+        self.auto_restore = auto_restore;
+        self
+    }
+
+    /// Auto-restore policy given to new input methods
+    pub fn auto_restore(&self) -> &AutoRestore {
+        &self.auto_restore
+    }
+
+    /// Use the given tone placement style for the input methods created
+    pub fn with_tone_style(mut self, tone_style: ToneStyle) -> Self {
+        self.tone_style = tone_style;
+        self
+    }
+
+    /// Tone placement style given to new input methods
+    pub fn tone_style(&self) -> ToneStyle {
+        self.tone_style
+    }
+
+    /// Register a user-defined input method
+    ///
+    /// Fails if its ID is taken by a built-in method or another schema.
+    pub fn add_schema(&mut self, schema: Schema) -> Result<(), SchemaError> {
+        if self.input_methods().contains(&schema.id()) {
+            let message = format!("input method '{}' already exists", schema.id());
+            return Err(schema.error(message));
+        }
+        self.schemas.push(schema);
+        Ok(())
+    }
+
+    /// Register the user-defined input methods of a directory (see
+    /// [`schema::load_dir`])
+    ///
+    /// # Returns
+    /// An error for each schema that was not registered
+    pub fn load_schemas(&mut self, dir: impl AsRef<Path>) -> Vec<SchemaError> {
+        let (schemas, mut errors) = schema::load_dir(dir);
+        for schema in schemas {
+            if let Err(error) = self.add_schema(schema) {
+                errors.push(error);
+            }
+        }
+        errors
+    }
+
+    /// User-defined input methods
+    pub fn schemas(&self) -> &[Schema] {
+        &self.schemas
+    }
+}
+
+/// Name of `value` in an option's choice table
+fn choice_name<T: PartialEq>(table: &[(&'static str, T)], value: &T) -> Option<OptionValue> {
+    table
+        .iter()
+        .find(|(_, v)| v == value)
+        .map(|(name, _)| OptionValue::from(*name))
+}
+
+/// Value named by `value` in an option's choice table
+fn choice_value<T: Copy>(table: &[(&str, T)], value: &OptionValue) -> Result<T, String> {
+    let name = value.as_str().unwrap_or_default();
+    table
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, v)| *v)
+        .ok_or_else(|| format!("unsupported value {}", value))
 }
 
 impl Default for VietnamesePlugin {
@@ -39,19 +138,96 @@ impl LanguagePlugin for VietnamesePlugin {
     }
 
     fn input_methods(&self) -> Vec<&str> {
-        vec!["telex", "telex_v2", "vni", "viqr"]
+        let schemas = self.schemas.iter().map(Schema::id);
+        BUILTIN_METHODS.iter().copied().chain(schemas).collect()
     }
 
     fn create_input_method(&self, id: &str) -> Option<Box<dyn InputMethodTrait>> {
         match id {
-            "telex" => Some(Box::new(TelexMethod::new())),
-            "telex_v2" => Some(Box::new(TelexMethodV2::new())),
-            "vni" => Some(Box::new(VNIMethod::new())),
-            "viqr" => Some(Box::new(VIQRMethod::new())),
-            _ => None,
+            "telex" => Some(Box::new(
+                TelexMethod::new().with_auto_restore(self.auto_restore.clone()),
+            )),
+            "telex_v2" => Some(Box::new(
+                TelexMethodV2::new()
+                    .with_auto_restore(self.auto_restore.clone())
+                    .with_tone_style(self.tone_style),
+            )),
+            "vni" => Some(Box::new(
+                VNIMethod::new()
+                    .with_auto_restore(self.auto_restore.clone())
+                    .with_tone_style(self.tone_style),
+            )),
+            "viqr" => Some(Box::new(
+                VIQRMethod::new()
+                    .with_auto_restore(self.auto_restore.clone())
+                    .with_tone_style(self.tone_style),
+            )),
+            _ => {
+                let schema = self.schemas.iter().find(|schema| schema.id() == id)?;
+                Some(Box::new(
+                    SchemaMethod::from_keymap(schema.clone())
+                        .with_auto_restore(self.auto_restore.clone())
+                        .with_tone_style(self.tone_style),
+                ))
+            }
         }
     }
 
+    fn metadata(&self) -> PluginMetadata {
+        let metadata = PluginMetadata::new(self.id(), self.name())
+            .with_language_tag("vi")
+            .with_script("Latn")
+            .with_version(env!("CARGO_PKG_VERSION"))
+            .with_display_name("vi", "Chữ Việt")
+            .with_display_name("en", "Vietnamese")
+            .with_icon("vietnamese")
+            .with_method(
+                MethodMetadata::new("telex", "Telex")
+                    .with_description(
+                        "Letters for marks and tones: aa → â, w → ư, s → sắc, f → huyền",
+                    )
+                    .with_capabilities(Capabilities::TONAL)
+                    .with_priority(10),
+            )
+            .with_method(
+                MethodMetadata::new("telex_v2", "Telex V2 (Smart)")
+                    .with_description(
+                        "Syllable-aware Telex with spelling checks and free tone placement",
+                    )
+                    .with_display_name("vi", "Telex V2 (thông minh)")
+                    .with_capabilities(
+                        Capabilities::TONAL | Capabilities::PREEDIT | Capabilities::RESUME,
+                    ),
+            )
+            .with_method(
+                MethodMetadata::new("vni", "VNI")
+                    .with_description(
+                        "Digits for marks and tones: a6 → â, u7 → ư, 1 → sắc, 2 → huyền",
+                    )
+                    .with_capabilities(
+                        Capabilities::TONAL | Capabilities::PREEDIT | Capabilities::RESUME,
+                    ),
+            )
+            .with_method(
+                MethodMetadata::new("viqr", "VIQR")
+                    .with_description(
+                        "Punctuation for marks and tones: a^ → â, u+ → ư, ' → sắc, ` → huyền",
+                    )
+                    .with_capabilities(
+                        Capabilities::TONAL | Capabilities::PREEDIT | Capabilities::RESUME,
+                    ),
+            );
+
+        self.schemas.iter().fold(metadata, |metadata, schema| {
+            let method = MethodMetadata::new(schema.id(), schema.name())
+                .with_description(schema.description().unwrap_or_default())
+                .with_capabilities(
+                    Capabilities::TONAL | Capabilities::PREEDIT | Capabilities::RESUME,
+                );
+            metadata.with_method(method)
+        })
+    }
+
     fn lookup(&self) -> &dyn LookupProvider {
         &self.lookup_telex
     }
@@ -60,6 +236,40 @@ impl LanguagePlugin for VietnamesePlugin {
         // TODO: Implement VietnameseRules
         unimplemented!("VietnameseRules not yet implemented")
     }
+
+    fn options(&self) -> Vec<OptionSpec> {
+        vec![
+            OptionSpec::choice(
+                "auto_restore",
+                &["off", "restore_on_boundary", "strict"],
+                "restore_on_boundary",
+                "Restore words that are not Vietnamese to the keys typed",
+            ),
+            OptionSpec::choice(
+                "tone_style",
+                &["new", "old"],
+                "new",
+                "Tone placement in oa, oe, uy: new (hoà, thuý) or old (hòa, thúy)",
+            ),
+        ]
+    }
+
+    fn option(&self, key: &str) -> Option<OptionValue> {
+        match key {
+            "auto_restore" => choice_name(AUTO_RESTORE_MODES, &self.auto_restore.mode),
+            "tone_style" => choice_name(TONE_STYLES, &self.tone_style),
+            _ => None,
+        }
+    }
+
+    fn set_option(&mut self, key: &str, value: &OptionValue) -> Result<(), String> {
+        match key {
+            "auto_restore" => self.auto_restore.mode = choice_value(AUTO_RESTORE_MODES, value)?,
+            "tone_style" => self.tone_style = choice_value(TONE_STYLES, value)?,
+            _ => return Err(format!("unknown option '{}'", key)),
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -82,6 +292,69 @@ mod tests {
         assert!(methods.contains(&"viqr"));
     }
 
+    #[test]
+    fn test_metadata() {
+        let plugin = VietnamesePlugin::new();
+        let metadata = plugin.metadata();
+        let methods: Vec<_> = metadata.methods.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(methods, plugin.input_methods());
+        assert_eq!(metadata.language_tag, "vi");
+        assert_eq!(metadata.display_name("en-US"), "Vietnamese");
+        assert_eq!(
+            metadata.default_method().map(|m| m.id.as_str()),
+            Some("telex")
+        );
+        assert!(metadata
+            .method("telex_v2")
+            .unwrap()
+            .capabilities
+            .contains(Capabilities::PREEDIT));
+    }
+
+    #[test]
+    fn test_options() {
+        let mut plugin = VietnamesePlugin::new();
+        for spec in plugin.options() {
+            assert!(spec.validate(&spec.default).is_ok());
+            assert_eq!(plugin.option(&spec.key), Some(spec.default));
+        }
+
+        plugin.set_option("tone_style", &"old".into()).unwrap();
+        plugin.set_option("auto_restore", &"off".into()).unwrap();
+        assert_eq!(plugin.tone_style(), ToneStyle::Old);
+        assert_eq!(plugin.auto_restore().mode, AutoRestoreMode::Off);
+        assert!(plugin.set_option("tone_style", &"modern".into()).is_err());
+        assert!(plugin.set_option("spelling", &true.into()).is_err());
+    }
+
+    #[test]
+    fn test_schemas() {
+        let mut plugin = VietnamesePlugin::new();
+        let text = "[method]\nid = \"my_vni\"\nname = \"My VNI\"\n\n[tones]\n1 = \"acute\"\n";
+        plugin.add_schema(Schema::from_toml(text).unwrap()).unwrap();
+        assert_eq!(
+            plugin.input_methods(),
+            ["telex", "telex_v2", "vni", "viqr", "my_vni"]
+        );
+        assert_eq!(
+            plugin
+                .create_input_method("my_vni")
+                .map(|m| m.name().to_string()),
+            Some("My VNI".to_string())
+        );
+        assert!(plugin.metadata().method("my_vni").is_some());
+
+        // IDs of built-in methods and other schemas are taken
+        let error = plugin
+            .add_schema(Schema::from_toml(text).unwrap())
+            .unwrap_err();
+        assert_eq!(error.line, Some(2));
+        let text = text.replace("my_vni", "vni");
+        assert!(plugin
+            .add_schema(Schema::from_toml(&text).unwrap())
+            .is_err());
+    }
+
     #[test]
     fn test_create_telex() {
         let plugin = VietnamesePlugin::new();
@@ -1,105 +1,81 @@
 // rules.rs - Vietnamese language rules (tone placement, etc.)
 
-use crate::syllable::Tone;
+use crate::syllable::{apply_tone_to_char, Tone};
 
 /// Tone placement style
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ToneStyle {
     /// New Style (default): hoà, thuý
+    #[default]
     New,
     /// Old Style: hòa, thúy
     Old,
 }
 
-impl Default for ToneStyle {
-    fn default() -> Self {
-        Self::New
-    }
-}
-
 /// Place tone mark on vowel according to Vietnamese rules
+///
+/// A letter with a mark (â, ă, ê, ô, ơ, ư) takes the tone, the last one if
+/// there are two (ướ). Otherwise "oa", "oe" and "uy" follow `style`, other
+/// pairs take it on the first letter (ái, ía, úa) and triples on the middle
+/// one (oái, uỷu).
 pub fn place_tone(vowel: &str, tone: Tone, style: ToneStyle) -> String {
-    let chars: Vec<char> = vowel.chars().collect();
+    let mut chars: Vec<char> = vowel.chars().collect();
     if chars.is_empty() {
         return vowel.to_string();
     }
 
-    // If only 1 character, apply tone to it
-    if chars.len() == 1 {
-        return apply_tone_to_char(chars[0], tone).to_string();
-    }
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap())
+        .collect();
+    let marked = lower
+        .iter()
+        .rposition(|c| matches!(c, 'â' | 'ă' | 'ê' | 'ô' | 'ơ' | 'ư'));
 
-    // If 2 characters
-    if chars.len() == 2 {
-        let v1 = chars[0].to_lowercase().next().unwrap();
-        let v2 = chars[1].to_lowercase().next().unwrap();
+    let position = match (marked, lower.as_slice()) {
+        (Some(i), _) => i,
+        // New Style: hoà, hoè, thuý; Old Style: hòa, hòe, thúy
+        (None, ['o', 'a' | 'e'] | ['u', 'y']) => match style {
+            ToneStyle::New => 1,
+            ToneStyle::Old => 0,
+        },
+        (None, [_, _, _]) => 1,
+        (None, _) => 0,
+    };
 
-        // Special cases for "oa", "oe", "uy"
-        // New Style: place on 2nd char (hoà, hoè, thuý)
-        // Old Style: place on 1st char (hòa, hòe, thúy)
-        if (v1 == 'o' && (v2 == 'a' || v2 == 'e' || v2 == 'ă')) || (v1 == 'u' && v2 == 'y') {
-            match style {
-                ToneStyle::New => {
-                    // Place on 2nd char
-                    let mut res = String::new();
-                    res.push(chars[0]);
-                    res.push(apply_tone_to_char(chars[1], tone));
-                    return res;
-                }
-                ToneStyle::Old => {
-                    // Place on 1st char (except uy -> úy is standard even in old style sometimes, but strict old style is úy)
-                    let mut res = String::new();
-                    res.push(apply_tone_to_char(chars[0], tone));
-                    res.push(chars[1]);
-                    return res;
-                }
-            }
-        }
+    chars[position] = apply_tone_to_char(chars[position], tone);
+    chars.into_iter().collect()
+}
 
-        // Default rule for 2 chars: place on 1st char (e.g., "ai" -> "ái", "eu" -> "ếu")
-        // UNLESS it's "uo" (uơ) or "ie" (iê) - but those are usually represented as single modified vowels in some contexts,
-        // but here we deal with raw chars.
-        // Actually, "ia", "ua", "ưa" -> tone on 1st char (ía, úa, ứa)
-        // "iê", "uô", "ươ" -> tone on 2nd char (iế, uố, ướ)
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Let's check if the second char is a modified vowel that usually takes tone
-        if v2 == 'ê' || v2 == 'ô' || v2 == 'ơ' {
-            let mut res = String::new();
-            res.push(chars[0]);
-            res.push(apply_tone_to_char(chars[1], tone));
-            return res;
+    #[test]
+    fn test_place_tone() {
+        for (vowel, tone, expected) in [
+            ("a", Tone::Acute, "á"),
+            ("oa", Tone::Grave, "oà"),
+            ("ai", Tone::Acute, "ái"),
+            ("ua", Tone::HookAbove, "ủa"),
+            ("ưa", Tone::Tilde, "ữa"),
+            ("iê", Tone::Underdot, "iệ"),
+            ("ươ", Tone::Grave, "ườ"),
+            ("uyê", Tone::Tilde, "uyễ"),
+            ("ươi", Tone::Acute, "ưới"),
+            ("uây", Tone::Acute, "uấy"),
+            ("oai", Tone::Acute, "oái"),
+            ("uyu", Tone::HookAbove, "uỷu"),
+        ] {
+            assert_eq!(
+                place_tone(vowel, tone, ToneStyle::New),
+                expected,
+                "{}",
+                vowel
+            );
         }
 
-        // Default: 1st char
-        let mut res = String::new();
-        res.push(apply_tone_to_char(chars[0], tone));
-        res.push(chars[1]);
-        return res;
+        assert_eq!(place_tone("oa", Tone::Grave, ToneStyle::Old), "òa");
+        assert_eq!(place_tone("oă", Tone::Underdot, ToneStyle::Old), "oặ");
     }
-
-    // If 3 characters (e.g., "yeu", "uoi")
-    if chars.len() == 3 {
-        // Usually place on 2nd char (yếu, uối)
-        let mut res = String::new();
-        res.push(chars[0]);
-        res.push(apply_tone_to_char(chars[1], tone));
-        res.push(chars[2]);
-        return res;
-    }
-
-    // Fallback: place on 1st char
-    let mut res = String::new();
-    res.push(apply_tone_to_char(chars[0], tone));
-    for i in 1..chars.len() {
-        res.push(chars[i]);
-    }
-    res
-}
-
-/// Helper to apply tone to a single char (copied from syllable.rs for now, should refactor)
-fn apply_tone_to_char(ch: char, tone: Tone) -> char {
-    // This duplicates logic in syllable.rs. We should move it here or make it public there.
-    // For now, I'll duplicate to get it working, then refactor.
-    use crate::syllable::apply_tone_to_char as apply_tone_internal;
-    apply_tone_internal(ch, tone)
 }
@@ -0,0 +1,869 @@
+// schema.rs - User-defined input methods loaded from schema files
+
+use crate::compose::{Composer, KeyRole, Keymap, Placement, Repeat, UoHorn};
+use crate::syllable::{Modification, Tone};
+use serde::de::{self, Deserializer, Visitor};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Tone names in schema files; `None` takes the tone off
+const TONE_NAMES: &[(&str, Option<Tone>)] = &[
+    ("acute", Some(Tone::Acute)),
+    ("sắc", Some(Tone::Acute)),
+    ("grave", Some(Tone::Grave)),
+    ("huyền", Some(Tone::Grave)),
+    ("hook_above", Some(Tone::HookAbove)),
+    ("hỏi", Some(Tone::HookAbove)),
+    ("tilde", Some(Tone::Tilde)),
+    ("ngã", Some(Tone::Tilde)),
+    ("underdot", Some(Tone::Underdot)),
+    ("nặng", Some(Tone::Underdot)),
+    ("none", None),
+];
+
+/// Mark names in schema files
+const MARK_NAMES: &[(&str, Modification)] = &[
+    ("circumflex", Modification::Circumflex),
+    ("breve", Modification::Breve),
+    ("horn", Modification::Horn),
+    ("dstroke", Modification::DStroke),
+];
+
+/// Letters a shortcut may type
+const SHORTCUT_LETTERS: &str = "ăâêôơưđ";
+
+/// Format of a schema file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaFormat {
+    Toml,
+    Yaml,
+}
+
+impl SchemaFormat {
+    /// Format of a file from its extension: ".toml", ".yaml" or ".yml"
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(SchemaFormat::Toml),
+            "yaml" | "yml" => Some(SchemaFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Error in a schema file, with the line it was found on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// File, if the schema was read from one
+    pub path: Option<PathBuf>,
+
+    /// Line (from 1), if known
+    pub line: Option<usize>,
+
+    /// Column (from 1, in characters), if known
+    pub column: Option<usize>,
+
+    /// What is wrong
+    pub message: String,
+}
+
+impl SchemaError {
+    fn new(at: Option<(usize, usize)>, message: impl Into<String>) -> Self {
+        Self {
+            path: None,
+            line: at.map(|(line, _)| line),
+            column: at.map(|(_, column)| column),
+            message: message.into(),
+        }
+    }
+}
+
+/// "file:line:column: message"
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}", path.display())?,
+            None => f.write_str("schema")?,
+        }
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// What a key of a schema does
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Binding {
+    Tone(Tone),
+    RemoveTone,
+    Mark(Vec<Modification>),
+    Escape,
+}
+
+/// Input method defined in a schema file
+///
+/// A schema gives the tone keys, the mark keys, shortcuts that type a
+/// marked letter on their own, and the options of the built-in methods;
+/// composition is the same as for Telex, VNI and VIQR ([`Composer`]).
+/// Letter keys work in both cases.
+///
+/// ```toml
+/// [method]
+/// id = "telex_brackets"
+/// name = "Telex [ ]"
+/// description = "Telex with [ for ơ and ] for ư"
+///
+/// [tones]
+/// s = "acute"
+/// f = "grave"
+/// r = "hook_above"
+/// x = "tilde"
+/// j = "underdot"
+/// z = "none"
+///
+/// [marks]
+/// a = "circumflex"
+/// e = "circumflex"
+/// o = "circumflex"
+/// w = ["horn", "breve"]
+/// d = "dstroke"
+///
+/// [shortcuts]
+/// "[" = "ơ"
+/// "]" = "ư"
+///
+/// [options]
+/// placement = "free"
+/// repeat = "takes_off_right_away"
+/// ```
+///
+/// The same in YAML uses the same sections (`tones:`, `marks:`...); keys
+/// that are punctuation must be quoted there.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    id: String,
+    name: String,
+    description: Option<String>,
+    bindings: BTreeMap<char, Binding>,
+    shortcuts: BTreeMap<char, char>,
+    placement: Placement,
+    repeat: Repeat,
+    uo_horn: UoHorn,
+
+    /// File the schema was read from
+    path: Option<PathBuf>,
+
+    /// Where the ID is written
+    id_at: Option<(usize, usize)>,
+}
+
+/// Input method of a user-defined schema
+pub type SchemaMethod = Composer<Schema>;
+
+/// Key of a section: a string, or a digit YAML reads as a number
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct KeyName(String);
+
+impl<'de> Deserialize<'de> for KeyName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyVisitor;
+
+        impl Visitor<'_> for KeyVisitor {
+            type Value = KeyName;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a key")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<KeyName, E> {
+                Ok(KeyName(v.to_string()))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<KeyName, E> {
+                Ok(KeyName(v.to_string()))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<KeyName, E> {
+                Ok(KeyName(v.to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(KeyVisitor)
+    }
+}
+
+/// One mark name or a list of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Names {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Content of a schema file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    method: MethodSection,
+    #[serde(default)]
+    tones: BTreeMap<KeyName, String>,
+    #[serde(default)]
+    marks: BTreeMap<KeyName, Names>,
+    #[serde(default)]
+    shortcuts: BTreeMap<KeyName, String>,
+    #[serde(default)]
+    options: OptionsSection,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MethodSection {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct OptionsSection {
+    #[serde(default)]
+    placement: Option<String>,
+    #[serde(default)]
+    repeat: Option<String>,
+    #[serde(default)]
+    uo_horn: Option<String>,
+    #[serde(default)]
+    escape: Option<String>,
+}
+
+/// An entry of the tones, marks or shortcuts section, or the escape key
+enum Entry {
+    Binding(Binding),
+    Shortcut(char),
+}
+
+impl Schema {
+    /// Read a schema
+    pub fn parse(text: &str, format: SchemaFormat) -> Result<Self, SchemaError> {
+        let file: SchemaFile = match format {
+            SchemaFormat::Toml => toml::from_str(text).map_err(|e| {
+                let at = e.span().map(|span| position(text, span.start));
+                SchemaError::new(at, e.message())
+            })?,
+            SchemaFormat::Yaml => serde_yaml::from_str(text).map_err(|e| {
+                let at = e.location().map(|l| (l.line(), l.column()));
+                let message = e.to_string();
+                let message = match message.rsplit_once(" at line ") {
+                    Some((message, _)) if at.is_some() => message.to_string(),
+                    _ => message,
+                };
+                SchemaError::new(at, message)
+            })?,
+        };
+        Self::build(file, text)
+    }
+
+    /// Read a schema in TOML
+    pub fn from_toml(text: &str) -> Result<Self, SchemaError> {
+        Self::parse(text, SchemaFormat::Toml)
+    }
+
+    /// Read a schema in YAML
+    pub fn from_yaml(text: &str) -> Result<Self, SchemaError> {
+        Self::parse(text, SchemaFormat::Yaml)
+    }
+
+    /// Read a schema file; the format follows the extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SchemaError> {
+        let path = path.as_ref();
+        let in_file = |mut error: SchemaError| {
+            error.path = Some(path.to_path_buf());
+            error
+        };
+        let format = SchemaFormat::from_path(path).ok_or_else(|| {
+            in_file(SchemaError::new(
+                None,
+                "unknown format (expected .toml, .yaml or .yml)",
+            ))
+        })?;
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| in_file(SchemaError::new(None, e.to_string())))?;
+
+        let mut schema = Self::parse(&text, format).map_err(in_file)?;
+        schema.path = Some(path.to_path_buf());
+        Ok(schema)
+    }
+
+    /// Unique input method ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Input method name (display)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Short description, if the schema has one
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// File the schema was read from
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Error about the schema as a whole, pointing at its ID
+    pub(crate) fn error(&self, message: impl Into<String>) -> SchemaError {
+        let mut error = SchemaError::new(self.id_at, message);
+        error.path = self.path.clone();
+        error
+    }
+
+    /// Check the content of a file and build the keymap
+    fn build(file: SchemaFile, text: &str) -> Result<Self, SchemaError> {
+        let id = file.method.id;
+        let id_at = locate(text, "method", Some("id"));
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        {
+            return Err(SchemaError::new(
+                id_at,
+                format!(
+                    "invalid ID '{}' (expected lowercase letters, digits, '_' or '-')",
+                    id
+                ),
+            ));
+        }
+        if file.method.name.trim().is_empty() {
+            let at = locate(text, "method", Some("name"));
+            return Err(SchemaError::new(at, "the name is empty"));
+        }
+
+        let placement = match file.options.placement.as_deref() {
+            None | Some("free") => Placement::Free,
+            Some("after_letter") => Placement::AfterLetter,
+            Some(other) => {
+                return Err(SchemaError::new(
+                    locate(text, "options", Some("placement")),
+                    format!(
+                        "unknown placement '{}' (expected free or after_letter)",
+                        other
+                    ),
+                ))
+            }
+        };
+        let repeat = match file.options.repeat.as_deref() {
+            None | Some("takes_off") => Repeat::TakesOff,
+            Some("takes_off_right_away") => Repeat::TakesOffRightAway,
+            Some("keeps_mark") => Repeat::KeepsMark,
+            Some(other) => {
+                return Err(SchemaError::new(
+                    locate(text, "options", Some("repeat")),
+                    format!(
+                    "unknown repeat '{}' (expected takes_off, takes_off_right_away or keeps_mark)",
+                    other
+                ),
+                ))
+            }
+        };
+        let uo_horn = match file.options.uo_horn.as_deref() {
+            None | Some("together") => UoHorn::Together,
+            Some("once_the_word_goes_on") => UoHorn::OnceTheWordGoesOn,
+            Some(other) => {
+                return Err(SchemaError::new(
+                    locate(text, "options", Some("uo_horn")),
+                    format!(
+                        "unknown uo_horn '{}' (expected together or once_the_word_goes_on)",
+                        other
+                    ),
+                ))
+            }
+        };
+
+        // Check the entries in the order of the file, so that the first
+        // error is reported and a key bound twice is reported where it
+        // is bound the second time
+        let mut entries = Vec::new();
+        for (key, name) in &file.tones {
+            entries.push(("tones", key.0.as_str(), key.0.as_str(), tone_entry(name)));
+        }
+        for (key, names) in &file.marks {
+            entries.push(("marks", key.0.as_str(), key.0.as_str(), mark_entry(names)));
+        }
+        for (key, letter) in &file.shortcuts {
+            entries.push((
+                "shortcuts",
+                key.0.as_str(),
+                key.0.as_str(),
+                shortcut_entry(letter),
+            ));
+        }
+        if let Some(escape) = &file.options.escape {
+            let entry = Ok(Entry::Binding(Binding::Escape));
+            entries.push(("options", "escape", escape.as_str(), entry));
+        }
+        let mut located: Vec<_> = entries
+            .into_iter()
+            .map(|(section, name, key, entry)| {
+                (locate(text, section, Some(name)), section, key, entry)
+            })
+            .collect();
+        located.sort_by_key(|(at, ..)| *at);
+
+        let mut bindings = BTreeMap::new();
+        let mut sections: BTreeMap<char, &str> = BTreeMap::new();
+        let mut shortcuts = Vec::new();
+        for (at, section, key, entry) in located {
+            let entry = entry.map_err(|message| SchemaError::new(at, message))?;
+            let key = single_char(key).map(lowercase).ok_or_else(|| {
+                SchemaError::new(
+                    at,
+                    format!("key must be a single character, found '{}'", key),
+                )
+            })?;
+            if key.is_whitespace() || key.is_control() {
+                return Err(SchemaError::new(at, "key must be a visible character"));
+            }
+
+            match entry {
+                Entry::Binding(binding) => {
+                    if let Some(previous) = sections.insert(key, section) {
+                        return Err(SchemaError::new(
+                            at,
+                            format!("key '{}' is already bound in [{}]", key, previous),
+                        ));
+                    }
+                    bindings.insert(key, binding);
+                }
+                Entry::Shortcut(letter) => shortcuts.push((at, key, letter)),
+            }
+        }
+
+        // A shortcut is what a key types when it has nothing to mark
+        for &(at, key, _) in &shortcuts {
+            if let Some(section) = sections.get(&key).filter(|&&section| section != "marks") {
+                return Err(SchemaError::new(
+                    at,
+                    format!(
+                        "key '{}' is bound in [{}] and cannot be a shortcut",
+                        key, section
+                    ),
+                ));
+            }
+        }
+        let shortcuts = shortcuts
+            .into_iter()
+            .map(|(_, key, letter)| (key, letter))
+            .collect();
+
+        Ok(Self {
+            id,
+            name: file.method.name,
+            description: file.method.description,
+            bindings,
+            shortcuts,
+            placement,
+            repeat,
+            uo_horn,
+            path: None,
+            id_at,
+        })
+    }
+}
+
+impl Keymap for Schema {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn role(&self, key: char) -> Option<KeyRole<'_>> {
+        let role = match self.bindings.get(&lowercase(key))? {
+            Binding::Tone(tone) => KeyRole::Tone(*tone),
+            Binding::RemoveTone => KeyRole::RemoveTone,
+            Binding::Mark(marks) => KeyRole::Mark(marks),
+            Binding::Escape => KeyRole::Escape,
+        };
+        Some(role)
+    }
+
+    fn placement(&self) -> Placement {
+        self.placement
+    }
+
+    fn repeat(&self) -> Repeat {
+        self.repeat
+    }
+
+    fn uo_horn(&self) -> UoHorn {
+        self.uo_horn
+    }
+
+    fn shortcut(&self, key: char) -> Option<char> {
+        let letter = *self.shortcuts.get(&lowercase(key))?;
+        Some(if key.is_uppercase() {
+            letter.to_uppercase().next().unwrap_or(letter)
+        } else {
+            letter
+        })
+    }
+}
+
+/// Read the schemas of a directory (".toml", ".yaml" and ".yml" files, by
+/// file name); a directory that does not exist has none
+///
+/// # Returns
+/// The schemas read, and an error for each file that could not be
+pub fn load_dir(dir: impl AsRef<Path>) -> (Vec<Schema>, Vec<SchemaError>) {
+    let mut schemas = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(dir.as_ref()) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && SchemaFormat::from_path(path).is_some())
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => {
+            let mut error = SchemaError::new(None, e.to_string());
+            error.path = Some(dir.as_ref().to_path_buf());
+            errors.push(error);
+            Vec::new()
+        }
+    };
+    paths.sort();
+
+    for path in paths {
+        match Schema::load(&path) {
+            Ok(schema) => schemas.push(schema),
+            Err(error) => errors.push(error),
+        }
+    }
+    (schemas, errors)
+}
+
+fn tone_entry(name: &str) -> Result<Entry, String> {
+    let tone = TONE_NAMES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, tone)| *tone)
+        .ok_or_else(|| {
+            format!(
+                "unknown tone '{}' (expected acute, grave, hook_above, tilde, underdot or none)",
+                name
+            )
+        })?;
+    Ok(Entry::Binding(match tone {
+        Some(tone) => Binding::Tone(tone),
+        None => Binding::RemoveTone,
+    }))
+}
+
+fn mark_entry(names: &Names) -> Result<Entry, String> {
+    let names = match names {
+        Names::One(name) => std::slice::from_ref(name),
+        Names::Many(names) => names.as_slice(),
+    };
+    if names.is_empty() {
+        return Err("no marks given".to_string());
+    }
+    let mut marks = Vec::new();
+    for name in names {
+        let mark = MARK_NAMES
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, mark)| *mark)
+            .ok_or_else(|| {
+                format!(
+                    "unknown mark '{}' (expected circumflex, breve, horn or dstroke)",
+                    name
+                )
+            })?;
+        if !marks.contains(&mark) {
+            marks.push(mark);
+        }
+    }
+    Ok(Entry::Binding(Binding::Mark(marks)))
+}
+
+fn shortcut_entry(letter: &str) -> Result<Entry, String> {
+    single_char(letter)
+        .filter(|&c| SHORTCUT_LETTERS.contains(c))
+        .map(Entry::Shortcut)
+        .ok_or_else(|| {
+            format!(
+                "a shortcut types one of ă, â, ê, ô, ơ, ư, đ, found '{}'",
+                letter
+            )
+        })
+}
+
+/// The character of a one-character string
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Lowercase form of a key, so that letter keys work in both cases
+fn lowercase(key: char) -> char {
+    let mut lower = key.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(c), None) => c,
+        _ => key,
+    }
+}
+
+/// Line and column (from 1) of byte `offset` in `text`
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = text.get(..offset).unwrap_or(text);
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Line and column of `key` in `section`, or of the section itself
+///
+/// Works for both formats: sections are TOML tables ("[tones]") or
+/// top-level YAML keys ("tones:").
+fn locate(text: &str, section: &str, key: Option<&str>) -> Option<(usize, usize)> {
+    let mut section_at = None;
+    let mut in_section = false;
+    for (i, line) in text.lines().enumerate() {
+        let content = line.trim_start();
+        let column = line.chars().count() - content.chars().count() + 1;
+        if let Some(name) = section_name(line) {
+            in_section = name == section;
+            if in_section {
+                section_at = Some((i + 1, column));
+                if key.is_none() {
+                    break;
+                }
+            }
+        } else if in_section && key.is_some_and(|key| starts_with_key(content, key)) {
+            return Some((i + 1, column));
+        }
+    }
+    section_at
+}
+
+/// Name of the section a line opens, if it opens one
+fn section_name(line: &str) -> Option<&str> {
+    if let Some(header) = line.trim().strip_prefix('[') {
+        return header.split(']').next().map(str::trim);
+    }
+    if line.starts_with(char::is_whitespace) || line.starts_with('#') {
+        return None;
+    }
+    let (name, rest) = line.split_once(':')?;
+    let rest = rest.trim_start();
+    (rest.is_empty() || rest.starts_with('#')).then(|| name.trim())
+}
+
+/// Does the entry on a line have `key`, bare or quoted?
+fn starts_with_key(content: &str, key: &str) -> bool {
+    let escaped = key.replace('\\', "\\\\").replace('"', "\\\"");
+    let forms = [
+        key.to_string(),
+        format!("\"{}\"", escaped),
+        format!("'{}'", key.replace('\'', "''")),
+        format!("'{}'", key),
+    ];
+    forms.iter().any(|form| {
+        content
+            .strip_prefix(form.as_str())
+            .is_some_and(|rest| rest.trim_start().starts_with(['=', ':']))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup::VietnameseLookup;
+    use vikey_core::traits::InputMethodTrait;
+    use vikey_core::{InputBuffer, KeyEvent};
+
+    const TELEX_BRACKETS: &str = r#"
+[method]
+id = "telex_brackets"
+name = "Telex [ ]"
+
+[tones]
+s = "acute"
+f = "grave"
+r = "hook_above"
+x = "tilde"
+j = "underdot"
+z = "none"
+
+[marks]
+a = "circumflex"
+e = "circumflex"
+o = "circumflex"
+w = ["horn", "breve"]
+d = "dstroke"
+
+[shortcuts]
+"[" = "ơ"
+"]" = "ư"
+w = "ư"
+
+[options]
+repeat = "takes_off_right_away"
+"#;
+
+    fn type_word(method: &mut SchemaMethod, keys: &str) -> String {
+        let mut buffer = InputBuffer::new();
+        let lookup = VietnameseLookup::new_telex();
+        for c in keys.chars() {
+            method.process(&KeyEvent::from_char(c), &mut buffer, &lookup);
+        }
+        method.output()
+    }
+
+    fn error(text: &str, format: SchemaFormat) -> (Option<usize>, String) {
+        let error = Schema::parse(text, format).unwrap_err();
+        (error.line, error.message)
+    }
+
+    #[test]
+    fn test_toml_schema() {
+        let schema = Schema::from_toml(TELEX_BRACKETS).unwrap();
+        assert_eq!(schema.id(), "telex_brackets");
+        assert_eq!(schema.name(), "Telex [ ]");
+        assert_eq!(schema.role('S'), Some(KeyRole::Tone(Tone::Acute)));
+        assert_eq!(schema.role('z'), Some(KeyRole::RemoveTone));
+        assert_eq!(schema.role('k'), None);
+        assert_eq!(schema.shortcut('W'), Some('Ư'));
+
+        for (keys, expected) in [
+            ("vieetj", "việt"),
+            ("tuwowng", "tương"),
+            ("t]", "tư"),
+            ("t[i", "tơi"),
+            ("w", "ư"),
+            ("ww", "w"),
+            ("Wf", "Ừ"),
+            ("ass", "as"),
+        ] {
+            let mut method = SchemaMethod::from_keymap(schema.clone());
+            assert_eq!(type_word(&mut method, keys), expected, "{}", keys);
+        }
+    }
+
+    #[test]
+    fn test_yaml_schema() {
+        let text = r#"
+method:
+  id: vni_lite
+  name: VNI lite
+tones:
+  1: sắc
+  2: huyền
+  3: hỏi
+  4: ngã
+  5: nặng
+marks:
+  6: circumflex
+  7: horn
+  8: breve
+  9: dstroke
+"#;
+        let schema = Schema::from_yaml(text).unwrap();
+        assert_eq!(schema.role('1'), Some(KeyRole::Tone(Tone::Acute)));
+        let mut method = SchemaMethod::from_keymap(schema);
+        assert_eq!(type_word(&mut method, "vie65t"), "việt");
+    }
+
+    #[test]
+    fn test_options() {
+        let with_options = |options: &str| {
+            let text = format!(
+                "[method]\nid = \"x\"\nname = \"X\"\n\n[tones]\n1 = \"acute\"\n\n\
+                 [marks]\n6 = \"circumflex\"\n7 = \"horn\"\n\n[options]\n{}\n",
+                options
+            );
+            SchemaMethod::from_keymap(Schema::from_toml(&text).unwrap())
+        };
+
+        let mut method = with_options("repeat = \"keeps_mark\"");
+        assert_eq!(type_word(&mut method, "a66"), "â6");
+        let mut method = with_options("repeat = \"takes_off\"");
+        assert_eq!(type_word(&mut method, "a66"), "a6");
+
+        let mut method = with_options("uo_horn = \"together\"");
+        assert_eq!(type_word(&mut method, "tuo7"), "tươ");
+        let mut method = with_options("uo_horn = \"once_the_word_goes_on\"");
+        assert_eq!(type_word(&mut method, "tuo7"), "tuơ");
+        assert_eq!(type_word(&mut method, "ng"), "tương");
+
+        let toml = "[method]\nid = \"x\"\nname = \"X\"\n\n[options]\nuo_horn = \"late\"\n";
+        let (line, message) = error(toml, SchemaFormat::Toml);
+        assert_eq!(line, Some(6));
+        assert!(message.contains("unknown uo_horn 'late'"), "{}", message);
+    }
+
+    #[test]
+    fn test_errors_point_at_the_line() {
+        let toml =
+            "[method]\nid = \"x\"\nname = \"X\"\n\n[tones]\ns = \"acute\"\nf = \"falling\"\n";
+        let (line, message) = error(toml, SchemaFormat::Toml);
+        assert_eq!(line, Some(7));
+        assert!(message.contains("unknown tone 'falling'"), "{}", message);
+
+        let toml = "[method]\nid = \"x\"\nname = \"X\"\n\n[tones]\ns = \"acute\"\n\n[marks]\ns = \"horn\"\n";
+        let (line, message) = error(toml, SchemaFormat::Toml);
+        assert_eq!(line, Some(9));
+        assert_eq!(message, "key 's' is already bound in [tones]");
+
+        let toml = "[method]\nid = \"My Telex\"\nname = \"X\"\n";
+        assert_eq!(error(toml, SchemaFormat::Toml).0, Some(2));
+
+        let toml = "[method]\nid = \"x\"\nname = \"X\"\n\n[marks]\nww = \"horn\"\n";
+        assert_eq!(error(toml, SchemaFormat::Toml).0, Some(6));
+
+        let toml = "[method]\nid = \"x\"\nname = \"X\"\ncolor = \"red\"\n";
+        assert_eq!(error(toml, SchemaFormat::Toml).0, Some(4));
+
+        let yaml = "method:\n  id: x\n  name: X\nshortcuts:\n  \"]\": ư\n  \"[\": o\n";
+        let (line, message) = error(yaml, SchemaFormat::Yaml);
+        assert_eq!(line, Some(6));
+        assert!(message.contains("found 'o'"), "{}", message);
+
+        let yaml = "method:\n  id: x\n  name: X\ntones: [s]\n";
+        assert_eq!(error(yaml, SchemaFormat::Yaml).0, Some(4));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut error = SchemaError::new(Some((3, 1)), "key 's' is already bound in [tones]");
+        assert_eq!(
+            error.to_string(),
+            "schema:3:1: key 's' is already bound in [tones]"
+        );
+        error.path = Some(PathBuf::from("my-telex.toml"));
+        assert_eq!(
+            error.to_string(),
+            "my-telex.toml:3:1: key 's' is already bound in [tones]"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 // syllable.rs - Vietnamese syllable structure (inspired by vi-rs)
 
+use crate::rules::{place_tone, ToneStyle};
 use std::fmt;
+use unicode_normalization::UnicodeNormalization;
 
 /// Vietnamese tone marks
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -113,15 +115,76 @@ impl Syllable {
     }
 
     /// Check if syllable structure is permissible
+    ///
+    /// The "u" after "q" and the "i" after "g" are checked as part of the
+    /// initial ("giữa" is "gi" + "ưa").
     pub fn is_permissible(&self) -> bool {
         use crate::validation::is_permissible_syllable;
-        is_permissible_syllable(&self.initial, &self.vowel, &self.final_consonant)
+        let (glide, vowel) = self.vowel.split_at(self.glide_len());
+        let initial = format!("{}{}", self.initial, glide);
+        is_permissible_syllable(&initial, vowel, &self.final_consonant)
+    }
+
+    /// Parse a written syllable (e.g. "việt") back into its parts
+    ///
+    /// Letter modifications stay in the letters themselves ("iê", "đ") and
+    /// `modifications` is left empty. Returns `None` if the word is not a
+    /// single permissible syllable or carries more than one tone.
+    pub fn parse(word: &str) -> Option<Self> {
+        let mut syllable = Syllable::new();
+
+        for ch in word.chars() {
+            if !ch.is_alphabetic() {
+                return None;
+            }
+
+            let mut letter = String::new();
+            for c in std::iter::once(ch).nfd() {
+                match tone_from_mark(c) {
+                    Some(_) if syllable.tone.is_some() => return None,
+                    Some(tone) => syllable.tone = Some(tone),
+                    None => letter.push(c),
+                }
+            }
+            for c in letter.nfc() {
+                syllable.push(c);
+            }
+        }
+
+        if syllable.is_empty() || (syllable.tone.is_some() && syllable.vowel.is_empty()) {
+            return None;
+        }
+        syllable.is_permissible().then_some(syllable)
     }
 }
 
-impl fmt::Display for Syllable {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Apply transformations and render
+/// Tone carried by a combining mark (NFD)
+fn tone_from_mark(mark: char) -> Option<Tone> {
+    match mark {
+        '\u{301}' => Some(Tone::Acute),
+        '\u{300}' => Some(Tone::Grave),
+        '\u{309}' => Some(Tone::HookAbove),
+        '\u{303}' => Some(Tone::Tilde),
+        '\u{323}' => Some(Tone::Underdot),
+        _ => None,
+    }
+}
+
+impl Syllable {
+    /// Render the syllable, placing the tone mark in the given style
+    ///
+    /// The style only matters for open "oa", "oe" and "uy" syllables
+    /// (hoà/hòa, thuý/thúy); with a final consonant the tone always goes on
+    /// the second vowel (hoàn, thuýt). The "u" after "q" and the "i" after
+    /// "g" belong to the initial, so the tone goes on the vowel after them
+    /// (quá, quý, già).
+    pub fn render(&self, style: ToneStyle) -> String {
+        let style = if self.final_consonant.is_empty() {
+            style
+        } else {
+            ToneStyle::New
+        };
+
         let mut result = String::new();
 
         // Initial consonant
@@ -129,13 +192,39 @@ impl fmt::Display for Syllable {
 
         // Vowel with modifications and tone
         let vowel = apply_modifications(&self.vowel, &self.modifications);
-        let vowel_with_tone = apply_tone(&vowel, self.tone);
-        result.push_str(&vowel_with_tone);
+        let glide = self.glide_len();
+        let (glide, vowel) = vowel.split_at(glide);
+        result.push_str(glide);
+        result.push_str(&apply_tone(vowel, self.tone, style));
 
         // Final consonant
         result.push_str(&self.final_consonant);
 
-        write!(f, "{}", result)
+        result
+    }
+
+    /// Byte length of the vowel letter that belongs to the initial ("qu", "gi")
+    fn glide_len(&self) -> usize {
+        let mut vowel = self.vowel.chars();
+        let (Some(first), Some(_)) = (vowel.next(), vowel.next()) else {
+            return 0;
+        };
+        let glide = match self.initial.to_lowercase().as_str() {
+            "q" => matches!(first, 'u' | 'U'),
+            "g" => matches!(first, 'i' | 'I'),
+            _ => false,
+        };
+        if glide {
+            first.len_utf8()
+        } else {
+            0
+        }
+    }
+}
+
+impl fmt::Display for Syllable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(ToneStyle::New))
     }
 }
 
@@ -174,14 +263,13 @@ fn apply_modifications(vowel: &str, mods: &[Modification]) -> String {
 }
 
 /// Apply tone mark to vowel
-fn apply_tone(vowel: &str, tone: Option<Tone>) -> String {
+fn apply_tone(vowel: &str, tone: Option<Tone>, style: ToneStyle) -> String {
     let Some(tone) = tone else {
         return vowel.to_string();
     };
 
     // Use smart tone placement rules
-    use crate::rules::{place_tone, ToneStyle};
-    place_tone(vowel, tone, ToneStyle::New)
+    place_tone(vowel, tone, style)
 }
 
 /// Apply tone to a single character
@@ -645,6 +733,21 @@ mod tests {
         assert_eq!(syllable.vowel, "oa");
     }
 
+    #[test]
+    fn test_parse() {
+        let syllable = Syllable::parse("Việt").unwrap();
+        assert_eq!(syllable.initial, "V");
+        assert_eq!(syllable.vowel, "iê");
+        assert_eq!(syllable.final_consonant, "t");
+        assert_eq!(syllable.tone, Some(Tone::Underdot));
+        assert_eq!(syllable.to_string(), "Việt");
+
+        assert_eq!(Syllable::parse("đường").unwrap().initial, "đ");
+        assert!(Syllable::parse("hello").is_none());
+        assert!(Syllable::parse("a1").is_none());
+        assert!(Syllable::parse("").is_none());
+    }
+
     #[test]
     fn test_tone_application() {
         let mut syllable = Syllable::new();
@@ -653,4 +756,14 @@ mod tests {
 
         assert_eq!(syllable.to_string(), "á");
     }
+
+    #[test]
+    fn test_render_after_qu_and_gi() {
+        for word in [
+            "quá", "quý", "quyển", "già", "giữa", "giếng", "gì", "nguyễn",
+        ] {
+            let syllable = Syllable::parse(word).unwrap();
+            assert_eq!(syllable.render(ToneStyle::Old), word);
+        }
+    }
 }
@@ -136,7 +136,7 @@ pub fn is_valid_syllable(initial: &str, vowel: &str, final_cons: &str) -> bool {
     }
 
     // Check VC compatibility
-    let vc_valid = if final_cons.is_empty() {
+    if final_cons.is_empty() {
         true
     } else {
         // Check if ANY pair of (vowel_group, final_group) is valid
@@ -153,9 +153,7 @@ pub fn is_valid_syllable(initial: &str, vowel: &str, final_cons: &str) -> bool {
             }
         }
         found
-    };
-
-    vc_valid
+    }
 }
 
 fn find_group_indices(groups: &[&str], target: &str) -> Vec<usize> {
@@ -199,20 +197,69 @@ pub fn is_permissible_syllable(initial: &str, vowel: &str, final_cons: &str) ->
     let final_cons = final_cons.to_lowercase();
 
     // Check if components are valid prefixes
-    if !is_valid_prefix(&INITIAL_CONSONANTS, &initial) {
-        eprintln!("Invalid initial: {} {:?}", initial, initial.as_bytes());
-        return false;
+    is_valid_prefix(&INITIAL_CONSONANTS, &initial)
+        && is_valid_prefix(&VOWELS, &vowel)
+        && is_valid_prefix(&FINAL_CONSONANTS, &final_cons)
+}
+
+/// Check if a written word could still become a Vietnamese syllable
+pub fn is_permissible_word(word: &str) -> bool {
+    match split_syllable(word) {
+        Some((initial, vowel, final_cons)) => {
+            is_permissible_syllable(&initial, &vowel, &final_cons)
+        }
+        None => false,
     }
-    if !is_valid_prefix(&VOWELS, &vowel) {
-        eprintln!("Invalid vowel: {} {:?}", vowel, vowel.as_bytes());
-        return false;
+}
+
+/// Remove tone marks from a word, keeping letter modifications (tiếng → tiêng)
+pub fn strip_tones(word: &str) -> String {
+    word.nfd()
+        .filter(|c| !matches!(c, '\u{300}' | '\u{301}' | '\u{303}' | '\u{309}' | '\u{323}'))
+        .nfc()
+        .collect()
+}
+
+/// Split a written word into (initial, vowel, final), lowercased and without tone
+///
+/// Returns `None` if the word is not shaped like a single syllable
+/// (e.g. vowels after the final consonant, or characters that are not letters).
+pub fn split_syllable(word: &str) -> Option<(String, String, String)> {
+    let word = strip_tones(word).to_lowercase();
+    if word.is_empty() || !word.chars().all(|c| c.is_alphabetic()) {
+        return None;
     }
-    if !is_valid_prefix(&FINAL_CONSONANTS, &final_cons) {
-        eprintln!("Invalid final: {} {:?}", final_cons, final_cons.as_bytes());
-        return false;
+
+    let chars: Vec<char> = word.chars().collect();
+    let is_vowel = |c: char| "aăâeêioôơuưy".contains(c);
+
+    // Initial consonant; "qu" and "gi" (before a vowel) include a vowel letter
+    let mut i = chars.iter().take_while(|&&c| !is_vowel(c)).count();
+    if (word.starts_with("qu") && chars.len() > 2)
+        || (word.starts_with("gi") && chars.get(2).is_some_and(|&c| is_vowel(c)))
+    {
+        i = 2;
+    }
+
+    let vowel_len = chars[i..].iter().take_while(|&&c| is_vowel(c)).count();
+    let rest = &chars[i + vowel_len..];
+    if rest.iter().any(|&c| is_vowel(c)) {
+        return None;
     }
 
-    true
+    Some((
+        chars[..i].iter().collect(),
+        chars[i..i + vowel_len].iter().collect(),
+        rest.iter().collect(),
+    ))
+}
+
+/// Check if a written word (with tone marks) is a valid Vietnamese syllable
+pub fn is_valid_word(word: &str) -> bool {
+    match split_syllable(word) {
+        Some((initial, vowel, final_cons)) => is_valid_syllable(&initial, &vowel, &final_cons),
+        None => false,
+    }
 }
 
 #[cfg(test)]
@@ -263,4 +310,24 @@ mod tests {
         // dương components
         assert!(is_permissible_syllable("d", "ươ", "ng"));
     }
+
+    #[test]
+    fn test_split_syllable() {
+        let split = |w| split_syllable(w).unwrap();
+        assert_eq!(split("tiếng"), ("t".into(), "iê".into(), "ng".into()));
+        assert_eq!(split("Quyết"), ("qu".into(), "yê".into(), "t".into()));
+        assert_eq!(split("gìn"), ("g".into(), "i".into(), "n".into()));
+        assert_eq!(split("giữa"), ("gi".into(), "ưa".into(), "".into()));
+        assert_eq!(split_syllable("windows"), None);
+        assert_eq!(split_syllable("a1"), None);
+    }
+
+    #[test]
+    fn test_valid_words() {
+        assert!(is_valid_word("tiếng"));
+        assert!(is_valid_word("Việt"));
+        assert!(is_valid_word("được"));
+        assert!(!is_valid_word("đc"));
+        assert!(!is_valid_word("wíndơ"));
+    }
 }
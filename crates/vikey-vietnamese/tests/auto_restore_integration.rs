@@ -0,0 +1,203 @@
+// Integration tests for auto-restore of non-Vietnamese words
+
+use vikey_core::{Action, CompositionMode, Engine};
+use vikey_vietnamese::{AutoRestore, AutoRestoreMode, VietnamesePlugin};
+
+fn create_engine(method: &str, auto_restore: AutoRestore) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .register(Box::new(
+            VietnamesePlugin::new().with_auto_restore(auto_restore),
+        ))
+        .unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method(method).unwrap();
+    engine
+}
+
+/// Simulate the host document: pass-through keys are typed as-is
+fn type_string(engine: &mut Engine, output: &mut String, input: &str) {
+    for c in input.chars() {
+        match engine.process(c) {
+            Action::Commit(text) => output.push_str(&text),
+            Action::Replace {
+                backspace_count,
+                text,
+            } => {
+                for _ in 0..backspace_count {
+                    output.pop();
+                }
+                output.push_str(&text);
+            }
+            Action::PassThrough => output.push(c),
+            _ => {}
+        }
+    }
+}
+
+fn backspace(engine: &mut Engine, output: &mut String) {
+    match engine.process_backspace() {
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            for _ in 0..backspace_count {
+                output.pop();
+            }
+            output.push_str(&text);
+        }
+        Action::PassThrough => {
+            output.pop();
+        }
+        _ => {}
+    }
+}
+
+fn typed(method: &str, auto_restore: AutoRestore, input: &str) -> String {
+    let mut engine = create_engine(method, auto_restore);
+    let mut output = String::new();
+    type_string(&mut engine, &mut output, input);
+    output
+}
+
+#[test]
+fn test_v2_keeps_english_keys() {
+    // Keys that cannot be Vietnamese are no longer dropped
+    assert_eq!(
+        typed("telex_v2", AutoRestore::default(), "windows "),
+        "windows "
+    );
+    assert_eq!(
+        typed("telex_v2", AutoRestore::default(), "hello "),
+        "hello "
+    );
+}
+
+#[test]
+fn test_v2_vietnamese_words_untouched() {
+    assert_eq!(
+        typed("telex_v2", AutoRestore::default(), "tieesng vieetj "),
+        "tiếng việt "
+    );
+}
+
+#[test]
+fn test_v2_restore_on_boundary() {
+    assert_eq!(typed("telex_v2", AutoRestore::default(), "ddc "), "ddc ");
+    assert_eq!(
+        typed("telex_v2", AutoRestore::default(), "ddawe."),
+        "ddawe."
+    );
+}
+
+#[test]
+fn test_v2_off() {
+    let off = AutoRestore::new(AutoRestoreMode::Off);
+    assert_eq!(typed("telex_v2", off, "ddc "), "đc ");
+}
+
+#[test]
+fn test_v2_strict_reject() {
+    let strict = AutoRestore::new(AutoRestoreMode::StrictReject);
+    let mut engine = create_engine("telex_v2", strict);
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "ddaw");
+    assert_eq!(output, "đă");
+
+    // "ăe" cannot start any Vietnamese vowel: revert right away
+    type_string(&mut engine, &mut output, "e");
+    assert_eq!(output, "ddawe");
+
+    // The rest of the word is not transformed
+    type_string(&mut engine, &mut output, "s ");
+    assert_eq!(output, "ddawes ");
+}
+
+#[test]
+fn test_v2_kept_word() {
+    let auto_restore = AutoRestore::default();
+    auto_restore.keep_word("đc");
+    assert_eq!(typed("telex_v2", auto_restore, "ddc "), "đc ");
+}
+
+#[test]
+fn test_word_kept_after_the_engine_is_built() {
+    for method in ["telex", "telex_v2", "vni"] {
+        let auto_restore = AutoRestore::default();
+        let mut engine = create_engine(method, auto_restore.clone());
+        let input = if method == "vni" { "d9c " } else { "ddc " };
+
+        let mut output = String::new();
+        type_string(&mut engine, &mut output, input);
+        assert_eq!(output, input, "{}", method);
+
+        auto_restore.keep_word("đc");
+        let mut output = String::new();
+        type_string(&mut engine, &mut output, input);
+        assert_eq!(output, "đc ", "{}", method);
+    }
+}
+
+#[test]
+fn test_v2_backspace_takes_back_restore() {
+    let mut engine = create_engine("telex_v2", AutoRestore::default());
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "ddc ");
+    assert_eq!(output, "ddc ");
+
+    // "I meant it": backspace brings the composed word back
+    backspace(&mut engine, &mut output);
+    assert_eq!(output, "đc");
+
+    // ...and it is no longer restored on the next boundary
+    type_string(&mut engine, &mut output, " ");
+    assert_eq!(output, "đc ");
+
+    // Only for that word
+    type_string(&mut engine, &mut output, "ddc ");
+    assert_eq!(output, "đc ddc ");
+}
+
+#[test]
+fn test_v2_restore_in_preedit_mode() {
+    let mut engine = create_engine("telex_v2", AutoRestore::default());
+    assert!(engine.set_composition_mode(CompositionMode::Preedit));
+
+    for c in "ddc".chars() {
+        engine.process(c);
+    }
+    assert_eq!(engine.process(' '), Action::Commit("ddc ".to_string()));
+}
+
+#[test]
+fn test_telex_restore_on_boundary() {
+    assert_eq!(
+        typed("telex", AutoRestore::default(), "windows "),
+        "windows "
+    );
+    assert_eq!(typed("telex", AutoRestore::default(), "tuowngs "), "tướng ");
+}
+
+#[test]
+fn test_telex_off() {
+    let off = AutoRestore::new(AutoRestoreMode::Off);
+    assert_eq!(typed("telex", off, "dd "), "đ ");
+}
+
+#[test]
+fn test_telex_strict_reject() {
+    let strict = AutoRestore::new(AutoRestoreMode::StrictReject);
+    let mut engine = create_engine("telex", strict);
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "windo");
+    assert_eq!(output, "windo");
+
+    type_string(&mut engine, &mut output, "w");
+    assert_eq!(output, "window");
+
+    type_string(&mut engine, &mut output, "s ");
+    assert_eq!(output, "windows ");
+}
@@ -0,0 +1,83 @@
+// Integration tests for the composition core shared by Telex, VNI and VIQR
+
+use vikey_core::{Action, Engine};
+use vikey_vietnamese::VietnamesePlugin;
+
+fn create_engine(method: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method(method).unwrap();
+    engine
+}
+
+fn process_string(engine: &mut Engine, input: &str) -> String {
+    let mut output = String::new();
+    for c in input.chars() {
+        match engine.process(c) {
+            Action::PassThrough => output.push(c),
+            Action::Commit(text) => output.push_str(&text),
+            Action::Replace {
+                backspace_count,
+                text,
+            } => {
+                for _ in 0..backspace_count {
+                    output.pop();
+                }
+                output.push_str(&text);
+            }
+            _ => {}
+        }
+    }
+    output
+}
+
+#[test]
+fn test_same_words_in_every_method() {
+    let words = [
+        ("nguyễn", "nguyeenx ", "nguye6n4 ", "nguye^~n "),
+        ("quá", "quas ", "qua1 ", "qua' "),
+        ("già", "giaf ", "gia2 ", "gia` "),
+        ("giữa", "giuwax ", "giu7a4 ", "giu+~a "),
+        ("hoặc", "hoawcj ", "hoa8c5 ", "hoa(.c "),
+        ("được", "dduwowcj ", "d9u7o7c5 ", "ddu+o+.c "),
+        ("người", "nguwowif ", "nguo7i2 ", "ngu+o+`i "),
+        ("khuỷu", "khuyru ", "khuy3u ", "khuy?u "),
+        ("lươn", "luwown ", "luo7n ", "luo+n "),
+    ];
+
+    for (word, telex, vni, viqr) in words {
+        for (method, keys) in [
+            ("telex", telex),
+            ("telex_v2", telex),
+            ("vni", vni),
+            ("viqr", viqr),
+        ] {
+            let mut engine = create_engine(method);
+            assert_eq!(
+                process_string(&mut engine, keys),
+                format!("{} ", word),
+                "{} {}",
+                method,
+                keys
+            );
+        }
+    }
+}
+
+#[test]
+fn test_uo_takes_the_horn() {
+    // Telex V2 waits for the word to go on before the "u" takes its horn
+    for (method, keys, horn) in [
+        ("telex", "tuow", "tươ"),
+        ("telex_v2", "tuow", "tuơ"),
+        ("vni", "tuo7", "tươ"),
+        ("viqr", "tuo+", "tươ"),
+    ] {
+        let mut engine = create_engine(method);
+        assert_eq!(process_string(&mut engine, keys), horn, "{}", method);
+        engine.reset();
+        let keys = format!("{}ng", keys);
+        assert_eq!(process_string(&mut engine, &keys), "tương", "{}", method);
+    }
+}
@@ -0,0 +1,221 @@
+// Integration tests for engine configuration and plugin options
+
+use vikey_core::{Action, Config, ConfigError, Engine, KeyBinding, OptionValue};
+use vikey_vietnamese::VietnamesePlugin;
+
+fn create_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine
+}
+
+/// Apply an action to the simulated document
+fn apply(output: &mut String, key: char, action: Action) {
+    match action {
+        Action::Commit(text) => output.push_str(&text),
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            for _ in 0..backspace_count {
+                output.pop();
+            }
+            output.push_str(&text);
+        }
+        Action::PassThrough => output.push(key),
+        _ => {}
+    }
+}
+
+fn type_string(engine: &mut Engine, input: &str) -> String {
+    let mut output = String::new();
+    for c in input.chars() {
+        let action = engine.process(c);
+        apply(&mut output, c, action);
+    }
+    output
+}
+
+#[test]
+fn test_config_from_toml() {
+    let mut engine = create_engine();
+    let config = Config::from_toml_str(
+        r#"
+        language = "vietnamese"
+        input_method = "telex_v2"
+
+        [plugins.vietnamese]
+        tone_style = "old"
+        auto_restore = "off"
+        "#,
+    )
+    .unwrap();
+    engine.set_config(config).unwrap();
+
+    assert_eq!(engine.current_input_method(), Some("telex_v2"));
+    assert_eq!(type_string(&mut engine, "hoaf thuys "), "hòa thúy ");
+    assert_eq!(type_string(&mut engine, "ddc "), "đc ");
+}
+
+#[test]
+fn test_live_option_change() {
+    let mut engine = create_engine();
+    let mut config = Config {
+        language: Some("vietnamese".to_string()),
+        input_method: Some("telex_v2".to_string()),
+        ..Config::default()
+    };
+    engine.set_config(config.clone()).unwrap();
+    assert_eq!(type_string(&mut engine, "ddc "), "ddc ");
+
+    // The tray's "auto correct" toggle
+    config.set_plugin_option("vietnamese", "auto_restore", "off");
+    engine.set_config(config.clone()).unwrap();
+    assert_eq!(type_string(&mut engine, "ddc "), "đc ");
+
+    // Options left out go back to their defaults
+    config.plugins.clear();
+    engine.set_config(config).unwrap();
+    assert_eq!(type_string(&mut engine, "ddc "), "ddc ");
+}
+
+#[test]
+fn test_option_change_drops_composition() {
+    let mut engine = create_engine();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method("telex_v2").unwrap();
+    type_string(&mut engine, "hoa");
+
+    let mut config = engine.config();
+    config.set_plugin_option("vietnamese", "tone_style", "old");
+    engine.set_config(config).unwrap();
+    assert!(engine.buffer_content().is_empty());
+
+    // Same configuration again: nothing to rebuild
+    type_string(&mut engine, "hoa");
+    engine.set_config(engine.config()).unwrap();
+    assert_eq!(engine.buffer_content(), "hoa");
+}
+
+#[test]
+fn test_config_reports_plugin_options() {
+    let mut engine = create_engine();
+    engine.set_language("vietnamese").unwrap();
+
+    let config = engine.config();
+    assert_eq!(config.language.as_deref(), Some("vietnamese"));
+    assert_eq!(
+        config.plugin_option("vietnamese", "tone_style"),
+        Some(&OptionValue::from("new"))
+    );
+    assert_eq!(
+        config.plugin_option("vietnamese", "auto_restore"),
+        Some(&OptionValue::from("restore_on_boundary"))
+    );
+}
+
+#[test]
+fn test_invalid_config_is_not_applied() {
+    let mut engine = create_engine();
+    engine.set_language("vietnamese").unwrap();
+    let before = engine.config();
+
+    let mut config = Config {
+        input_method: Some("telex_v3".to_string()),
+        ..before.clone()
+    };
+    config
+        .set_plugin_option("vietnamese", "tone_style", "modern")
+        .set_plugin_option("vietnamese", "spelling", true);
+
+    let errors = engine.validate_config(&config);
+    assert_eq!(
+        errors,
+        vec![
+            ConfigError::UnknownInputMethod {
+                language: "vietnamese".to_string(),
+                method: "telex_v3".to_string(),
+            },
+            ConfigError::UnknownOption {
+                plugin: "vietnamese".to_string(),
+                key: "spelling".to_string(),
+            },
+            ConfigError::InvalidOption {
+                plugin: "vietnamese".to_string(),
+                key: "tone_style".to_string(),
+                message: "expected one of \"new\", \"old\", got \"modern\"".to_string(),
+            },
+        ]
+    );
+    assert_eq!(engine.set_config(config), Err(errors));
+    assert_eq!(engine.config(), before);
+}
+
+#[test]
+fn test_valid_entries_are_applied_next_to_invalid_ones() {
+    let mut engine = create_engine();
+    engine.set_language("vietnamese").unwrap();
+
+    let config = Config::from_toml_str(
+        r#"
+        language = "vietnamese"
+        input_method = "vni"
+
+        [plugins.vietnamese]
+        tone_style = "modern"
+        auto_restore = "off"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        engine.set_config(config),
+        Err(vec![ConfigError::InvalidOption {
+            plugin: "vietnamese".to_string(),
+            key: "tone_style".to_string(),
+            message: "expected one of \"new\", \"old\", got \"modern\"".to_string(),
+        }])
+    );
+
+    let config = engine.config();
+    assert_eq!(config.input_method.as_deref(), Some("vni"));
+    assert_eq!(
+        config.plugin_option("vietnamese", "tone_style"),
+        Some(&OptionValue::from("new"))
+    );
+    assert_eq!(
+        config.plugin_option("vietnamese", "auto_restore"),
+        Some(&OptionValue::from("off"))
+    );
+    assert_eq!(type_string(&mut engine, "hoa2 "), "hoà ");
+}
+
+#[test]
+fn test_disabled_and_keys() {
+    let mut engine = create_engine();
+    let config = Config::from_toml_str(
+        r#"
+        language = "vietnamese"
+        enabled = false
+
+        [keys]
+        undo = "Ctrl+Shift+Z"
+        "#,
+    )
+    .unwrap();
+    engine.set_config(config).unwrap();
+
+    assert_eq!(engine.process('a'), Action::PassThrough);
+    assert_eq!(type_string(&mut engine, "vieetj"), "vieetj");
+    assert_eq!(engine.undo_key(), "Ctrl+Shift+Z".parse::<KeyBinding>().ok());
+
+    engine.set_enabled(true);
+    assert_eq!(type_string(&mut engine, "vieetj"), "việt");
+}
+
+#[test]
+fn test_parse_error_points_to_line() {
+    let error =
+        Config::from_toml_str("language = \"vietnamese\"\n\n[keys]\nundo = 5\n").unwrap_err();
+    assert!(matches!(error, ConfigError::Parse(_)));
+    assert!(error.to_string().contains("line 4"), "{}", error);
+}
@@ -0,0 +1,140 @@
+// Integration tests for the output encoding stage on top of real input methods
+
+use vikey_core::{Action, Config, ConfigError, Engine, SessionManager};
+use vikey_vietnamese::{encoding, Charset, OutputEncoding, VietnamesePlugin};
+
+fn create_engine(method: &str, charset: Charset) -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method(method).unwrap();
+    engine
+        .add_stage(Box::new(OutputEncoding::new(charset)))
+        .unwrap();
+    engine
+}
+
+/// Apply an action to the simulated document
+fn apply(document: &mut String, key: Option<char>, action: Action) {
+    match action {
+        Action::PassThrough => document.extend(key),
+        Action::Commit(text) => document.push_str(&text),
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            for _ in 0..backspace_count {
+                document.pop();
+            }
+            document.push_str(&text);
+        }
+        _ => {}
+    }
+}
+
+fn type_string(engine: &mut Engine, document: &mut String, input: &str) {
+    for c in input.chars() {
+        let action = engine.process(c);
+        apply(document, Some(c), action);
+    }
+}
+
+#[test]
+fn test_document_is_written_in_charset() {
+    for method in ["telex", "telex_v2"] {
+        for charset in Charset::ALL {
+            let mut engine = create_engine(method, charset);
+            let mut document = String::new();
+            type_string(&mut engine, &mut document, "Tieengs Vieejt ddaay thuw ");
+            assert_eq!(
+                document,
+                charset.encode("Tiếng Việt đây thư "),
+                "{} / {}",
+                method,
+                charset
+            );
+        }
+    }
+}
+
+#[test]
+fn test_backspace_in_two_byte_charset() {
+    let mut engine = create_engine("telex_v2", Charset::VniWindows);
+    let mut document = String::new();
+    type_string(&mut engine, &mut document, "vieej");
+    assert_eq!(document, "vieä");
+
+    let action = engine.process_backspace();
+    apply(&mut document, None, action);
+    type_string(&mut engine, &mut document, "t ");
+    assert_eq!(
+        document,
+        Charset::VniWindows.encode(&format!("{} ", engine_word()))
+    );
+}
+
+/// What "vieej", Backspace, "t" composes to in Unicode
+fn engine_word() -> String {
+    let mut engine = create_engine("telex_v2", Charset::Unicode);
+    let mut document = String::new();
+    type_string(&mut engine, &mut document, "vieej");
+    let action = engine.process_backspace();
+    apply(&mut document, None, action);
+    type_string(&mut engine, &mut document, "t");
+    document
+}
+
+#[test]
+fn test_charset_from_config() {
+    let mut engine = create_engine("telex_v2", Charset::Unicode);
+    let mut config = engine.config();
+    config.set_stage_option(encoding::ID, "charset", "tcvn3");
+    engine.set_config(config).unwrap();
+
+    let mut document = String::new();
+    type_string(&mut engine, &mut document, "ddaay ");
+    assert_eq!(document, "\u{AE}\u{A9}y ");
+    assert_eq!(
+        engine.config().stage_option(encoding::ID, "charset"),
+        Some(&"tcvn3".into())
+    );
+
+    let mut config = Config::default();
+    config.set_stage_option(encoding::ID, "charset", "vps");
+    assert!(matches!(
+        engine.validate_config(&config)[..],
+        [ConfigError::InvalidOption { .. }]
+    ));
+}
+
+#[test]
+fn test_charset_per_context() {
+    let mut sessions = SessionManager::new(create_engine("telex_v2", Charset::Unicode));
+    let mut legacy = String::new();
+    let mut modern = String::new();
+
+    sessions.focus(1);
+    sessions
+        .engine_mut()
+        .set_session_option(encoding::ID, "charset", Some("vni-windows".into()))
+        .unwrap();
+    type_string(sessions.engine_mut(), &mut legacy, "xin chaof ");
+
+    sessions.focus(2);
+    type_string(sessions.engine_mut(), &mut modern, "xin chaof ");
+
+    // The override comes back with its context, even after a config reload
+    let config = sessions.engine().config();
+    sessions.set_config(config).unwrap();
+    sessions.focus(1);
+    type_string(sessions.engine_mut(), &mut legacy, "bajn");
+
+    assert_eq!(legacy, "xin chaøo baïn");
+    assert_eq!(modern, "xin chào ");
+
+    sessions.destroy(1);
+    sessions.focus(1);
+    let mut fresh = String::new();
+    type_string(sessions.engine_mut(), &mut fresh, "chaof");
+    assert_eq!(fresh, "chào");
+}
@@ -0,0 +1,176 @@
+// Integration tests for engine events (observer API)
+
+use std::sync::{Arc, Mutex};
+use vikey_core::{CompositionMode, Engine, EngineEvent, KeyEvent, Modifiers};
+use vikey_vietnamese::{AutoRestore, AutoRestoreMode, VietnamesePlugin};
+
+type Events = Arc<Mutex<Vec<EngineEvent>>>;
+
+fn create_engine(plugin: VietnamesePlugin) -> (Engine, Events) {
+    let mut engine = Engine::new();
+    engine.register(Box::new(plugin)).unwrap();
+
+    let events = Events::default();
+    let sink = events.clone();
+    engine.subscribe(Box::new(move |event: &EngineEvent| {
+        sink.lock().unwrap().push(event.clone());
+    }));
+
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method("telex_v2").unwrap();
+    events.lock().unwrap().clear();
+    (engine, events)
+}
+
+fn take(events: &Events) -> Vec<EngineEvent> {
+    std::mem::take(&mut *events.lock().unwrap())
+}
+
+fn committed(events: &[EngineEvent]) -> Vec<&str> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            EngineEvent::Committed { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn type_string(engine: &mut Engine, input: &str) {
+    for c in input.chars() {
+        engine.process(c);
+    }
+}
+
+#[test]
+fn test_language_and_method_changed() {
+    let (mut engine, events) = create_engine(VietnamesePlugin::new());
+
+    engine.set_input_method("vni").unwrap();
+    engine.set_language("vietnamese").unwrap();
+
+    assert_eq!(
+        take(&events),
+        vec![
+            EngineEvent::MethodChanged {
+                method: "vni".to_string()
+            },
+            EngineEvent::LanguageChanged {
+                language: "vietnamese".to_string()
+            },
+            EngineEvent::MethodChanged {
+                method: "telex".to_string()
+            },
+        ]
+    );
+
+    // Failed switches emit nothing
+    assert!(engine.set_input_method("unknown").is_err());
+    assert!(take(&events).is_empty());
+}
+
+#[test]
+fn test_replaced_and_committed() {
+    let (mut engine, events) = create_engine(VietnamesePlugin::new());
+
+    type_string(&mut engine, "vieets ");
+    let events = take(&events);
+
+    assert!(events.contains(&EngineEvent::Replaced {
+        backspace_count: 3,
+        text: "viê".to_string(),
+    }));
+    assert_eq!(committed(&events), vec!["viết"]);
+}
+
+#[test]
+fn test_committed_preedit() {
+    let (mut engine, events) = create_engine(VietnamesePlugin::new());
+    assert!(engine.set_composition_mode(CompositionMode::Preedit));
+
+    type_string(&mut engine, "ddi ");
+    type_string(&mut engine, "ab");
+    engine.process(KeyEvent::from_char('c').with_modifiers(Modifiers::CONTROL));
+
+    let events = take(&events);
+    assert_eq!(committed(&events), vec!["đi", "ab"]);
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, EngineEvent::Replaced { .. })));
+}
+
+#[test]
+fn test_committed_after_auto_restore() {
+    let (mut engine, events) = create_engine(VietnamesePlugin::new());
+
+    type_string(&mut engine, "ddc ");
+    assert_eq!(committed(&take(&events)), vec!["ddc "]);
+}
+
+#[test]
+fn test_nested_calls_emit_once() {
+    let (mut engine, events) = create_engine(VietnamesePlugin::new());
+
+    type_string(&mut engine, "tos");
+    take(&events);
+
+    // Ctrl+Z goes through process() and undo()
+    engine.process(KeyEvent::from_char('z').with_modifiers(Modifiers::CONTROL));
+    assert_eq!(
+        take(&events),
+        vec![EngineEvent::Replaced {
+            backspace_count: 1,
+            text: "o".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_rejected() {
+    let (mut engine, events) = create_engine(VietnamesePlugin::new());
+
+    // "w" cannot start a Vietnamese word; later keys are not rejected again
+    type_string(&mut engine, "wind");
+    let rejected: Vec<_> = take(&events)
+        .into_iter()
+        .filter(|e| matches!(e, EngineEvent::Rejected { .. }))
+        .collect();
+
+    assert_eq!(
+        rejected,
+        vec![EngineEvent::Rejected {
+            key: KeyEvent::from_char('w'),
+            word: "w".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_rejected_strict() {
+    let plugin =
+        VietnamesePlugin::new().with_auto_restore(AutoRestore::new(AutoRestoreMode::StrictReject));
+    let (mut engine, events) = create_engine(plugin);
+
+    type_string(&mut engine, "ddc");
+    let rejected: Vec<_> = take(&events)
+        .into_iter()
+        .filter(|e| matches!(e, EngineEvent::Rejected { .. }))
+        .collect();
+
+    assert_eq!(rejected.len(), 1);
+}
+
+#[test]
+fn test_unsubscribe() {
+    let mut engine = Engine::new();
+    let events = Events::default();
+    let sink = events.clone();
+    let id = engine.subscribe(Box::new(move |event: &EngineEvent| {
+        sink.lock().unwrap().push(event.clone());
+    }));
+
+    assert!(engine.unsubscribe(id));
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    assert!(take(&events).is_empty());
+}
@@ -0,0 +1,103 @@
+// Integration tests for host lifecycle events (focus, caret, mouse)
+
+use vikey_core::{Action, CompositionMode, Engine, InterruptPolicy, KeyCode, KeyEvent};
+use vikey_vietnamese::VietnamesePlugin;
+
+fn create_engine(mode: CompositionMode) -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method("telex_v2").unwrap();
+    assert!(engine.set_composition_mode(mode));
+    engine
+}
+
+fn type_keys(engine: &mut Engine, input: &str) -> Action {
+    let mut last = Action::DoNothing;
+    for c in input.chars() {
+        last = engine.process(c);
+    }
+    last
+}
+
+#[test]
+fn test_focus_out_commits_preedit() {
+    let mut engine = create_engine(CompositionMode::Preedit);
+    type_keys(&mut engine, "vieet");
+
+    assert_eq!(engine.on_focus_out(), Action::CommitPreedit);
+    assert!(engine.buffer_content().is_empty());
+}
+
+#[test]
+fn test_discard_policy_clears_preedit() {
+    let mut engine = create_engine(CompositionMode::Preedit);
+    engine.set_interrupt_policy(InterruptPolicy::Discard);
+    type_keys(&mut engine, "vieet");
+
+    assert_eq!(engine.on_mouse_click(), Action::preedit(String::new()));
+    assert!(engine.buffer_content().is_empty());
+}
+
+#[test]
+fn test_cursor_moved_starts_new_word() {
+    let mut engine = create_engine(CompositionMode::Direct);
+    type_keys(&mut engine, "to");
+
+    assert_eq!(engine.on_cursor_moved(), Action::DoNothing);
+    assert!(!engine.can_undo());
+
+    // "s" must not put a tone on the "o" typed before the caret moved
+    let action = engine.process('s');
+    assert_eq!(
+        action,
+        Action::Replace {
+            backspace_count: 0,
+            text: "s".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_key_without_text_starts_new_word() {
+    // A punctuation key the frontend could not read still reached the
+    // application, so "j" must not put a tone on "viet"
+    for code in [KeyCode::Other(0xBE), KeyCode::Function(5)] {
+        let mut engine = create_engine(CompositionMode::Direct);
+        type_keys(&mut engine, "viet");
+        assert_eq!(
+            engine.process(KeyEvent::new(code, None)),
+            Action::PassThrough
+        );
+        assert_eq!(
+            engine.process('j'),
+            Action::Replace {
+                backspace_count: 0,
+                text: "j".to_string(),
+            }
+        );
+    }
+
+    // Pressing Shift alone keeps the word
+    let mut engine = create_engine(CompositionMode::Direct);
+    type_keys(&mut engine, "vieet");
+    engine.process(KeyEvent::new(KeyCode::Modifier, None));
+    type_keys(&mut engine, "j");
+    assert_eq!(engine.buffer_content(), "việt");
+}
+
+#[test]
+fn test_focus_in_drops_stale_composition() {
+    let mut engine = create_engine(CompositionMode::Preedit);
+    type_keys(&mut engine, "ddi");
+
+    assert_eq!(engine.on_focus_in(), Action::CommitPreedit);
+    assert_eq!(engine.on_focus_in(), Action::DoNothing);
+}
+
+#[test]
+fn test_events_without_composition() {
+    let mut engine = create_engine(CompositionMode::Preedit);
+    assert_eq!(engine.on_focus_out(), Action::DoNothing);
+    assert_eq!(engine.on_mouse_click(), Action::DoNothing);
+}
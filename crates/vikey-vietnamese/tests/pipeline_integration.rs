@@ -0,0 +1,90 @@
+// Integration tests for the middleware pipeline on top of a real input method
+
+use std::sync::{Arc, Mutex};
+use vikey_core::{Action, Engine, EngineEvent, Middleware, StageContext};
+use vikey_vietnamese::VietnamesePlugin;
+
+/// Wraps every finished word in brackets, keeping the separator
+struct Brackets;
+
+impl Middleware for Brackets {
+    fn id(&self) -> &str {
+        "brackets"
+    }
+
+    fn process(&mut self, action: Action, context: &StageContext) -> Action {
+        if !context.word_ended() {
+            return action;
+        }
+        let separator = match &action {
+            Action::PassThrough => context.key.and_then(|key| key.char()),
+            Action::Commit(text) => text.chars().next(),
+            _ => None,
+        };
+        match separator {
+            Some(c) => Action::Replace {
+                backspace_count: context.before.chars().count(),
+                text: format!("[{}]{}", context.before, c),
+            },
+            None => action,
+        }
+    }
+}
+
+fn create_engine(method: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method(method).unwrap();
+    engine.add_stage(Box::new(Brackets)).unwrap();
+    engine
+}
+
+fn type_string(engine: &mut Engine, input: &str) -> Vec<Action> {
+    input.chars().map(|c| engine.process(c)).collect()
+}
+
+#[test]
+fn test_stage_rewrites_word_end() {
+    for method in ["telex", "telex_v2"] {
+        let mut engine = create_engine(method);
+        let actions = type_string(&mut engine, "vieets ");
+        assert_eq!(
+            actions.last(),
+            Some(&Action::Replace {
+                backspace_count: 4,
+                text: "[viết] ".to_string(),
+            }),
+            "{}",
+            method
+        );
+        // Keys inside the word are left alone
+        assert!(!matches!(&actions[0], Action::Replace { text, .. } if text.contains('[')));
+    }
+}
+
+#[test]
+fn test_observers_see_rewritten_action() {
+    let mut engine = create_engine("telex_v2");
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    engine.subscribe(Box::new(move |event: &EngineEvent| {
+        sink.lock().unwrap().push(event.clone());
+    }));
+
+    type_string(&mut engine, "dduowngf.");
+    assert!(events.lock().unwrap().contains(&EngineEvent::Committed {
+        text: "[đường].".to_string()
+    }));
+}
+
+#[test]
+fn test_disabled_stage_is_skipped() {
+    let mut engine = create_engine("telex_v2");
+    let mut config = engine.config();
+    config.set_stage_option("brackets", "enabled", false);
+    engine.set_config(config).unwrap();
+
+    let actions = type_string(&mut engine, "vieets ");
+    assert_eq!(actions.last(), Some(&Action::PassThrough));
+}
@@ -0,0 +1,137 @@
+// Integration tests for Engine::restore_raw (revert the word to the keys typed)
+
+use vikey_core::{Action, CompositionMode, Engine, KeyBinding, KeyCode, KeyEvent, Modifiers};
+use vikey_vietnamese::VietnamesePlugin;
+
+fn create_engine(method: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method(method).unwrap();
+    engine
+}
+
+/// Apply an action to the simulated document
+fn apply(output: &mut String, action: Action) {
+    match action {
+        Action::Commit(text) => output.push_str(&text),
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            for _ in 0..backspace_count {
+                output.pop();
+            }
+            output.push_str(&text);
+        }
+        _ => {}
+    }
+}
+
+fn type_string(engine: &mut Engine, output: &mut String, input: &str) {
+    for c in input.chars() {
+        let action = engine.process(c);
+        apply(output, action);
+    }
+}
+
+fn escape() -> KeyEvent {
+    KeyEvent::new(KeyCode::Escape, None)
+}
+
+#[test]
+fn test_escape_restores_raw_telex_v2() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "tieesng");
+    assert_eq!(output, "tiếng");
+
+    apply(&mut output, engine.process(escape()));
+    assert_eq!(output, "tieesng");
+
+    // The word is finished: nothing left to restore or undo
+    assert!(!engine.can_restore_raw());
+    assert!(!engine.can_undo());
+    assert!(engine.buffer_content().is_empty());
+}
+
+#[test]
+fn test_restore_raw_keeps_case() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "DDaau");
+    assert_eq!(output, "Đâu");
+
+    apply(&mut output, engine.restore_raw());
+    assert_eq!(output, "DDaau");
+}
+
+#[test]
+fn test_restore_raw_telex() {
+    let mut engine = create_engine("telex");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "dd");
+    assert_eq!(output, "đ");
+
+    apply(&mut output, engine.restore_raw());
+    assert_eq!(output, "dd");
+}
+
+#[test]
+fn test_escape_without_transformation_passes_through() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "ban");
+    assert!(!engine.can_restore_raw());
+    assert_eq!(engine.process(escape()), Action::PassThrough);
+    assert_eq!(output, "ban");
+}
+
+#[test]
+fn test_restore_raw_after_word_boundary() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "as ");
+    assert_eq!(engine.restore_raw(), Action::PassThrough);
+}
+
+#[test]
+fn test_configurable_restore_chord() {
+    let mut engine = create_engine("telex_v2");
+    let chord = KeyBinding::new(KeyCode::Char('r'), Modifiers::CONTROL | Modifiers::SHIFT);
+    engine.set_restore_key(Some(chord));
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "tooi");
+    assert_eq!(output, "tôi");
+
+    // Escape no longer restores
+    assert_eq!(engine.process(escape()), Action::PassThrough);
+
+    let mut output = String::new();
+    type_string(&mut engine, &mut output, "tooi");
+    let key = KeyEvent::new(KeyCode::Char('r'), Some('R'))
+        .with_modifiers(Modifiers::CONTROL | Modifiers::SHIFT);
+    apply(&mut output, engine.process(key));
+    assert_eq!(output, "tooi");
+}
+
+#[test]
+fn test_restore_raw_in_preedit_mode() {
+    let mut engine = create_engine("telex_v2");
+    assert!(engine.set_composition_mode(CompositionMode::Preedit));
+
+    for c in "vieetj".chars() {
+        engine.process(c);
+    }
+
+    assert_eq!(
+        engine.process(escape()),
+        Action::Commit("vieetj".to_string())
+    );
+}
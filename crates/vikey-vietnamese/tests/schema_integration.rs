@@ -0,0 +1,163 @@
+// Integration tests for user-defined input methods loaded from schema files
+
+use std::fs;
+use std::path::PathBuf;
+use vikey_core::{Action, Engine};
+use vikey_vietnamese::VietnamesePlugin;
+
+/// Fresh, empty directory for one test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vikey-schema-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Apply an action to the simulated document
+fn apply(output: &mut String, key: char, action: Action) {
+    match action {
+        Action::PassThrough => output.push(key),
+        Action::Commit(text) => output.push_str(&text),
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            for _ in 0..backspace_count {
+                output.pop();
+            }
+            output.push_str(&text);
+        }
+        _ => {}
+    }
+}
+
+fn type_string(engine: &mut Engine, input: &str) -> String {
+    let mut output = String::new();
+    for c in input.chars() {
+        let action = engine.process(c);
+        apply(&mut output, c, action);
+    }
+    output
+}
+
+const SIMPLE_TELEX: &str = r#"
+[method]
+id = "simple_telex"
+name = "Simple Telex"
+description = "Telex with [ for ơ, ] for ư and w alone for ư"
+
+[tones]
+s = "acute"
+f = "grave"
+r = "hook_above"
+x = "tilde"
+j = "underdot"
+z = "none"
+
+[marks]
+a = "circumflex"
+e = "circumflex"
+o = "circumflex"
+w = ["horn", "breve"]
+d = "dstroke"
+
+[shortcuts]
+"[" = "ơ"
+"]" = "ư"
+w = "ư"
+
+[options]
+repeat = "takes_off_right_away"
+"#;
+
+const VIQR_CARET: &str = r#"
+method:
+  id: viqr_caret
+  name: VIQR (caret)
+tones:
+  "'": sắc
+  "`": huyền
+  "?": hỏi
+  "~": ngã
+  ".": nặng
+marks:
+  "^": circumflex
+  "(": breve
+  "*": horn
+  d: dstroke
+options:
+  placement: after_letter
+  repeat: takes_off_right_away
+  escape: "\\"
+"#;
+
+#[test]
+fn test_schemas_are_registered_with_the_builtins() {
+    let dir = temp_dir("registered");
+    fs::write(dir.join("simple-telex.toml"), SIMPLE_TELEX).unwrap();
+    fs::write(dir.join("viqr-caret.yaml"), VIQR_CARET).unwrap();
+    fs::write(dir.join("notes.txt"), "not a schema").unwrap();
+
+    let mut plugin = VietnamesePlugin::new();
+    assert_eq!(plugin.load_schemas(&dir), []);
+    let mut engine = Engine::new();
+    engine.register(Box::new(plugin)).unwrap();
+    engine.set_language("vietnamese").unwrap();
+
+    assert_eq!(
+        engine.input_methods(),
+        [
+            "telex",
+            "telex_v2",
+            "vni",
+            "viqr",
+            "simple_telex",
+            "viqr_caret"
+        ]
+    );
+
+    engine.set_input_method("simple_telex").unwrap();
+    assert_eq!(
+        type_string(&mut engine, "Tieengs Vieetj t]f nay c[ng mwa "),
+        "Tiếng Việt từ nay cơng mưa "
+    );
+
+    engine.set_input_method("viqr_caret").unwrap();
+    engine.reset();
+    assert_eq!(
+        type_string(&mut engine, "ddu*o*`ng Ha` No^.i. Ta\\? "),
+        "đường Hà Nội. Ta? "
+    );
+}
+
+#[test]
+fn test_invalid_schemas_are_reported_with_their_line() {
+    let dir = temp_dir("invalid");
+    let invalid = SIMPLE_TELEX.replace("x = \"tilde\"", "x = \"wave\"");
+    fs::write(dir.join("invalid.toml"), invalid).unwrap();
+    let taken = SIMPLE_TELEX.replace("simple_telex", "vni");
+    fs::write(dir.join("taken.toml"), taken).unwrap();
+    fs::write(dir.join("broken.yaml"), "method:\n  id: [\n").unwrap();
+
+    let mut plugin = VietnamesePlugin::new();
+    let errors = plugin.load_schemas(&dir);
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|e| {
+            let file = e.path.as_ref().unwrap().file_name().unwrap();
+            (file.to_str().unwrap().to_string(), e.line)
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            ("broken.yaml".to_string(), Some(2)),
+            ("invalid.toml".to_string(), Some(11)),
+            ("taken.toml".to_string(), Some(3)),
+        ]
+    );
+    assert!(plugin.schemas().is_empty());
+
+    // A directory that does not exist has no schemas
+    assert_eq!(plugin.load_schemas(dir.join("missing")), []);
+}
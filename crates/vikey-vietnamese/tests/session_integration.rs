@@ -0,0 +1,160 @@
+// Integration tests for per-context sessions
+
+use vikey_core::{Action, Engine, SessionManager};
+use vikey_vietnamese::VietnamesePlugin;
+
+fn create_sessions(capacity: usize) -> SessionManager {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method("telex_v2").unwrap();
+    SessionManager::with_capacity(engine, capacity)
+}
+
+/// Apply an action to the simulated document
+fn apply(output: &mut String, action: Action) {
+    match action {
+        Action::Commit(text) => output.push_str(&text),
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            for _ in 0..backspace_count {
+                output.pop();
+            }
+            output.push_str(&text);
+        }
+        _ => {}
+    }
+}
+
+fn type_string(sessions: &mut SessionManager, output: &mut String, input: &str) {
+    for c in input.chars() {
+        let action = sessions.engine_mut().process(c);
+        if action == Action::PassThrough {
+            output.push(c);
+        } else {
+            apply(output, action);
+        }
+    }
+}
+
+#[test]
+fn test_switch_mid_word_keeps_composition() {
+    let mut sessions = create_sessions(8);
+    let mut first = String::new();
+    let mut second = String::new();
+
+    sessions.focus(1);
+    type_string(&mut sessions, &mut first, "vie");
+
+    sessions.focus(2);
+    type_string(&mut sessions, &mut second, "ddi");
+
+    sessions.focus(1);
+    type_string(&mut sessions, &mut first, "ets");
+
+    sessions.focus(2);
+    type_string(&mut sessions, &mut second, "f");
+
+    assert_eq!(first, "viết");
+    assert_eq!(second, "đì");
+}
+
+#[test]
+fn test_new_context_starts_empty() {
+    let mut sessions = create_sessions(8);
+    let mut output = String::new();
+
+    sessions.focus(1);
+    type_string(&mut sessions, &mut output, "ti");
+    sessions.focus(2);
+
+    assert!(sessions.engine().buffer_content().is_empty());
+    assert!(!sessions.engine().can_undo());
+}
+
+#[test]
+fn test_undo_history_is_per_context() {
+    let mut sessions = create_sessions(8);
+    let mut first = String::new();
+    let mut second = String::new();
+
+    sessions.focus(1);
+    type_string(&mut sessions, &mut first, "tos");
+    sessions.focus(2);
+    type_string(&mut sessions, &mut second, "ab");
+    assert!(!sessions.engine().can_undo());
+
+    sessions.focus(1);
+    assert!(sessions.engine().can_undo());
+    let action = sessions.engine_mut().undo();
+    apply(&mut first, action);
+    assert_eq!(first, "to");
+}
+
+#[test]
+fn test_method_change_restarts_parked_context() {
+    let mut sessions = create_sessions(8);
+    let mut first = String::new();
+
+    sessions.focus(1);
+    type_string(&mut sessions, &mut first, "vie");
+    sessions.focus(2);
+    sessions.engine_mut().set_input_method("telex").unwrap();
+
+    sessions.focus(1);
+    assert!(sessions.engine().buffer_content().is_empty());
+}
+
+#[test]
+fn test_config_change_restarts_parked_context() {
+    let mut sessions = create_sessions(8);
+    let mut first = String::new();
+
+    sessions.focus(1);
+    type_string(&mut sessions, &mut first, "vie");
+    sessions.focus(2);
+
+    // Same config: parked composition is kept
+    let config = sessions.engine().config();
+    sessions.set_config(config.clone()).unwrap();
+    sessions.focus(1);
+    assert_eq!(sessions.engine().buffer_content(), "vie");
+
+    sessions.focus(2);
+    let mut config = config;
+    config.set_plugin_option("vietnamese", "tone_style", "old");
+    sessions.set_config(config).unwrap();
+
+    sessions.focus(1);
+    assert!(sessions.engine().buffer_content().is_empty());
+}
+
+#[test]
+fn test_evicted_context_starts_over() {
+    let mut sessions = create_sessions(2);
+    let mut output = String::new();
+
+    sessions.focus(1);
+    type_string(&mut sessions, &mut output, "vie");
+    sessions.focus(2);
+    sessions.focus(3);
+    assert!(!sessions.contains(1));
+
+    sessions.focus(1);
+    assert!(sessions.engine().buffer_content().is_empty());
+}
+
+#[test]
+fn test_destroy_active_context() {
+    let mut sessions = create_sessions(8);
+    let mut output = String::new();
+
+    sessions.focus(1);
+    type_string(&mut sessions, &mut output, "vie");
+    assert!(sessions.destroy(1));
+
+    assert_eq!(sessions.active(), None);
+    assert!(sessions.engine().buffer_content().is_empty());
+}
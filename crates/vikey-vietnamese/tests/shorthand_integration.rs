@@ -0,0 +1,134 @@
+// Integration tests for shorthand expansion on top of the Vietnamese methods
+
+use vikey_core::{Action, CompositionMode, Config, Engine, MacroTable, Shorthand};
+use vikey_vietnamese::VietnamesePlugin;
+
+fn macros() -> MacroTable {
+    let mut table = MacroTable::new();
+    table.insert("vn", "Việt Nam").unwrap();
+    table.insert("ko", "không").unwrap();
+    table.insert("đc", "được").unwrap();
+    table
+}
+
+fn create_engine(method: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine
+        .add_stage(Box::new(Shorthand::new(macros())))
+        .unwrap();
+    engine
+        .set_config(Config {
+            language: Some("vietnamese".to_string()),
+            input_method: Some(method.to_string()),
+            shorthand: true,
+            ..Config::default()
+        })
+        .unwrap();
+    engine
+}
+
+fn type_string(engine: &mut Engine, input: &str) -> Vec<Action> {
+    input.chars().map(|c| engine.process(c)).collect()
+}
+
+#[test]
+fn test_expands_on_word_boundary() {
+    for method in ["telex", "telex_v2", "vni"] {
+        let mut engine = create_engine(method);
+        let actions = type_string(&mut engine, "vn,");
+        assert_eq!(
+            actions.last(),
+            Some(&Action::Replace {
+                backspace_count: 2,
+                text: "Việt Nam,".to_string(),
+            }),
+            "{}",
+            method
+        );
+    }
+}
+
+#[test]
+fn test_preserves_case() {
+    let mut engine = create_engine("telex_v2");
+    let actions = type_string(&mut engine, "Ko KO ");
+    assert_eq!(
+        actions[2],
+        Action::Replace {
+            backspace_count: 2,
+            text: "Không ".to_string(),
+        }
+    );
+    assert_eq!(
+        actions[5],
+        Action::Replace {
+            backspace_count: 2,
+            text: "KHÔNG ".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_composed_key() {
+    // "ddc" is shown as "đc" and would be auto-restored; the macro wins
+    for (method, keys) in [("telex", "ddc "), ("telex_v2", "ddc "), ("vni", "d9c ")] {
+        let mut engine = create_engine(method);
+        let actions = type_string(&mut engine, keys);
+        assert_eq!(
+            actions.last(),
+            Some(&Action::Replace {
+                backspace_count: 2,
+                text: "được ".to_string(),
+            }),
+            "{}",
+            method
+        );
+    }
+}
+
+#[test]
+fn test_no_expansion_inside_words_or_when_disabled() {
+    let mut engine = create_engine("telex_v2");
+    let actions = type_string(&mut engine, "vna ");
+    assert_eq!(actions.last(), Some(&Action::PassThrough));
+
+    let mut config = engine.config();
+    config.shorthand = false;
+    engine.set_config(config).unwrap();
+    assert!(!engine.config().shorthand);
+    let actions = type_string(&mut engine, "vn ");
+    assert_eq!(actions.last(), Some(&Action::PassThrough));
+}
+
+#[test]
+fn test_preedit_commits_expansion() {
+    let mut engine = create_engine("telex_v2");
+    engine.set_composition_mode(CompositionMode::Preedit);
+    let actions = type_string(&mut engine, "vn.");
+    assert_eq!(
+        actions.last(),
+        Some(&Action::Commit("Việt Nam.".to_string()))
+    );
+}
+
+#[test]
+fn test_reloaded_table_keeps_switch() {
+    let mut engine = create_engine("telex_v2");
+    let mut table = MacroTable::new();
+    table.insert("hn", "Hà Nội").unwrap();
+    assert!(engine
+        .replace_stage(Box::new(Shorthand::new(table)))
+        .is_some());
+    assert!(engine.config().shorthand);
+
+    let actions = type_string(&mut engine, "hn vn ");
+    assert_eq!(
+        actions[2],
+        Action::Replace {
+            backspace_count: 2,
+            text: "Hà Nội ".to_string(),
+        }
+    );
+    assert_eq!(actions[5], Action::PassThrough);
+}
@@ -0,0 +1,130 @@
+// Integration tests for re-editing committed words from surrounding text
+
+use vikey_core::{Action, CompositionMode, Engine, KeyCode, KeyEvent};
+use vikey_vietnamese::VietnamesePlugin;
+
+fn create_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method("telex_v2").unwrap();
+    engine
+}
+
+/// Apply an action to the simulated document (text before the caret)
+fn apply(output: &mut String, key: char, action: Action) {
+    match action {
+        Action::Commit(text) => output.push_str(&text),
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            for _ in 0..backspace_count {
+                output.pop();
+            }
+            output.push_str(&text);
+        }
+        Action::PassThrough => output.push(key),
+        _ => {}
+    }
+}
+
+fn type_string(engine: &mut Engine, output: &mut String, input: &str) {
+    for c in input.chars() {
+        let action = engine.process(c);
+        apply(output, c, action);
+    }
+}
+
+#[test]
+fn test_tone_on_committed_word() {
+    let mut engine = create_engine();
+    let mut document = "Tiếng viêt".to_string();
+
+    assert!(engine.set_surrounding_text(&document));
+    type_string(&mut engine, &mut document, "j");
+    assert_eq!(document, "Tiếng việt");
+}
+
+#[test]
+fn test_mark_and_tone_on_committed_word() {
+    let mut engine = create_engine();
+    let mut document = "Tiếng viet".to_string();
+
+    assert!(engine.set_surrounding_text(&document));
+    type_string(&mut engine, &mut document, "ej");
+    assert_eq!(document, "Tiếng việt");
+}
+
+#[test]
+fn test_change_tone_and_mark() {
+    let mut engine = create_engine();
+    let mut document = "tiếng".to_string();
+
+    assert!(engine.set_surrounding_text(&document));
+    type_string(&mut engine, &mut document, "f");
+    assert_eq!(document, "tiềng");
+
+    let mut document = "duong".to_string();
+    engine.reset();
+    assert!(engine.set_surrounding_text(&document));
+    type_string(&mut engine, &mut document, "wf");
+    assert_eq!(document, "dường");
+}
+
+#[test]
+fn test_continue_typing_after_resume() {
+    let mut engine = create_engine();
+    let mut document = "vie".to_string();
+
+    assert!(engine.set_surrounding_text(&document));
+    type_string(&mut engine, &mut document, "ets ");
+    assert_eq!(document, "viết ");
+}
+
+#[test]
+fn test_undo_after_resume() {
+    let mut engine = create_engine();
+    let mut document = "toan".to_string();
+
+    assert!(engine.set_surrounding_text(&document));
+    type_string(&mut engine, &mut document, "f");
+    assert_eq!(document, "toàn");
+
+    let action = engine.undo();
+    apply(&mut document, '\0', action);
+    assert_eq!(document, "toan");
+
+    let action = engine.redo();
+    apply(&mut document, '\0', action);
+    assert_eq!(document, "toàn");
+}
+
+#[test]
+fn test_restore_key_does_not_touch_resumed_word() {
+    let mut engine = create_engine();
+    assert!(engine.set_surrounding_text("việt"));
+    assert!(!engine.can_restore_raw());
+    assert_eq!(
+        engine.process(KeyEvent::new(KeyCode::Escape, None)),
+        Action::PassThrough
+    );
+}
+
+#[test]
+fn test_not_resumed() {
+    let mut engine = create_engine();
+
+    // Caret after a separator or a non-Vietnamese word
+    assert!(!engine.set_surrounding_text("viet "));
+    assert!(!engine.set_surrounding_text("hello"));
+    assert!(!engine.set_surrounding_text(""));
+
+    // A word is already being composed
+    engine.process('t');
+    assert!(!engine.set_surrounding_text("xin"));
+
+    // Preedit: committed text is not part of the composition
+    assert!(engine.set_composition_mode(CompositionMode::Preedit));
+    assert!(!engine.set_surrounding_text("viet"));
+}
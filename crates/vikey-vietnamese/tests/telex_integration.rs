@@ -30,7 +30,7 @@ fn process_string(engine: &mut Engine, input: &str) -> String {
                 }
                 output.push_str(&text);
             }
-            Action::DoNothing => {}
+            _ => {}
         }
     }
 
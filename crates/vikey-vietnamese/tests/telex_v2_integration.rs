@@ -30,7 +30,7 @@ fn process_string(engine: &mut Engine, input: &str) -> String {
                 }
                 output.push_str(&text);
             }
-            Action::DoNothing => {}
+            _ => {}
         }
     }
 
@@ -157,3 +157,44 @@ fn test_v2_word_building() {
 
     assert_eq!(process_string(&mut engine, "tuowngs"), "tướng"); // tone
 }
+
+#[test]
+fn test_v2_preedit_mode() {
+    use vikey_core::{CompositionMode, KeyEvent, Modifiers};
+
+    let mut engine = create_engine_v2();
+    assert!(engine.set_composition_mode(CompositionMode::Preedit));
+    assert_eq!(engine.composition_mode(), CompositionMode::Preedit);
+
+    let mut preedit = String::new();
+    for c in "vieetj".chars() {
+        match engine.process(c) {
+            Action::UpdatePreedit { text, .. } => preedit = text,
+            other => panic!("Expected preedit update, got {:?}", other),
+        }
+    }
+    assert_eq!(preedit, "việt");
+
+    // Space commits the composition on the word boundary
+    assert_eq!(engine.process(' '), Action::CommitPreedit);
+
+    // A shortcut chord commits the pending preedit before passing through
+    engine.process('a');
+    let ctrl_s = KeyEvent::from_char('s').with_modifiers(Modifiers::CONTROL);
+    assert_eq!(engine.process(ctrl_s), Action::CommitPreedit);
+    assert_eq!(engine.process(ctrl_s), Action::PassThrough);
+}
+
+#[test]
+fn test_v2_preedit_mode_unsupported_method() {
+    use vikey_core::CompositionMode;
+
+    let mut engine = create_engine_v2();
+    engine.set_input_method("telex").unwrap();
+    assert!(!engine.set_composition_mode(CompositionMode::Preedit));
+    assert_eq!(engine.composition_mode(), CompositionMode::Direct);
+
+    // Mode is remembered and applied when switching back
+    engine.set_input_method("telex_v2").unwrap();
+    assert_eq!(engine.composition_mode(), CompositionMode::Preedit);
+}
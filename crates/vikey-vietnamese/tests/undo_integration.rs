@@ -0,0 +1,169 @@
+// Integration tests for the engine-managed undo/redo history
+
+use vikey_core::{Action, CompositionMode, Engine, KeyBinding, KeyCode, KeyEvent, Modifiers};
+use vikey_vietnamese::VietnamesePlugin;
+
+fn create_engine(method: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method(method).unwrap();
+    engine
+}
+
+/// Apply an action to the simulated document
+fn apply(output: &mut String, action: Action) {
+    match action {
+        Action::Commit(text) => output.push_str(&text),
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            for _ in 0..backspace_count {
+                output.pop();
+            }
+            output.push_str(&text);
+        }
+        _ => {}
+    }
+}
+
+fn type_string(engine: &mut Engine, output: &mut String, input: &str) {
+    for c in input.chars() {
+        let action = engine.process(c);
+        apply(output, action);
+    }
+}
+
+fn ctrl(ch: char) -> KeyEvent {
+    KeyEvent::from_char(ch).with_modifiers(Modifiers::CONTROL)
+}
+
+#[test]
+fn test_undo_tone_then_circumflex() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "tonso");
+    assert_eq!(output, "tốn");
+
+    // Undo circumflex (last transformation)
+    apply(&mut output, engine.undo());
+    assert_eq!(output, "tón");
+
+    // Undo tone
+    apply(&mut output, engine.undo());
+    assert_eq!(output, "ton");
+
+    // Nothing left to undo: plain letters are kept
+    assert!(!engine.can_undo());
+    assert_eq!(engine.undo(), Action::PassThrough);
+    assert_eq!(output, "ton");
+}
+
+#[test]
+fn test_redo() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "tieesng");
+    assert_eq!(output, "tiếng");
+
+    apply(&mut output, engine.undo());
+    assert_eq!(output, "tiêng");
+    apply(&mut output, engine.undo());
+    assert_eq!(output, "tieng");
+
+    assert!(engine.can_redo());
+    apply(&mut output, engine.redo());
+    assert_eq!(output, "tiêng");
+    apply(&mut output, engine.redo());
+    assert_eq!(output, "tiếng");
+    assert!(!engine.can_redo());
+}
+
+#[test]
+fn test_typing_after_undo_continues_word() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "as");
+    assert_eq!(output, "á");
+
+    apply(&mut output, engine.undo());
+    assert_eq!(output, "a");
+
+    // A new key drops the redo history but keeps composing the word
+    type_string(&mut engine, &mut output, "f");
+    assert_eq!(output, "à");
+    assert!(!engine.can_redo());
+}
+
+#[test]
+fn test_undo_key() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "dd");
+    assert_eq!(output, "đ");
+
+    apply(&mut output, engine.process(ctrl('z')));
+    assert_eq!(output, "d");
+
+    apply(&mut output, engine.process(ctrl('y')));
+    assert_eq!(output, "đ");
+}
+
+#[test]
+fn test_undo_key_without_history_passes_through() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "ba");
+    assert_eq!(engine.process(ctrl('z')), Action::PassThrough);
+
+    // The chord ended the word
+    assert!(engine.buffer_content().is_empty());
+}
+
+#[test]
+fn test_configurable_undo_key() {
+    let mut engine = create_engine("telex_v2");
+    engine.set_undo_key(Some(KeyBinding::new(KeyCode::Function(2), Modifiers::NONE)));
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "os");
+    assert_eq!(engine.process(ctrl('z')), Action::PassThrough);
+
+    let mut output = String::new();
+    type_string(&mut engine, &mut output, "os");
+    apply(
+        &mut output,
+        engine.process(KeyEvent::new(KeyCode::Function(2), None)),
+    );
+    assert_eq!(output, "o");
+}
+
+#[test]
+fn test_history_ends_at_word_boundary() {
+    let mut engine = create_engine("telex_v2");
+    let mut output = String::new();
+
+    type_string(&mut engine, &mut output, "as ");
+    assert!(!engine.can_undo());
+}
+
+#[test]
+fn test_undo_in_preedit_mode() {
+    let mut engine = create_engine("telex_v2");
+    assert!(engine.set_composition_mode(CompositionMode::Preedit));
+
+    for c in "aws".chars() {
+        engine.process(c);
+    }
+
+    match engine.undo() {
+        Action::UpdatePreedit { text, .. } => assert_eq!(text, "ă"),
+        other => panic!("expected UpdatePreedit, got {:?}", other),
+    }
+}
@@ -0,0 +1,206 @@
+// Integration tests for VIQRMethod
+
+use vikey_core::{Action, Engine};
+use vikey_vietnamese::{Charset, VietnamesePlugin};
+
+fn create_engine_viqr() -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method("viqr").unwrap();
+    engine
+}
+
+/// Apply an action to the simulated document
+fn apply(output: &mut String, key: Option<char>, action: Action) {
+    match action {
+        Action::PassThrough => output.extend(key),
+        Action::Commit(text) => output.push_str(&text),
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            // Remove last N characters
+            for _ in 0..backspace_count {
+                output.pop();
+            }
+            output.push_str(&text);
+        }
+        _ => {}
+    }
+}
+
+fn process_string(engine: &mut Engine, input: &str) -> String {
+    let mut output = String::new();
+    for c in input.chars() {
+        let action = engine.process(c);
+        apply(&mut output, Some(c), action);
+    }
+    output
+}
+
+#[test]
+fn test_viqr_all_tones() {
+    let mut engine = create_engine_viqr();
+
+    let tests = vec![
+        ("a'", "á"), // sắc
+        ("a`", "à"), // huyền
+        ("a?", "ả"), // hỏi
+        ("a~", "ã"), // ngã
+        ("a.", "ạ"), // nặng
+    ];
+
+    for (input, expected) in tests {
+        engine.reset();
+        let result = process_string(&mut engine, input);
+        assert_eq!(result, expected, "Failed for input: {}", input);
+    }
+}
+
+#[test]
+fn test_viqr_marks() {
+    let mut engine = create_engine_viqr();
+
+    let tests = vec![
+        ("a^", "â"),
+        ("e^", "ê"),
+        ("o^", "ô"),
+        ("a(", "ă"),
+        ("o+", "ơ"),
+        ("u+", "ư"),
+        ("dd", "đ"),
+        ("DD", "Đ"),
+        ("tu+o+ng", "tương"),
+        ("tuo+ng'", "tương'"),
+        ("tu+o+'ng", "tướng"),
+        ("nguye^n", "nguyên"),
+    ];
+
+    for (input, expected) in tests {
+        engine.reset();
+        let result = process_string(&mut engine, input);
+        assert_eq!(result, expected, "Failed for input: {}", input);
+    }
+}
+
+#[test]
+fn test_viqr_word_building() {
+    let mut engine = create_engine_viqr();
+
+    // Build word character by character
+    for (input, expected) in [
+        ("v", "v"),
+        ("vi", "vi"),
+        ("vie", "vie"),
+        ("vie^", "viê"),
+        ("vie^.", "việ"),
+        ("vie^.t", "việt"),
+    ] {
+        engine.reset();
+        assert_eq!(process_string(&mut engine, input), expected);
+    }
+}
+
+#[test]
+fn test_viqr_sentence_punctuation() {
+    let mut engine = create_engine_viqr();
+
+    let tests = vec![
+        // After a consonant or a toned vowel, punctuation ends the word
+        ("Ha` No^.i.", "Hà Nội."),
+        ("Nghi?", "Nghỉ"),
+        ("Nghi?? ", "Nghi? "),
+        ("Vie^.t Nam. ", "Việt Nam. "),
+        ("Ba.n kho?e kho^ng? ", "Bạn khoẻ không? "),
+        ("Cha`o ba.n! ", "Chào bạn! "),
+        // English keeps its punctuation
+        ("ok? It's fine. ", "ok? It's fine. "),
+        ("(vi du.) ", "(vi dụ) "),
+        // A backslash types the next key as-is
+        ("Ddi\\. ", "Đi. "),
+        ("Ta\\? ", "Ta? "),
+        ("a\\^ ", "a^ "),
+        ("C:\\\\ ", "C:\\ "),
+        // The same mark twice takes it off
+        ("a'' ", "a' "),
+        ("a^^ ", "a^ "),
+        ("ddd ", "dd "),
+    ];
+
+    for (input, expected) in tests {
+        engine.reset();
+        let result = process_string(&mut engine, input);
+        assert_eq!(result, expected, "Failed for input: {}", input);
+    }
+}
+
+#[test]
+fn test_viqr_charset_conformance() {
+    // Text written in the VIQR charset types back the Unicode text
+    let text = "Tiếng Việt là ngôn ngữ của người Việt, được viết bằng chữ Quốc ngữ. \
+        Đây là một đoạn văn bản để thử: ai? đi đâu? Ừ, thôi.";
+    let mut engine = create_engine_viqr();
+    let typed = process_string(&mut engine, &format!("{} ", Charset::Viqr.encode(text)));
+    assert_eq!(typed, format!("{} ", text));
+}
+
+#[test]
+fn test_viqr_backspace() {
+    let mut engine = create_engine_viqr();
+    let mut output = process_string(&mut engine, "ddi\\");
+    assert_eq!(output, "đi\\");
+
+    // Backspace takes the pending escape back
+    let action = engine.process_backspace();
+    apply(&mut output, None, action);
+    assert_eq!(output, "đi");
+
+    let action = engine.process('.');
+    apply(&mut output, Some('.'), action);
+    assert_eq!(output, "đị");
+
+    let action = engine.process_backspace();
+    apply(&mut output, None, action);
+    assert_eq!(output, "đi");
+}
+
+#[test]
+fn test_viqr_undo() {
+    let mut engine = create_engine_viqr();
+    let mut output = process_string(&mut engine, "vie^.t");
+    assert_eq!(output, "việt");
+
+    // Marks are taken back latest first, letters stay
+    let action = engine.undo();
+    apply(&mut output, None, action);
+    assert_eq!(output, "viêt");
+
+    let action = engine.undo();
+    apply(&mut output, None, action);
+    assert_eq!(output, "viet");
+
+    let action = engine.redo();
+    apply(&mut output, None, action);
+    assert_eq!(output, "viêt");
+}
+
+#[test]
+fn test_viqr_preedit_mode() {
+    use vikey_core::CompositionMode;
+
+    let mut engine = create_engine_viqr();
+    assert!(engine.set_composition_mode(CompositionMode::Preedit));
+
+    let mut preedit = String::new();
+    for c in "vie^.t".chars() {
+        match engine.process(c) {
+            Action::UpdatePreedit { text, .. } => preedit = text,
+            other => panic!("Expected preedit update, got {:?}", other),
+        }
+    }
+    assert_eq!(preedit, "việt");
+
+    // Punctuation that cannot mark the word commits it
+    assert_eq!(engine.process('.'), Action::CommitPreedit);
+}
@@ -0,0 +1,254 @@
+// Integration tests for VNIMethod
+
+use vikey_core::{Action, Engine};
+use vikey_vietnamese::VietnamesePlugin;
+
+fn create_engine_vni() -> Engine {
+    let mut engine = Engine::new();
+    engine.register(Box::new(VietnamesePlugin::new())).unwrap();
+    engine.set_language("vietnamese").unwrap();
+    engine.set_input_method("vni").unwrap();
+    engine
+}
+
+/// Apply an action to the simulated document
+fn apply(output: &mut String, key: Option<char>, action: Action) {
+    match action {
+        Action::PassThrough => output.extend(key),
+        Action::Commit(text) => output.push_str(&text),
+        Action::Replace {
+            backspace_count,
+            text,
+        } => {
+            // Remove last N characters
+            for _ in 0..backspace_count {
+                output.pop();
+            }
+            output.push_str(&text);
+        }
+        _ => {}
+    }
+}
+
+fn process_string(engine: &mut Engine, input: &str) -> String {
+    let mut output = String::new();
+    for c in input.chars() {
+        let action = engine.process(c);
+        apply(&mut output, Some(c), action);
+    }
+    output
+}
+
+#[test]
+fn test_vni_simple_word() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "hoa"), "hoa");
+}
+
+#[test]
+fn test_vni_tone_mark() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "hoa1"), "hoá");
+}
+
+#[test]
+fn test_vni_circumflex() {
+    let mut engine = create_engine_vni();
+    for (input, expected) in [("a6", "â"), ("e6", "ê"), ("o6", "ô")] {
+        engine.reset();
+        assert_eq!(process_string(&mut engine, input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_vni_breve() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "a8"), "ă");
+}
+
+#[test]
+fn test_vni_horn_o() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "o7"), "ơ");
+}
+
+#[test]
+fn test_vni_horn_u() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "u7"), "ư");
+}
+
+#[test]
+fn test_vni_d_stroke() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "d9"), "đ");
+}
+
+#[test]
+fn test_vni_complex_word() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "tuong7"), "tương");
+}
+
+#[test]
+fn test_vni_complex_with_tone() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "tuong71"), "tướng");
+}
+
+#[test]
+fn test_vni_all_tones() {
+    let mut engine = create_engine_vni();
+
+    let tests = vec![
+        ("a1", "á"), // sắc
+        ("a2", "à"), // huyền
+        ("a3", "ả"), // hỏi
+        ("a4", "ã"), // ngã
+        ("a5", "ạ"), // nặng
+    ];
+
+    for (input, expected) in tests {
+        engine.reset();
+        let result = process_string(&mut engine, input);
+        assert_eq!(result, expected, "Failed for input: {}", input);
+    }
+}
+
+#[test]
+fn test_vni_tone_removal() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "toan20"), "toan");
+    engine.reset();
+    assert_eq!(process_string(&mut engine, "toan201"), "toán");
+}
+
+#[test]
+fn test_vni_word_building() {
+    let mut engine = create_engine_vni();
+
+    // Build word character by character
+    assert_eq!(process_string(&mut engine, "t"), "t");
+    engine.reset();
+
+    assert_eq!(process_string(&mut engine, "tu"), "tu");
+    engine.reset();
+
+    assert_eq!(process_string(&mut engine, "tuo"), "tuo");
+    engine.reset();
+
+    assert_eq!(process_string(&mut engine, "tuo7"), "tươ"); // uo7→ươ
+    engine.reset();
+
+    assert_eq!(process_string(&mut engine, "tuo7n"), "tươn");
+    engine.reset();
+
+    assert_eq!(process_string(&mut engine, "tuo7ng"), "tương");
+    engine.reset();
+
+    assert_eq!(process_string(&mut engine, "tuo7ng1"), "tướng"); // tone
+}
+
+#[test]
+fn test_vni_free_order() {
+    let mut engine = create_engine_vni();
+    for input in ["vie65t ", "viet65 ", "vie6t5 ", "vie56t "] {
+        engine.reset();
+        assert_eq!(process_string(&mut engine, input), "việt ", "{}", input);
+    }
+
+    engine.reset();
+    assert_eq!(
+        process_string(&mut engine, "Tie61ng Vie6t5 "),
+        "Tiếng Việt "
+    );
+}
+
+#[test]
+fn test_vni_double_key_escape() {
+    let mut engine = create_engine_vni();
+
+    let tests = vec![
+        ("a11 ", "a1 "),
+        ("a66 ", "a6 "),
+        ("a88 ", "a8 "),
+        ("u77 ", "u7 "),
+        ("d99 ", "d9 "),
+        ("vie66t ", "vie6t "),
+    ];
+
+    for (input, expected) in tests {
+        engine.reset();
+        let result = process_string(&mut engine, input);
+        assert_eq!(result, expected, "Failed for input: {}", input);
+    }
+}
+
+#[test]
+fn test_vni_numbers_stay_literal() {
+    let mut engine = create_engine_vni();
+    assert_eq!(process_string(&mut engine, "2024 b6 "), "2024 b6 ");
+}
+
+#[test]
+fn test_vni_backspace() {
+    let mut engine = create_engine_vni();
+    let mut output = process_string(&mut engine, "vie6t5");
+    assert_eq!(output, "việt");
+
+    // Backspace removes the last key, not the last letter: the tone goes
+    let action = engine.process_backspace();
+    apply(&mut output, None, action);
+    assert_eq!(output, "viêt");
+
+    let action = engine.process_backspace();
+    apply(&mut output, None, action);
+    assert_eq!(output, "viê");
+
+    for c in "n1".chars() {
+        let action = engine.process(c);
+        apply(&mut output, Some(c), action);
+    }
+    assert_eq!(output, "viến");
+}
+
+#[test]
+fn test_vni_undo() {
+    let mut engine = create_engine_vni();
+    let mut output = process_string(&mut engine, "d9a1");
+    assert_eq!(output, "đá");
+
+    // Marks are taken back latest first, letters stay
+    let action = engine.undo();
+    apply(&mut output, None, action);
+    assert_eq!(output, "đa");
+
+    let action = engine.undo();
+    apply(&mut output, None, action);
+    assert_eq!(output, "da");
+
+    let action = engine.redo();
+    apply(&mut output, None, action);
+    assert_eq!(output, "đa");
+}
+
+#[test]
+fn test_vni_preedit_mode() {
+    use vikey_core::CompositionMode;
+
+    let mut engine = create_engine_vni();
+    assert!(engine.set_composition_mode(CompositionMode::Preedit));
+    assert_eq!(engine.composition_mode(), CompositionMode::Preedit);
+
+    let mut preedit = String::new();
+    for c in "vie6t5".chars() {
+        match engine.process(c) {
+            Action::UpdatePreedit { text, .. } => preedit = text,
+            other => panic!("Expected preedit update, got {:?}", other),
+        }
+    }
+    assert_eq!(preedit, "việt");
+
+    // Space commits the composition on the word boundary
+    assert_eq!(engine.process(' '), Action::CommitPreedit);
+}
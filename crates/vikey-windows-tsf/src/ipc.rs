@@ -7,17 +7,88 @@ use std::io::{Read, Write};
 use std::fs::OpenOptions;
 use std::time::Duration;
 
+/// Physical key (mirror of `vikey_core::KeyCode`, same variant order for bincode)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCode {
+    Char(char),
+    Space,
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Escape,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Function(u8),
+    Modifier,
+    Other(u32),
+}
+
+/// Modifier bit set (mirror of `vikey_core::Modifiers`)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers(pub u8);
+
+impl Modifiers {
+    pub const SHIFT: u8 = 1 << 0;
+    pub const CONTROL: u8 = 1 << 1;
+    pub const ALT: u8 = 1 << 2;
+    pub const META: u8 = 1 << 3;
+    pub const CAPS_LOCK: u8 = 1 << 4;
+}
+
+/// Press or release (mirror of `vikey_core::KeyState`)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyState {
+    Pressed,
+    Released,
+}
+
+/// Keystroke sent to broker (mirror of `vikey_core::KeyEvent`)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub text: Option<char>,
+    pub modifiers: Modifiers,
+    pub state: KeyState,
+}
+
 /// IPC Request from TSF DLL to broker
 #[derive(Serialize, Deserialize, Debug)]
 pub enum IpcRequest {
     /// Process a keystroke
-    ProcessKey(char),
+    ProcessKey(KeyEvent),
     /// Process backspace
     ProcessBackspace,
     /// Reset engine state
     Reset,
     /// Ping to check if broker is alive
     Ping,
+    /// A text field gained focus: route following keys to its own session
+    FocusContext(u64),
+    /// A text field was closed: drop its session
+    DestroyContext(u64),
+    /// The text service gained keyboard focus
+    FocusIn,
+    /// The text service lost keyboard focus
+    FocusOut,
+    /// The caret was moved by the application
+    CursorMoved,
+    /// The user clicked into the text
+    MouseClick,
+    /// Text before the caret, so a committed word can be edited again
+    SurroundingText(String),
+    /// Output charset ID (e.g. "tcvn3") for the focused text field only;
+    /// `None` goes back to the configured one
+    SetCharset(Option<String>),
+    /// Is a word being composed in the focused text field?
+    IsComposing,
 }
 
 /// IPC Response from broker to TSF DLL
@@ -29,15 +100,23 @@ pub enum IpcResponse {
     Pong,
     /// Error occurred
     Error(String),
+    /// Answer to `IsComposing`
+    Composing(bool),
 }
 
 /// Action to execute in the application
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Action {
-    /// Do nothing, pass through
+    /// Key was consumed, nothing to change in the text
     DoNothing,
+    /// Key was not handled; deliver it to the application unchanged
+    PassThrough,
     /// Replace text: delete N chars, insert string
     Replace { delete: usize, insert: String },
+    /// Show/update the composition string (preedit mode)
+    UpdatePreedit { text: String, cursor: usize },
+    /// Commit the composition string, then pass the key through
+    CommitPreedit,
 }
 
 /// IPC Client for Named Pipe communication
@@ -162,7 +241,7 @@ impl IpcClient {
     }
     
     /// Process a keystroke
-    pub fn process_key(&mut self, ch: char) -> Result<Action, String> {
+    pub fn process_key(&mut self, key: KeyEvent) -> Result<Action, String> {
         #[cfg(debug_assertions)]
         {
             use std::io::Write;
@@ -173,11 +252,11 @@ impl IpcClient {
                 .open(log_path)
             {
                 let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
-                let _ = writeln!(file, "[{}] IpcClient::process_key('{}')", timestamp, ch);
+                let _ = writeln!(file, "[{}] IpcClient::process_key({:?})", timestamp, key);
             }
         }
         
-        let request = IpcRequest::ProcessKey(ch);
+        let request = IpcRequest::ProcessKey(key);
         
         match self.send_request(&request) {
             Ok(IpcResponse::Action(action)) => {
@@ -227,6 +306,68 @@ impl IpcClient {
         }
     }
     
+    /// Switch the broker to the session of a focused text field
+    pub fn focus_context(&mut self, id: u64) -> Result<(), String> {
+        let request = IpcRequest::FocusContext(id);
+        
+        match self.send_request(&request) {
+            Ok(IpcResponse::Action(_)) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err),
+            Ok(_) => Err("Unexpected response".to_string()),
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Drop the broker session of a closed text field
+    pub fn destroy_context(&mut self, id: u64) -> Result<(), String> {
+        let request = IpcRequest::DestroyContext(id);
+        
+        match self.send_request(&request) {
+            Ok(IpcResponse::Action(_)) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err),
+            Ok(_) => Err("Unexpected response".to_string()),
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Choose the output charset of the focused text field (`None`: the
+    /// configured one)
+    pub fn set_charset(&mut self, charset: Option<&str>) -> Result<(), String> {
+        let request = IpcRequest::SetCharset(charset.map(str::to_string));
+        
+        match self.send_request(&request) {
+            Ok(IpcResponse::Action(_)) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err),
+            Ok(_) => Err("Unexpected response".to_string()),
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Is a word being composed in the focused text field?
+    pub fn is_composing(&mut self) -> Result<bool, String> {
+        let request = IpcRequest::IsComposing;
+        
+        match self.send_request(&request) {
+            Ok(IpcResponse::Composing(composing)) => Ok(composing),
+            Ok(IpcResponse::Error(err)) => Err(err),
+            Ok(_) => Err("Unexpected response".to_string()),
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Notify the broker of a focus, caret or mouse event
+    ///
+    /// `request` must be one of `FocusIn`, `FocusOut`, `CursorMoved`,
+    /// `MouseClick` or `SurroundingText`.
+    pub fn notify(&mut self, request: IpcRequest) -> Result<Action, String> {
+        match self.send_request(&request) {
+            Ok(IpcResponse::Action(action)) => Ok(action),
+            Ok(IpcResponse::Error(err)) => Err(err),
+            Ok(_) => Err("Unexpected response".to_string()),
+            Err(e) => Err(e),
+        }
+    }
+    
     /// Check if connected
     pub fn is_connected(&self) -> bool {
         self.connected
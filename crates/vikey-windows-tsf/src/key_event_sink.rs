@@ -1,19 +1,27 @@
 //! Key Event Sink
 //!
-//! Implements ITfKeyEventSink to intercept and process keystrokes
+//! Implements ITfKeyEventSink to intercept and process keystrokes, and
+//! ITfThreadMgrEventSink to tell the broker which document has the focus
 
 use windows::core::*;
 use windows::Win32::UI::TextServices::*;
 use windows::Win32::Foundation::*;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetKeyState, GetKeyboardState, MapVirtualKeyW, ToUnicode, MAPVK_VK_TO_CHAR, MAPVK_VK_TO_VSC,
+};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::sync::Mutex;
-use crate::ipc::{IpcClient, Action};
+use crate::ipc::{IpcClient, IpcRequest, Action, KeyCode, KeyEvent, KeyState, Modifiers};
 use crate::text_ops;
 
 /// Key Event Sink for Vikey
-#[implement(ITfKeyEventSink)]
+#[implement(ITfKeyEventSink, ITfThreadMgrEventSink)]
 pub struct KeyEventSink {
     ipc_client: Mutex<IpcClient>,
     client_id: Mutex<u32>,
+    /// Broker context of the focused document
+    focused_context: Mutex<Option<u64>>,
 }
 
 impl KeyEventSink {
@@ -39,6 +47,7 @@ impl KeyEventSink {
         Self {
             ipc_client: Mutex::new(client),
             client_id: Mutex::new(0),
+            focused_context: Mutex::new(None),
         }
     }
     
@@ -46,6 +55,79 @@ impl KeyEventSink {
     pub fn set_client_id(&self, client_id: u32) {
         *self.client_id.lock().unwrap() = client_id;
     }
+    
+    /// Connect to the broker if needed; a new connection is told the
+    /// focused document first, so keys reach its session
+    fn ensure_connected(&self, client: &mut IpcClient) -> bool {
+        if !client.is_connected() && client.connect().is_ok() {
+            if let Some(id) = *self.focused_context.lock().unwrap() {
+                let _ = client.focus_context(id);
+            }
+        }
+        client.is_connected()
+    }
+}
+
+/// Broker context ID of a document
+///
+/// Built from the identity of the document manager and the process ID,
+/// since the applications of every process share one broker.
+fn context_id(document: &ITfDocumentMgr) -> Option<u64> {
+    let identity: IUnknown = document.cast().ok()?;
+    let mut hasher = DefaultHasher::new();
+    (std::process::id(), identity.as_raw() as usize).hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+impl ITfThreadMgrEventSink_Impl for KeyEventSink {
+    fn OnInitDocumentMgr(&self, _pdim: Option<&ITfDocumentMgr>) -> Result<()> {
+        Ok(())
+    }
+    
+    fn OnUninitDocumentMgr(&self, pdim: Option<&ITfDocumentMgr>) -> Result<()> {
+        // The text field is gone: drop its session in the broker
+        let Some(id) = pdim.and_then(context_id) else {
+            return Ok(());
+        };
+        let mut focused = self.focused_context.lock().unwrap();
+        if *focused == Some(id) {
+            *focused = None;
+        }
+        drop(focused);
+        
+        let mut client = self.ipc_client.lock().unwrap();
+        if client.is_connected() {
+            let _ = client.destroy_context(id);
+        }
+        Ok(())
+    }
+    
+    fn OnSetFocus(
+        &self,
+        pdimfocus: Option<&ITfDocumentMgr>,
+        _pdimprevfocus: Option<&ITfDocumentMgr>,
+    ) -> Result<()> {
+        // Keys now go to the session of the focused document; focus moving
+        // to a window without a document keeps the last one
+        let Some(id) = pdimfocus.and_then(context_id) else {
+            return Ok(());
+        };
+        *self.focused_context.lock().unwrap() = Some(id);
+        
+        let mut client = self.ipc_client.lock().unwrap();
+        if self.ensure_connected(&mut client) {
+            let _ = client.focus_context(id);
+        }
+        Ok(())
+    }
+    
+    fn OnPushContext(&self, _pic: Option<&ITfContext>) -> Result<()> {
+        Ok(())
+    }
+    
+    fn OnPopContext(&self, _pic: Option<&ITfContext>) -> Result<()> {
+        Ok(())
+    }
 }
 
 impl ITfKeyEventSink_Impl for KeyEventSink {
@@ -64,6 +146,24 @@ impl ITfKeyEventSink_Impl for KeyEventSink {
             }
         }
         
+        // Let the broker end the composition of the field we are leaving
+        let mut client = self.ipc_client.lock().unwrap();
+        if client.is_connected() {
+            let request = if fforeground.as_bool() {
+                // Another application may have used the broker meanwhile:
+                // switch back to the session of our focused document
+                if let Some(id) = *self.focused_context.lock().unwrap() {
+                    let _ = client.focus_context(id);
+                }
+                IpcRequest::FocusIn
+            } else {
+                IpcRequest::FocusOut
+            };
+            // TODO: Commit/clear the ITfComposition once preedit is rendered;
+            // in direct mode the broker only ends the word
+            let _ = client.notify(request);
+        }
+        
         Ok(())
     }
     
@@ -90,12 +190,29 @@ impl ITfKeyEventSink_Impl for KeyEventSink {
             }
         }
         
-        // Check if this is a printable character
-        if is_printable_key(vkey) {
+        let key = build_key_event(vkey, lparam, KeyState::Pressed);
+        let mut client = self.ipc_client.lock().unwrap();
+        
+        // Keys that type text go to the engine. So does Backspace while a
+        // word is being composed: the input method may retype the word
+        // instead of deleting one character
+        let ours = if is_chord(&key) {
+            false
+        } else if key.code == KeyCode::Backspace {
+            client.is_connected() && client.is_composing().unwrap_or(false)
+        } else {
+            types_text(&key)
+        };
+        
+        if ours {
             // We want to handle this key
             Ok(BOOL(1))
         } else {
-            // Pass through
+            // Not ours: let the broker reset its composition (chords, arrows, Esc...)
+            // and pass the key through to the application
+            if client.is_connected() {
+                let _ = client.process_key(key);
+            }
             Ok(BOOL(0))
         }
     }
@@ -107,7 +224,7 @@ impl ITfKeyEventSink_Impl for KeyEventSink {
         lparam: LPARAM,
     ) -> Result<BOOL> {
         let vkey = wparam.0 as u32;
-        let ch = vkey_to_char(vkey);
+        let key = build_key_event(vkey, lparam, KeyState::Pressed);
         
         #[cfg(debug_assertions)]
         {
@@ -119,21 +236,16 @@ impl ITfKeyEventSink_Impl for KeyEventSink {
                 .open(log_path)
             {
                 let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
-                let _ = writeln!(file, "[{}] OnKeyDown(vkey=0x{:X}, char='{}', lparam=0x{:X})", 
-                    timestamp, vkey, ch, lparam.0);
+                let _ = writeln!(file, "[{}] OnKeyDown(vkey=0x{:X}, key={:?}, lparam=0x{:X})", 
+                    timestamp, vkey, key, lparam.0);
             }
         }
         
         // Try to process with IPC
         let mut client = self.ipc_client.lock().unwrap();
         
-        // If not connected, try to reconnect
-        if !client.is_connected() {
-            let _ = client.connect();
-        }
-        
-        // If still not connected, pass through
-        if !client.is_connected() {
+        // If not connected, try to reconnect; if that fails, pass through
+        if !self.ensure_connected(&mut client) {
             #[cfg(debug_assertions)]
             {
                 use std::io::Write;
@@ -151,7 +263,7 @@ impl ITfKeyEventSink_Impl for KeyEventSink {
         }
         
         // Process keystroke via IPC
-        match client.process_key(ch) {
+        match client.process_key(key) {
             Ok(Action::Replace { delete, insert }) => {
                 #[cfg(debug_assertions)]
                 {
@@ -218,7 +330,16 @@ impl ITfKeyEventSink_Impl for KeyEventSink {
                 }
             }
             Ok(Action::DoNothing) => {
-                // Pass through
+                // Consumed by the engine, nothing to insert
+                Ok(BOOL(1))
+            }
+            Ok(Action::PassThrough) => {
+                // Not handled by the engine, let the application get the key
+                Ok(BOOL(0))
+            }
+            Ok(Action::UpdatePreedit { .. }) | Ok(Action::CommitPreedit) => {
+                // TODO: Render composition with ITfComposition; the broker runs
+                // the engine in direct mode for now, so just pass through
                 Ok(BOOL(0))
             }
             Err(e) => {
@@ -303,31 +424,131 @@ impl ITfKeyEventSink_Impl for KeyEventSink {
     }
 }
 
-/// Check if a virtual key is a printable character
-fn is_printable_key(vkey: u32) -> bool {
-    match vkey {
-        // A-Z
-        0x41..=0x5A => true,
-        // 0-9
-        0x30..=0x39 => true,
-        // Space
-        0x20 => true,
-        // OEM keys (punctuation, etc.)
-        0xBA..=0xC0 => true,
-        0xDB..=0xDF => true,
-        _ => false,
+/// Does the key type a character the engine composes with?
+///
+/// Letters, digits, punctuation and Space, when the keyboard layout gives
+/// them a character; Enter, Tab and the numeric keypad stay with the
+/// application.
+fn types_text(key: &KeyEvent) -> bool {
+    key.text.is_some() && matches!(key.code, KeyCode::Char(_) | KeyCode::Space)
+}
+
+/// Check if a key event is a shortcut chord (Ctrl/Alt/Win + key)
+fn is_chord(key: &KeyEvent) -> bool {
+    key.modifiers.0 & (Modifiers::CONTROL | Modifiers::ALT | Modifiers::META) != 0
+}
+
+/// Is the virtual key currently held down (or toggled, for Caps Lock)?
+fn key_down(vkey: i32) -> bool {
+    unsafe { (GetKeyState(vkey) as u16 & 0x8000) != 0 }
+}
+
+fn key_toggled(vkey: i32) -> bool {
+    unsafe { (GetKeyState(vkey) & 0x0001) != 0 }
+}
+
+/// Read the current modifier state
+fn current_modifiers() -> Modifiers {
+    const VK_SHIFT: i32 = 0x10;
+    const VK_CONTROL: i32 = 0x11;
+    const VK_MENU: i32 = 0x12;
+    const VK_CAPITAL: i32 = 0x14;
+    const VK_LWIN: i32 = 0x5B;
+    const VK_RWIN: i32 = 0x5C;
+    
+    let mut bits = 0u8;
+    if key_down(VK_SHIFT) {
+        bits |= Modifiers::SHIFT;
+    }
+    if key_down(VK_CONTROL) {
+        bits |= Modifiers::CONTROL;
+    }
+    if key_down(VK_MENU) {
+        bits |= Modifiers::ALT;
+    }
+    if key_down(VK_LWIN) || key_down(VK_RWIN) {
+        bits |= Modifiers::META;
+    }
+    if key_toggled(VK_CAPITAL) {
+        bits |= Modifiers::CAPS_LOCK;
     }
+    Modifiers(bits)
 }
 
-/// Convert virtual key to character
-fn vkey_to_char(vkey: u32) -> char {
-    match vkey {
-        // A-Z
-        0x41..=0x5A => ((vkey - 0x41) as u8 + b'a') as char,
-        // 0-9
-        0x30..=0x39 => ((vkey - 0x30) as u8 + b'0') as char,
-        // Space
-        0x20 => ' ',
-        _ => '?',
+/// Character the key types with the current layout and key state
+///
+/// Read with `ToUnicode`, so Shift, Caps Lock and AltGr apply as they will
+/// for the application ("Shift+1" is "!" on a US layout). Dead keys and
+/// control characters (Ctrl+letter) give `None`.
+fn layout_text(vkey: u32, scan_code: u32) -> Option<char> {
+    // Leave the dead-key state of the layout alone: the key is read again
+    // in OnKeyDown and by the application
+    const DONT_CHANGE_KEYBOARD_STATE: u32 = 0x4;
+    
+    let mut key_state = [0u8; 256];
+    unsafe { GetKeyboardState(&mut key_state) }.ok()?;
+    let mut buffer = [0u16; 8];
+    let len = unsafe {
+        ToUnicode(vkey, scan_code, Some(&key_state), &mut buffer, DONT_CHANGE_KEYBOARD_STATE)
+    };
+    if len != 1 {
+        return None;
     }
+    char::from_u32(buffer[0] as u32).filter(|c| !c.is_control())
+}
+
+/// Character of a punctuation key without Shift on the current layout
+fn layout_key_char(vkey: u32) -> Option<char> {
+    // The top bit marks a dead key
+    let mapped = unsafe { MapVirtualKeyW(vkey, MAPVK_VK_TO_CHAR) } & 0x7FFF_FFFF;
+    char::from_u32(mapped).filter(|&c| c != '\0' && !c.is_control())
+}
+
+/// Convert virtual key + current keyboard state to a key event
+fn build_key_event(vkey: u32, lparam: LPARAM, state: KeyState) -> KeyEvent {
+    let modifiers = current_modifiers();
+    let scan_code = match ((lparam.0 >> 16) & 0xFF) as u32 {
+        0 => unsafe { MapVirtualKeyW(vkey, MAPVK_VK_TO_VSC) },
+        scan_code => scan_code,
+    };
+    
+    let (code, text) = match vkey {
+        // A-Z
+        0x41..=0x5A => {
+            let lower = ((vkey - 0x41) as u8 + b'a') as char;
+            (KeyCode::Char(lower), layout_text(vkey, scan_code))
+        }
+        // 0-9: the layout decides what Shift gives ("!", or "1" on AZERTY)
+        0x30..=0x39 => {
+            let digit = ((vkey - 0x30) as u8 + b'0') as char;
+            (KeyCode::Char(digit), layout_text(vkey, scan_code))
+        }
+        // OEM keys (punctuation, etc.)
+        0xBA..=0xC0 | 0xDB..=0xDF | 0xE2 => match layout_key_char(vkey) {
+            Some(c) => (KeyCode::Char(c.to_ascii_lowercase()), layout_text(vkey, scan_code)),
+            None => (KeyCode::Other(vkey), None),
+        },
+        0x20 => (KeyCode::Space, Some(' ')),
+        0x0D => (KeyCode::Enter, Some('\n')),
+        0x09 => (KeyCode::Tab, Some('\t')),
+        0x08 => (KeyCode::Backspace, None),
+        0x2E => (KeyCode::Delete, None),
+        0x1B => (KeyCode::Escape, None),
+        0x25 => (KeyCode::Left, None),
+        0x26 => (KeyCode::Up, None),
+        0x27 => (KeyCode::Right, None),
+        0x28 => (KeyCode::Down, None),
+        0x24 => (KeyCode::Home, None),
+        0x23 => (KeyCode::End, None),
+        0x21 => (KeyCode::PageUp, None),
+        0x22 => (KeyCode::PageDown, None),
+        0x2D => (KeyCode::Insert, None),
+        // F1-F24
+        0x70..=0x87 => (KeyCode::Function((vkey - 0x70 + 1) as u8), None),
+        // Shift, Ctrl, Alt, Caps Lock, Win
+        0x10..=0x12 | 0x14 | 0x5B | 0x5C | 0xA0..=0xA5 => (KeyCode::Modifier, None),
+        _ => (KeyCode::Other(vkey), None),
+    };
+    
+    KeyEvent { code, text, modifiers, state }
 }
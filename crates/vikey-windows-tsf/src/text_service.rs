@@ -25,6 +25,9 @@ pub struct TextService {
     // Key event sink
     key_event_sink: Mutex<Option<ITfKeyEventSink>>,
     key_event_sink_cookie: Mutex<u32>,
+    
+    // The same sink follows the focused document (ITfThreadMgrEventSink)
+    thread_mgr_sink_cookie: Mutex<u32>,
 }
 
 impl TextService {
@@ -52,6 +55,7 @@ impl TextService {
             activated: Mutex::new(false),
             key_event_sink: Mutex::new(None),
             key_event_sink_cookie: Mutex::new(0),
+            thread_mgr_sink_cookie: Mutex::new(0),
         }
     }
 }
@@ -225,6 +229,16 @@ impl TextService {
         let mut cookie: u32 = 0;
         keystroke_mgr.AdviseKeyEventSink(tid, &sink, TRUE)?;
         
+        // Follow the focused document, so the broker keeps one session per
+        // text field
+        let source: ITfSource = thread_mgr.cast()?;
+        let thread_mgr_cookie = source.AdviseSink(&ITfThreadMgrEventSink::IID, &sink)?;
+        *self.thread_mgr_sink_cookie.lock().unwrap() = thread_mgr_cookie;
+        if let Ok(document) = thread_mgr.GetFocus() {
+            let thread_mgr_sink: ITfThreadMgrEventSink = sink.cast()?;
+            let _ = thread_mgr_sink.OnSetFocus(&document, None);
+        }
+        
         // Store sink and cookie
         *self.key_event_sink.lock().unwrap() = Some(sink);
         *self.key_event_sink_cookie.lock().unwrap() = cookie;
@@ -236,6 +250,15 @@ impl TextService {
     unsafe fn unregister_key_event_sink(&self) -> Result<()> {
         let cookie = *self.key_event_sink_cookie.lock().unwrap();
         
+        let thread_mgr_cookie = *self.thread_mgr_sink_cookie.lock().unwrap();
+        if thread_mgr_cookie != 0 {
+            if let Some(thread_mgr) = self.thread_mgr.lock().unwrap().as_ref() {
+                let source: ITfSource = thread_mgr.cast()?;
+                source.UnadviseSink(thread_mgr_cookie)?;
+            }
+            *self.thread_mgr_sink_cookie.lock().unwrap() = 0;
+        }
+        
         if cookie != 0 {
             if let Some(thread_mgr) = self.thread_mgr.lock().unwrap().as_ref() {
                 let keystroke_mgr: ITfKeystrokeMgr = thread_mgr.cast()?;